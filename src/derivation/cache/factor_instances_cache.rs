@@ -0,0 +1,750 @@
+use crate::prelude::*;
+
+/// A cache of pre-derived transaction signing factor instances, so that
+/// creating accounts and personas, or securifying them, does not require
+/// prompting the user for factor sources such as Ledger every time.
+///
+/// The cache is filled with `instances_per_key` instances for every
+/// `FactorInstancesCacheKey` of some factor sources in a single
+/// `KeysCollector` session, each factor source thus being prompted once.
+/// Instances are then taken from the cache, and only when a key has run
+/// out is a real derivation made, of the missing instances only.
+///
+/// Taking never waits for a refill. All methods take `&self` so that the
+/// cache can be shared in an `Arc`, allowing the host to `refill` it off
+/// the critical path, e.g. in a spawned task after an instance has been
+/// taken.
+#[derive(Debug)]
+pub struct FactorInstancesCache {
+    /// The number of instances to pre-derive per key.
+    instances_per_key: usize,
+
+    /// Mutable internal state of the cache, behind a lock so that the cache
+    /// can be used concurrently.
+    state: RwLock<FactorInstancesCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct FactorInstancesCacheState {
+    /// The cached instances per key, in increasing index order.
+    instances: HashMap<FactorInstancesCacheKey, IndexSet<HierarchicalDeterministicFactorInstance>>,
+
+    /// The index after the last index derived or being derived per key, so
+    /// that indices of instances which have been taken from the cache, but
+    /// which are not yet used by any entity in the profile, are not reused.
+    next_index: HashMap<FactorInstancesCacheKey, HDPathValue>,
+
+    /// The number of instances being derived per key, so that concurrent
+    /// refills do not overfill the cache.
+    pending: HashMap<FactorInstancesCacheKey, usize>,
+}
+
+impl FactorInstancesCacheState {
+    /// Caches `instance`, keeping the instances of its key in increasing
+    /// index order.
+    fn insert(&mut self, instance: HierarchicalDeterministicFactorInstance) {
+        let instances = self
+            .instances
+            .entry(FactorInstancesCacheKey::of(&instance))
+            .or_default();
+        instances.insert(instance);
+        instances.sort_by_key(|f| f.derivation_path().index);
+    }
+}
+
+impl FactorInstancesCache {
+    /// The default number of instances pre-derived per key.
+    pub const DEFAULT_INSTANCES_PER_KEY: usize = 30;
+
+    pub fn new(instances_per_key: usize) -> Self {
+        Self {
+            instances_per_key,
+            state: RwLock::new(FactorInstancesCacheState::default()),
+        }
+    }
+
+    /// The number of instances cached for `key`.
+    pub fn cached_count(&self, key: &FactorInstancesCacheKey) -> usize {
+        self.state
+            .read()
+            .unwrap()
+            .instances
+            .get(key)
+            .map_or(0, |instances| instances.len())
+    }
+
+    /// Removes and returns the instance with the lowest index cached for
+    /// `key`, if any.
+    pub fn take(
+        &self,
+        key: &FactorInstancesCacheKey,
+    ) -> Option<HierarchicalDeterministicFactorInstance> {
+        self.state
+            .write()
+            .unwrap()
+            .instances
+            .get_mut(key)
+            .and_then(|instances| instances.shift_remove_index(0))
+    }
+
+    /// Derives, in a single `KeysCollector` session, the instances missing
+    /// for every key of each of `factor_sources` on `network_id` to hold
+    /// `instances_per_key` instances, at indices reserved with
+    /// `used_derivation_indices`.
+    ///
+    /// Instances being derived by a concurrent refill count as cached, so
+    /// this returns `Ok(())` without deriving anything if concurrent
+    /// refills already derive all missing instances.
+    ///
+    /// Instances derived by factor sources which did not fail are cached
+    /// even if others failed, in which case
+    /// `Err(CommonError::FailedToDeriveFactorInstances)` is returned.
+    pub async fn refill(
+        &self,
+        factor_sources: &IndexSet<HDFactorSource>,
+        network_id: NetworkID,
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<()> {
+        let keys = factor_sources
+            .iter()
+            .flat_map(|f| FactorInstancesCacheKey::all_of(f.factor_source_id(), network_id));
+        let mut reservation = self.reserve(
+            keys,
+            |state, key| {
+                let cached = state.instances.get(key).map_or(0, |i| i.len());
+                let pending = state.pending.get(key).copied().unwrap_or(0);
+                self.instances_per_key.saturating_sub(cached + pending)
+            },
+            true,
+            used_derivation_indices,
+        )?;
+        if reservation.indices.is_empty() {
+            return Ok(());
+        }
+
+        reservation.derive(factor_sources, interactors).await;
+        if reservation.indices.is_empty() {
+            Ok(())
+        } else {
+            Err(CommonError::FailedToDeriveFactorInstances)
+        }
+    }
+
+    /// Takes an instance for `key` from the cache, or if the cache has run
+    /// out for `key`, derives one with `factor_source` without refilling.
+    ///
+    /// Returns `Err(CommonError::UnknownFactorSource)` if `factor_source` is
+    /// not the factor source of `key`.
+    pub async fn take_or_derive(
+        &self,
+        key: FactorInstancesCacheKey,
+        factor_source: &HDFactorSource,
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<HierarchicalDeterministicFactorInstance> {
+        if factor_source.factor_source_id() != key.factor_source_id {
+            return Err(CommonError::UnknownFactorSource);
+        }
+        self.take_many_or_derive(
+            IndexSet::just(factor_source.clone()),
+            key.network_id,
+            key.entity_kind,
+            key.key_space,
+            used_derivation_indices,
+            interactors,
+        )
+        .await
+        .map(|instances| instances[&key.factor_source_id].clone())
+    }
+
+    /// Takes an instance from the cache for each of `factor_sources`, and
+    /// derives one for each of the factor sources which have run out, in a
+    /// single `KeysCollector` session, without refilling.
+    ///
+    /// If the derivation fails, the instances taken are put back in the
+    /// cache and `Err(CommonError::FailedToDeriveFactorInstances)` is
+    /// returned.
+    pub async fn take_many_or_derive(
+        &self,
+        factor_sources: IndexSet<HDFactorSource>,
+        network_id: NetworkID,
+        entity_kind: CAP26EntityKind,
+        key_space: KeySpace,
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<IndexMap<FactorSourceIDFromHash, HierarchicalDeterministicFactorInstance>> {
+        let key_of = |f: &HDFactorSource| {
+            FactorInstancesCacheKey::new(f.factor_source_id(), network_id, entity_kind, key_space)
+        };
+        let mut taken = IndexMap::new();
+        let mut exhausted = IndexSet::new();
+        for factor_source in factor_sources.iter() {
+            match self.take(&key_of(factor_source)) {
+                Some(instance) => {
+                    taken.insert(factor_source.factor_source_id(), instance);
+                }
+                None => {
+                    exhausted.insert(factor_source.clone());
+                }
+            }
+        }
+
+        let mut derived = IndexSet::new();
+        if !exhausted.is_empty() {
+            let mut reservation = self.reserve(
+                exhausted.iter().map(key_of),
+                |_, _| 1,
+                false,
+                used_derivation_indices,
+            )?;
+            derived = reservation.derive(&exhausted, interactors).await;
+            if !reservation.indices.is_empty() {
+                let mut state = self.state.write().unwrap();
+                taken.into_values().for_each(|f| state.insert(f));
+                return Err(CommonError::FailedToDeriveFactorInstances);
+            }
+        }
+        taken.extend(derived.into_iter().map(|f| (f.factor_source_id, f)));
+
+        Ok(factor_sources
+            .iter()
+            .filter_map(|f| taken.swap_remove_entry(&f.factor_source_id()))
+            .collect())
+    }
+}
+
+impl FactorInstancesCache {
+    /// Reserves `count_of` indices for each of `keys`, with
+    /// `used_derivation_indices` and after the indices already reserved by
+    /// this cache, marking them as pending if they are reserved to `refill`.
+    ///
+    /// Returns `Err(CommonError::KeySpaceExhausted)`, reserving nothing in
+    /// this cache, if the indices of any key do not fit in its key space.
+    fn reserve(
+        &self,
+        keys: impl IntoIterator<Item = FactorInstancesCacheKey>,
+        count_of: impl Fn(&FactorInstancesCacheState, &FactorInstancesCacheKey) -> usize,
+        refill: bool,
+        used_derivation_indices: &impl UsedDerivationIndices,
+    ) -> Result<Reservation<'_>> {
+        let mut state = self.state.write().unwrap();
+        let mut indices = IndexMap::new();
+        for key in keys {
+            let count = count_of(&state, &key);
+            if count == 0 {
+                continue;
+            }
+            let request = CreateNextDerivationPathRequest::new(
+                key.factor_source_id,
+                key.network_id,
+                CAP26KeyKind::T9n,
+                key.entity_kind,
+                key.key_space,
+            );
+            let used =
+                used_derivation_indices.reserve_derivation_indices_with_request(request, count)?;
+            let start = state
+                .next_index
+                .get(&key)
+                .map_or(used.start, |next| used.start.max(*next));
+            indices.insert(key, key.key_space.range(start, count)?);
+        }
+        for (key, reserved) in indices.iter() {
+            state.next_index.insert(*key, reserved.end);
+            if refill {
+                *state.pending.entry(*key).or_default() += reserved.len();
+            }
+        }
+        Ok(Reservation {
+            cache: self,
+            refill,
+            indices,
+        })
+    }
+}
+
+/// Indices reserved by a `FactorInstancesCache` for instances about to be
+/// derived. The indices of the keys which have not been derived are
+/// released when dropped, i.e. if the derivation failed or was cancelled.
+struct Reservation<'a> {
+    cache: &'a FactorInstancesCache,
+
+    /// Whether the instances are derived to refill the cache, in which case
+    /// they are pending until derived, and then cached.
+    refill: bool,
+
+    /// The reserved indices of the keys which have not been derived yet.
+    indices: IndexMap<FactorInstancesCacheKey, std::ops::Range<HDPathValue>>,
+}
+
+impl Reservation<'_> {
+    /// Derives the instances at the reserved indices in a single
+    /// `KeysCollector` session with `factor_sources`, caching them if this
+    /// is a refill, and returns them. Only the indices of the keys whose
+    /// factor source failed are kept reserved.
+    async fn derive(
+        &mut self,
+        factor_sources: &IndexSet<HDFactorSource>,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> IndexSet<HierarchicalDeterministicFactorInstance> {
+        let derivation_paths = self.indices.iter().fold(
+            IndexMap::<_, IndexSet<_>>::new(),
+            |mut paths, (key, indices)| {
+                paths
+                    .entry(key.factor_source_id)
+                    .or_default()
+                    .extend(indices.clone().map(|i| key.derivation_path(i)));
+                paths
+            },
+        );
+        let derived = KeysCollector::new(factor_sources.clone(), derivation_paths, interactors)
+            .collect_keys()
+            .await
+            .all_factors();
+
+        let derived_keys = derived
+            .iter()
+            .map(FactorInstancesCacheKey::of)
+            .collect::<HashSet<_>>();
+        let refill = self.refill;
+        let mut state = self.cache.state.write().unwrap();
+        if refill {
+            derived.iter().cloned().for_each(|f| state.insert(f));
+        }
+        self.indices.retain(|key, indices| {
+            if !derived_keys.contains(key) {
+                return true;
+            }
+            if let Some(pending) = state.pending.get_mut(key).filter(|_| refill) {
+                *pending -= indices.len();
+            }
+            false
+        });
+        derived
+    }
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        let Ok(mut state) = self.cache.state.write() else {
+            return;
+        };
+        for (key, indices) in self.indices.drain(..) {
+            if let Some(pending) = state.pending.get_mut(&key).filter(|_| self.refill) {
+                *pending -= indices.len();
+            }
+            if state.next_index.get(&key) == Some(&indices.end) {
+                state.next_index.insert(key, indices.start);
+            }
+        }
+    }
+}
+
+impl Default for FactorInstancesCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_INSTANCES_PER_KEY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    type Sut = FactorInstancesCache;
+
+    fn key(
+        factor_source: &HDFactorSource,
+        entity_kind: CAP26EntityKind,
+        key_space: KeySpace,
+    ) -> FactorInstancesCacheKey {
+        FactorInstancesCacheKey::new(
+            factor_source.factor_source_id(),
+            NetworkID::Mainnet,
+            entity_kind,
+            key_space,
+        )
+    }
+
+    /// Interactors deriving with Ledger (and other non-device factor
+    /// sources) using `serial`.
+    fn interactors(
+        serial: fn(
+            SerialBatchKeyDerivationRequest,
+        ) -> Result<IndexSet<HierarchicalDeterministicFactorInstance>>,
+    ) -> Arc<dyn KeysCollectingInteractors> {
+        Arc::new(TestDerivationInteractors::new(
+            TestDerivationParallelInteractor::default(),
+            TestDerivationSerialInteractor::new(serial),
+        ))
+    }
+
+    fn derive(
+        request: SerialBatchKeyDerivationRequest,
+    ) -> Result<IndexSet<HierarchicalDeterministicFactorInstance>> {
+        Ok(request
+            .derivation_paths
+            .into_iter()
+            .map(|p| HierarchicalDeterministicFactorInstance::derive(p, &request.factor_source_id))
+            .collect())
+    }
+
+    #[actix_rt::test]
+    async fn refill_derives_every_key_in_one_prompt() {
+        static PROMPTS: AtomicUsize = AtomicUsize::new(0);
+        let ledger = fs_at(1);
+        let sut = Sut::new(3);
+        sut.refill(
+            &IndexSet::just(ledger.clone()),
+            NetworkID::Mainnet,
+            &StatelessDummyIndices,
+            interactors(|request| {
+                PROMPTS.fetch_add(1, Ordering::SeqCst);
+                derive(request)
+            }),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(PROMPTS.load(Ordering::SeqCst), 1);
+        for key in FactorInstancesCacheKey::all_of(ledger.factor_source_id(), NetworkID::Mainnet) {
+            assert_eq!(sut.cached_count(&key), 3);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn take_does_not_prompt() {
+        static PROMPTS: AtomicUsize = AtomicUsize::new(0);
+        let ledger = fs_at(1);
+        let sut = Sut::new(2);
+        let interactors = interactors(|request| {
+            PROMPTS.fetch_add(1, Ordering::SeqCst);
+            derive(request)
+        });
+        sut.refill(
+            &IndexSet::just(ledger.clone()),
+            NetworkID::Mainnet,
+            &StatelessDummyIndices,
+            interactors.clone(),
+        )
+        .await
+        .unwrap();
+
+        let key = key(&ledger, CAP26EntityKind::Account, KeySpace::Unsecurified);
+        let mut indices = vec![];
+        for _ in 0..2 {
+            let instance = sut
+                .take_or_derive(key, &ledger, &StatelessDummyIndices, interactors.clone())
+                .await
+                .unwrap();
+            indices.push(instance.derivation_path().index);
+        }
+
+        assert_eq!(PROMPTS.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            indices,
+            vec![
+                HDPathComponent::non_hardened(0),
+                HDPathComponent::non_hardened(1)
+            ]
+        );
+        assert_eq!(sut.cached_count(&key), 0);
+    }
+
+    #[actix_rt::test]
+    async fn take_or_derive_falls_back_to_derivation_without_refilling() {
+        static PROMPTS: AtomicUsize = AtomicUsize::new(0);
+        let ledger = fs_at(1);
+        let sut = Sut::new(2);
+        let key = key(&ledger, CAP26EntityKind::Identity, KeySpace::Securified);
+
+        let instance = sut
+            .take_or_derive(
+                key,
+                &ledger,
+                &StatelessDummyIndices,
+                interactors(|request| {
+                    PROMPTS.fetch_add(1, Ordering::SeqCst);
+                    derive(request)
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(PROMPTS.load(Ordering::SeqCst), 1);
+        assert_eq!(FactorInstancesCacheKey::of(&instance), key);
+        assert_eq!(
+            instance.derivation_path().index,
+            HDPathComponent::securified(0)
+        );
+        for key in FactorInstancesCacheKey::all_of(ledger.factor_source_id(), NetworkID::Mainnet) {
+            assert_eq!(sut.cached_count(&key), 0);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn take_or_derive_with_other_factor_source_is_err() {
+        let sut = Sut::new(2);
+        let result = sut
+            .take_or_derive(
+                key(&fs_at(1), CAP26EntityKind::Account, KeySpace::Unsecurified),
+                &fs_at(3),
+                &StatelessDummyIndices,
+                interactors(derive),
+            )
+            .await;
+
+        assert_eq!(result, Err(CommonError::UnknownFactorSource));
+    }
+
+    #[actix_rt::test]
+    async fn failed_derivation_puts_taken_instances_back() {
+        let (ledger, other) = (fs_at(1), fs_at(3));
+        let sut = Sut::new(2);
+        sut.refill(
+            &IndexSet::just(ledger.clone()),
+            NetworkID::Mainnet,
+            &StatelessDummyIndices,
+            interactors(derive),
+        )
+        .await
+        .unwrap();
+
+        let result = sut
+            .take_many_or_derive(
+                IndexSet::from_iter([ledger.clone(), other]),
+                NetworkID::Mainnet,
+                CAP26EntityKind::Account,
+                KeySpace::Unsecurified,
+                &StatelessDummyIndices,
+                Arc::new(TestDerivationInteractors::fail()),
+            )
+            .await;
+
+        assert_eq!(result, Err(CommonError::FailedToDeriveFactorInstances));
+        let key = key(&ledger, CAP26EntityKind::Account, KeySpace::Unsecurified);
+        assert_eq!(sut.cached_count(&key), 2);
+        assert_eq!(
+            sut.take(&key).unwrap().derivation_path().index,
+            HDPathComponent::non_hardened(0)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn failed_refill_releases_reserved_indices() {
+        let ledger = fs_at(1);
+        let sut = Sut::new(2);
+        let factor_sources = IndexSet::just(ledger.clone());
+        let result = sut
+            .refill(
+                &factor_sources,
+                NetworkID::Mainnet,
+                &StatelessDummyIndices,
+                Arc::new(TestDerivationInteractors::fail()),
+            )
+            .await;
+        assert_eq!(result, Err(CommonError::FailedToDeriveFactorInstances));
+
+        sut.refill(
+            &factor_sources,
+            NetworkID::Mainnet,
+            &StatelessDummyIndices,
+            interactors(derive),
+        )
+        .await
+        .unwrap();
+
+        let key = key(&ledger, CAP26EntityKind::Account, KeySpace::Unsecurified);
+        assert_eq!(sut.cached_count(&key), 2);
+        assert_eq!(
+            sut.take(&key).unwrap().derivation_path().index,
+            HDPathComponent::non_hardened(0)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn cache_and_direct_derivation_do_not_reuse_indices() {
+        let ledger = fs_at(1);
+        let profile = Profile::new(IndexSet::just(ledger.clone()), [], []);
+        let indices = ProfileDerivationIndices::new(&profile);
+        let sut = Sut::new(2);
+        sut.refill(
+            &IndexSet::just(ledger.clone()),
+            NetworkID::Mainnet,
+            &indices,
+            interactors(derive),
+        )
+        .await
+        .unwrap();
+
+        let key = key(&ledger, CAP26EntityKind::Account, KeySpace::Securified);
+        let cached = [sut.take(&key).unwrap(), sut.take(&key).unwrap()];
+        let direct = KeysCollector::derive_securified_factor_instances(
+            IndexSet::just(ledger.clone()),
+            NetworkID::Mainnet,
+            CAP26EntityKind::Account,
+            &indices,
+            interactors(derive),
+        )
+        .await
+        .unwrap()[&ledger.factor_source_id()]
+            .clone();
+
+        assert!(cached
+            .iter()
+            .all(|f| f.derivation_path() != direct.derivation_path()));
+        assert_eq!(
+            direct.derivation_path().index,
+            HDPathComponent::securified(2)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn refill_does_not_reuse_indices_of_taken_instances() {
+        let ledger = fs_at(1);
+        let sut = Sut::new(2);
+        let key = key(&ledger, CAP26EntityKind::Account, KeySpace::Unsecurified);
+        let factor_sources = IndexSet::just(ledger.clone());
+        let refill = || {
+            sut.refill(
+                &factor_sources,
+                NetworkID::Mainnet,
+                &StatelessDummyIndices,
+                interactors(derive),
+            )
+        };
+
+        refill().await.unwrap();
+        let taken = sut.take(&key).unwrap();
+        refill().await.unwrap();
+
+        assert_eq!(sut.cached_count(&key), 2);
+        let cached =
+            [sut.take(&key).unwrap(), sut.take(&key).unwrap()].map(|f| f.derivation_path().index);
+        assert_eq!(
+            taken.derivation_path().index,
+            HDPathComponent::non_hardened(0)
+        );
+        assert_eq!(
+            cached,
+            [
+                HDPathComponent::non_hardened(1),
+                HDPathComponent::non_hardened(2)
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn refill_starts_after_indices_used_in_profile() {
+        let ledger = fs_at(1);
+        let profile = Profile::new(
+            IndexSet::just(ledger.clone()),
+            [&Account::unsecurified_mainnet(
+                4,
+                "Alice",
+                ledger.factor_source_id(),
+            )],
+            [],
+        );
+        let sut = Sut::new(1);
+        let instance = sut
+            .take_or_derive(
+                key(&ledger, CAP26EntityKind::Account, KeySpace::Unsecurified),
+                &ledger,
+                &ProfileDerivationIndices::new(&profile),
+                interactors(derive),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            instance.derivation_path().index,
+            HDPathComponent::non_hardened(5)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn refill_beyond_key_space_is_err() {
+        let ledger = fs_at(1);
+        let profile = Profile::new(
+            IndexSet::just(ledger.clone()),
+            [&Account::new(
+                "Alice",
+                EntitySecurityState::unsecured(
+                    HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                        HDPathComponent::non_hardened(BIP32_SECURIFIED_HALF - 2),
+                        ledger.factor_source_id(),
+                    ),
+                ),
+            )],
+            [],
+        );
+        let sut = Sut::new(2);
+        let result = sut
+            .refill(
+                &IndexSet::just(ledger.clone()),
+                NetworkID::Mainnet,
+                &ProfileDerivationIndices::new(&profile),
+                interactors(derive),
+            )
+            .await;
+
+        assert_eq!(result, Err(CommonError::KeySpaceExhausted));
+        for key in FactorInstancesCacheKey::all_of(ledger.factor_source_id(), NetworkID::Mainnet) {
+            assert_eq!(sut.cached_count(&key), 0);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn failing_factor_source_fails() {
+        let sut = Sut::new(2);
+        let result = sut
+            .refill(
+                &IndexSet::just(fs_at(1)),
+                NetworkID::Mainnet,
+                &StatelessDummyIndices,
+                Arc::new(TestDerivationInteractors::fail()),
+            )
+            .await;
+
+        assert_eq!(result, Err(CommonError::FailedToDeriveFactorInstances));
+    }
+
+    #[actix_rt::test]
+    async fn create_account_from_cache() {
+        let ledger = fs_at(1);
+        let sut = Sut::default();
+        sut.refill(
+            &IndexSet::just(ledger.clone()),
+            NetworkID::Mainnet,
+            &StatelessDummyIndices,
+            interactors(derive),
+        )
+        .await
+        .unwrap();
+        let instance = sut
+            .take_or_derive(
+                key(&ledger, CAP26EntityKind::Account, KeySpace::Unsecurified),
+                &ledger,
+                &StatelessDummyIndices,
+                interactors(derive),
+            )
+            .await
+            .unwrap();
+        let account = Account::new("Alice", EntitySecurityState::unsecured(instance.clone()));
+
+        assert_eq!(
+            account.security_state(),
+            EntitySecurityState::unsecured(instance)
+        );
+        assert_eq!(
+            sut.cached_count(&key(
+                &ledger,
+                CAP26EntityKind::Account,
+                KeySpace::Unsecurified
+            )),
+            Sut::DEFAULT_INSTANCES_PER_KEY - 1
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::prelude::*;
+
+/// The group of transaction signing factor instances a `FactorInstancesCache`
+/// keeps pre-derived instances of, e.g. securified account instances of a
+/// Ledger on mainnet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FactorInstancesCacheKey {
+    pub factor_source_id: FactorSourceIDFromHash,
+    pub network_id: NetworkID,
+    pub entity_kind: CAP26EntityKind,
+    pub key_space: KeySpace,
+}
+
+impl FactorInstancesCacheKey {
+    pub fn new(
+        factor_source_id: FactorSourceIDFromHash,
+        network_id: NetworkID,
+        entity_kind: CAP26EntityKind,
+        key_space: KeySpace,
+    ) -> Self {
+        Self {
+            factor_source_id,
+            network_id,
+            entity_kind,
+            key_space,
+        }
+    }
+
+    /// All keys of `factor_source_id` on `network_id`, i.e. for every entity
+    /// kind and key space.
+    pub fn all_of(factor_source_id: FactorSourceIDFromHash, network_id: NetworkID) -> Vec<Self> {
+        [CAP26EntityKind::Account, CAP26EntityKind::Identity]
+            .into_iter()
+            .cartesian_product([KeySpace::Unsecurified, KeySpace::Securified])
+            .map(|(entity_kind, key_space)| {
+                Self::new(factor_source_id, network_id, entity_kind, key_space)
+            })
+            .collect()
+    }
+
+    /// The key `factor_instance` is cached under.
+    pub fn of(factor_instance: &HierarchicalDeterministicFactorInstance) -> Self {
+        let path = factor_instance.derivation_path();
+        Self::new(
+            factor_instance.factor_source_id,
+            path.network_id,
+            path.entity_kind,
+            KeySpace::of(path.index),
+        )
+    }
+
+    /// The derivation path of the transaction signing factor instance at
+    /// `index` of this key.
+    pub fn derivation_path(&self, index: HDPathValue) -> DerivationPath {
+        DerivationPath::new(
+            self.network_id,
+            self.entity_kind,
+            CAP26KeyKind::T9n,
+            HDPathComponent::non_hardened(index),
+        )
+    }
+}
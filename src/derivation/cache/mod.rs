@@ -0,0 +1,5 @@
+mod factor_instances_cache;
+mod factor_instances_cache_key;
+
+pub use factor_instances_cache::*;
+pub use factor_instances_cache_key::*;
@@ -14,8 +14,10 @@ pub struct KeysCollector {
     dependencies: KeysCollectorDependencies,
 
     /// Mutable internal state of the collector which builds up the list
-    /// of public keys from each used factor source.
-    state: RefCell<KeysCollectorState>,
+    /// of public keys from each used factor source, behind a lock so that
+    /// the collector is `Send + Sync` and can be used on multi-threaded
+    /// runtimes.
+    state: RwLock<KeysCollectorState>,
 }
 
 impl KeysCollector {
@@ -32,7 +34,7 @@ impl KeysCollector {
 
         Self {
             dependencies,
-            state: RefCell::new(state),
+            state: RwLock::new(state),
         }
     }
 
@@ -44,6 +46,21 @@ impl KeysCollector {
         let preprocessor = KeysCollectorPreprocessor::new(derivation_paths);
         Self::with_preprocessor(all_factor_sources_in_profile, interactors, preprocessor)
     }
+
+    /// Uses factor sources in the order of kinds decided by `policy`, instead
+    /// of `FrictionOrderPolicy::MostFrictionFirst`.
+    pub fn with_friction_order(mut self, policy: FrictionOrderPolicy) -> Self {
+        let factors_of_kind = std::mem::take(&mut self.dependencies.factors_of_kind);
+        self.dependencies.factors_of_kind = policy.sort(factors_of_kind);
+        self
+    }
+
+    /// Retries factor sources which failed according to `policy`, instead of
+    /// giving up on them immediately.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.dependencies.retry_policy = policy;
+        self
+    }
 }
 
 impl KeysCollector {
@@ -55,7 +72,8 @@ impl KeysCollector {
     async fn derive_with_factors(&self) -> Result<()> {
         for factors_of_kind in self.dependencies.factors_of_kind.iter() {
             let interactor = self.get_interactor(factors_of_kind.kind);
-            let client = KeysCollectingClient::new(interactor);
+            let client =
+                KeysCollectingClient::new(interactor, self.dependencies.retry_policy.clone());
             client
                 .use_factor_sources(factors_of_kind.factor_sources(), self)
                 .await?;
@@ -71,9 +89,9 @@ impl KeysCollector {
     ) -> SerialBatchKeyDerivationRequest {
         let keyring = self
             .state
-            .borrow()
+            .read()
+            .unwrap()
             .keyrings
-            .borrow()
             .keyring_for(factor_source_id)
             .unwrap();
         assert_eq!(keyring.factors().len(), 0);
@@ -100,8 +118,18 @@ impl KeysCollector {
         self.input_for_interactor(factor_source_id)
     }
 
-    pub(crate) fn process_batch_response(&self, response: BatchDerivationResponse) {
-        self.state.borrow_mut().process_batch_response(response)
+    pub(crate) fn process_batch_response(&self, response: BatchDerivationResponse) -> Result<()> {
+        self.state.write().unwrap().process_batch_response(response)
+    }
+
+    pub(crate) fn process_parallel_batch_response(
+        &self,
+        response: ParallelBatchDerivationResponse,
+    ) -> Result<()> {
+        self.state
+            .write()
+            .unwrap()
+            .process_parallel_batch_response(response)
     }
 }
 
@@ -111,7 +139,7 @@ impl KeysCollector {
             .derive_with_factors() // in decreasing "friction order"
             .await
             .inspect_err(|e| eprintln!("Failed to use factor sources: {:#?}", e));
-        self.state.into_inner().keyrings.into_inner().outcome()
+        self.state.into_inner().unwrap().keyrings.outcome()
     }
 }
 
@@ -119,6 +147,12 @@ impl KeysCollector {
 pub struct KeyDerivationOutcome {
     pub factors_by_source:
         IndexMap<FactorSourceIDFromHash, IndexSet<HierarchicalDeterministicFactorInstance>>,
+
+    /// Ids of factor sources which the user skipped.
+    pub skipped_factor_sources: IndexSet<FactorSourceIDFromHash>,
+
+    /// Ids of factor sources which failed, and the error why.
+    pub failed_factor_sources: IndexMap<FactorSourceIDFromHash, CommonError>,
 }
 impl KeyDerivationOutcome {
     pub fn new(
@@ -126,8 +160,14 @@ impl KeyDerivationOutcome {
             FactorSourceIDFromHash,
             IndexSet<HierarchicalDeterministicFactorInstance>,
         >,
+        skipped_factor_sources: IndexSet<FactorSourceIDFromHash>,
+        failed_factor_sources: IndexMap<FactorSourceIDFromHash, CommonError>,
     ) -> Self {
-        Self { factors_by_source }
+        Self {
+            factors_by_source,
+            skipped_factor_sources,
+            failed_factor_sources,
+        }
     }
 
     /// ALL factor instances derived by the KeysCollector
@@ -139,3 +179,85 @@ impl KeyDerivationOutcome {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = KeysCollector;
+
+    fn sut() -> Sut {
+        Sut::new_test(
+            [
+                FactorSourceIDFromHash::fs0(),
+                FactorSourceIDFromHash::fs1(),
+                FactorSourceIDFromHash::fs3(),
+            ]
+            .map(|f| {
+                (
+                    f,
+                    IndexSet::just(DerivationPath::account_tx(
+                        NetworkID::Mainnet,
+                        HDPathComponent::non_hardened(0),
+                    )),
+                )
+            }),
+        )
+    }
+
+    fn kinds(sut: &Sut) -> Vec<FactorSourceKind> {
+        sut.dependencies
+            .factors_of_kind
+            .iter()
+            .map(|f| f.kind)
+            .collect()
+    }
+
+    #[test]
+    fn default_friction_order() {
+        use FactorSourceKind::*;
+        assert_eq!(kinds(&sut()), vec![Ledger, Arculus, Device]);
+    }
+
+    #[actix_rt::test]
+    async fn retry_policy_retries_failed_parallel_derivation() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static ATTEMPTS: AtomicUsize = AtomicUsize::new(0);
+
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let path = DerivationPath::account_tx(NetworkID::Mainnet, HDPathComponent::non_hardened(0));
+        let flaky = TestDerivationParallelInteractor::new(|request| {
+            if ATTEMPTS.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(CommonError::Failure);
+            }
+            Ok(request
+                .derivation_paths
+                .into_iter()
+                .map(|p| {
+                    HierarchicalDeterministicFactorInstance::derive(p, &request.factor_source_id)
+                })
+                .collect())
+        });
+        let sut = Sut::new(
+            HDFactorSource::all(),
+            IndexMap::from_iter([(fs0, IndexSet::just(path))]),
+            Arc::new(TestDerivationInteractors::new(
+                flaky,
+                TestDerivationSerialInteractor::default(),
+            )),
+        )
+        .with_retry_policy(RetryPolicy::new().with_max_attempts(FactorSourceKind::Device, 2));
+
+        let outcome = sut.collect_keys().await;
+        assert_eq!(ATTEMPTS.load(Ordering::SeqCst), 2);
+        assert_eq!(outcome.all_factors().len(), 1);
+        assert!(outcome.failed_factor_sources.is_empty());
+    }
+
+    #[test]
+    fn custom_friction_order() {
+        use FactorSourceKind::*;
+        let sut = sut().with_friction_order(FrictionOrderPolicy::custom([Device, Ledger]));
+        assert_eq!(kinds(&sut), vec![Device, Ledger, Arculus]);
+    }
+}
@@ -14,6 +14,9 @@ pub struct KeysCollectorDependencies {
     /// computer and thus unable to make a connection between the Radix Wallet
     /// and a Ledger device.
     pub(super) factors_of_kind: IndexSet<FactorSourcesOfKind>,
+
+    /// How many times to retry factor sources which failed.
+    pub(super) retry_policy: RetryPolicy,
 }
 
 impl KeysCollectorDependencies {
@@ -24,6 +27,7 @@ impl KeysCollectorDependencies {
         Self {
             interactors,
             factors_of_kind,
+            retry_policy: RetryPolicy::default(),
         }
     }
 }
@@ -4,7 +4,7 @@ use crate::prelude::*;
 pub struct Keyring {
     pub factor_source_id: FactorSourceIDFromHash,
     pub paths: IndexSet<DerivationPath>,
-    derived: RefCell<IndexSet<HierarchicalDeterministicFactorInstance>>,
+    derived: IndexSet<HierarchicalDeterministicFactorInstance>,
 }
 
 impl Keyring {
@@ -12,33 +12,50 @@ impl Keyring {
         Self {
             factor_source_id,
             paths,
-            derived: RefCell::new(IndexSet::new()),
+            derived: IndexSet::new(),
         }
     }
     pub fn factors(&self) -> IndexSet<HierarchicalDeterministicFactorInstance> {
-        self.derived.borrow().clone()
+        self.derived.clone()
     }
 
+    /// Returns `Err(CommonError::UnexpectedFactorInstance)` if any factor
+    /// instance was derived by another factor source, and
+    /// `Err(CommonError::DuplicateFactorInstance)` if any public key has
+    /// already been derived. The keyring is left unchanged on error.
     pub(crate) fn process_response(
-        &self,
+        &mut self,
         response: IndexSet<HierarchicalDeterministicFactorInstance>,
-    ) {
-        assert!(response
+    ) -> Result<()> {
+        if response
             .iter()
-            .all(|f| f.factor_source_id == self.factor_source_id
-                && !self
-                    .derived
-                    .borrow()
-                    .iter()
-                    .any(|x| x.public_key == f.public_key)));
+            .any(|f| f.factor_source_id != self.factor_source_id)
+        {
+            return Err(CommonError::UnexpectedFactorInstance);
+        }
+        let public_keys = response
+            .iter()
+            .map(|f| f.public_key.clone())
+            .collect::<IndexSet<_>>();
+        if public_keys.len() != response.len()
+            || self
+                .derived
+                .iter()
+                .any(|x| public_keys.contains(&x.public_key))
+        {
+            return Err(CommonError::DuplicateFactorInstance);
+        }
 
-        self.derived.borrow_mut().extend(response)
+        self.derived.extend(response);
+        Ok(())
     }
 }
 
 #[derive(Default, Clone, Debug)]
 pub struct Keyrings {
-    keyrings: RefCell<IndexMap<FactorSourceIDFromHash, Keyring>>,
+    keyrings: IndexMap<FactorSourceIDFromHash, Keyring>,
+    skipped: IndexSet<FactorSourceIDFromHash>,
+    failed: IndexMap<FactorSourceIDFromHash, CommonError>,
 }
 
 impl Keyrings {
@@ -55,34 +72,86 @@ impl Keyrings {
             })
             .collect::<IndexMap<FactorSourceIDFromHash, Keyring>>();
         Self {
-            keyrings: RefCell::new(keyrings),
+            keyrings,
+            skipped: IndexSet::new(),
+            failed: IndexMap::new(),
         }
     }
 
     pub fn outcome(self) -> KeyDerivationOutcome {
-        let key_rings = self.keyrings.into_inner();
         KeyDerivationOutcome::new(
-            key_rings
+            self.keyrings
                 .into_iter()
                 .map(|(k, v)| (k, v.factors()))
                 .collect(),
+            self.skipped,
+            self.failed,
         )
     }
 
     pub fn keyring_for(&self, factor_source_id: &FactorSourceIDFromHash) -> Option<Keyring> {
         self.keyrings
-            .borrow()
             .get(factor_source_id)
             .cloned()
             .inspect(|k| assert_eq!(k.factor_source_id, *factor_source_id))
     }
 
-    pub(crate) fn process_batch_response(&self, response: BatchDerivationResponse) {
+    fn ensure_known_factor_sources<'a>(
+        &self,
+        mut factor_source_ids: impl Iterator<Item = &'a FactorSourceIDFromHash>,
+    ) -> Result<()> {
+        if factor_source_ids.any(|id| !self.keyrings.contains_key(id)) {
+            return Err(CommonError::UnknownFactorSource);
+        }
+        Ok(())
+    }
+
+    /// Adds the derived factor instances to the keyring of the factor
+    /// source, a factor source which responded with factor instances which
+    /// cannot be added is treated as failed.
+    fn process_response(
+        &mut self,
+        factor_source_id: FactorSourceIDFromHash,
+        factors: IndexSet<HierarchicalDeterministicFactorInstance>,
+    ) {
+        let keyring = self.keyrings.get_mut(&factor_source_id).unwrap();
+        if let Err(error) = keyring.process_response(factors) {
+            self.failed.insert(factor_source_id, error);
+        }
+    }
+
+    /// Returns `Err(CommonError::UnknownFactorSource)` if the response
+    /// contains a factor source we did not request derivation from.
+    pub(crate) fn process_batch_response(
+        &mut self,
+        response: BatchDerivationResponse,
+    ) -> Result<()> {
+        self.ensure_known_factor_sources(response.per_factor_source.keys())?;
         for (factor_source_id, factors) in response.per_factor_source.into_iter() {
-            let mut rings = self.keyrings.borrow_mut();
-            let keyring = rings.get_mut(&factor_source_id).unwrap();
-            keyring.process_response(factors)
+            self.process_response(factor_source_id, factors)
+        }
+        Ok(())
+    }
+
+    pub(crate) fn process_parallel_batch_response(
+        &mut self,
+        response: ParallelBatchDerivationResponse,
+    ) -> Result<()> {
+        self.ensure_known_factor_sources(response.per_factor_source.keys())?;
+        for (factor_source_id, outcome) in response.per_factor_source.into_iter() {
+            match outcome {
+                FactorSourceOutcome::Used(factors) => {
+                    self.process_response(factor_source_id, factors)
+                }
+                FactorSourceOutcome::Skipped => {
+                    self.skipped.insert(factor_source_id);
+                }
+                FactorSourceOutcome::Failed(error) => {
+                    self.failed.insert(factor_source_id, error);
+                }
+            }
         }
+        Ok(())
     }
 }
 
@@ -122,3 +191,84 @@ impl KeysCollectorPreprocessor {
         (keyrings, factor_sources_of_kind)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = Keyrings;
+
+    fn instance(
+        factor_source_id: FactorSourceIDFromHash,
+    ) -> HierarchicalDeterministicFactorInstance {
+        HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+            HDPathComponent::non_hardened(0),
+            factor_source_id,
+        )
+    }
+
+    fn sut() -> Sut {
+        let fs0 = FactorSourceIDFromHash::fs0();
+        Sut::new(IndexMap::from_iter([(
+            fs0,
+            IndexSet::just(instance(fs0).derivation_path()),
+        )]))
+    }
+
+    fn response(
+        factor_source_id: FactorSourceIDFromHash,
+        factors: impl IntoIterator<Item = HierarchicalDeterministicFactorInstance>,
+    ) -> BatchDerivationResponse {
+        BatchDerivationResponse::new(IndexMap::from_iter([(
+            factor_source_id,
+            factors.into_iter().collect(),
+        )]))
+    }
+
+    #[test]
+    fn instance_of_other_factor_source_fails_factor_source() {
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let mut sut = sut();
+        sut.process_batch_response(response(fs0, [instance(FactorSourceIDFromHash::fs1())]))
+            .unwrap();
+        let outcome = sut.outcome();
+        assert!(outcome.factors_by_source.get(&fs0).unwrap().is_empty());
+        assert_eq!(
+            outcome.failed_factor_sources,
+            IndexMap::<_, _>::from_iter([(fs0, CommonError::UnexpectedFactorInstance)])
+        );
+    }
+
+    #[test]
+    fn duplicate_instance_fails_factor_source() {
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let mut sut = sut();
+        sut.process_batch_response(response(fs0, [instance(fs0)]))
+            .unwrap();
+        sut.process_batch_response(response(fs0, [instance(fs0)]))
+            .unwrap();
+        let outcome = sut.outcome();
+        assert_eq!(
+            outcome.factors_by_source.get(&fs0).unwrap(),
+            &IndexSet::just(instance(fs0))
+        );
+        assert_eq!(
+            outcome.failed_factor_sources,
+            IndexMap::<_, _>::from_iter([(fs0, CommonError::DuplicateFactorInstance)])
+        );
+    }
+
+    #[test]
+    fn unknown_factor_source_is_err() {
+        let fs1 = FactorSourceIDFromHash::fs1();
+        let mut sut = sut();
+        assert_eq!(
+            sut.process_batch_response(response(fs1, [instance(fs1)])),
+            Err(CommonError::UnknownFactorSource)
+        );
+        assert_eq!(
+            sut.process_parallel_batch_response(ParallelBatchDerivationResponse::skipped([fs1])),
+            Err(CommonError::UnknownFactorSource)
+        );
+    }
+}
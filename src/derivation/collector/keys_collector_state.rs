@@ -1,17 +1,25 @@
 use crate::prelude::*;
 
 pub struct KeysCollectorState {
-    pub(super) keyrings: RefCell<Keyrings>,
+    pub(super) keyrings: Keyrings,
 }
 
 impl KeysCollectorState {
     pub fn new(keyrings: Keyrings) -> Self {
-        Self {
-            keyrings: RefCell::new(keyrings),
-        }
+        Self { keyrings }
     }
 
-    pub(crate) fn process_batch_response(&self, response: BatchDerivationResponse) {
-        self.keyrings.borrow_mut().process_batch_response(response)
+    pub(crate) fn process_batch_response(
+        &mut self,
+        response: BatchDerivationResponse,
+    ) -> Result<()> {
+        self.keyrings.process_batch_response(response)
+    }
+
+    pub(crate) fn process_parallel_batch_response(
+        &mut self,
+        response: ParallelBatchDerivationResponse,
+    ) -> Result<()> {
+        self.keyrings.process_parallel_batch_response(response)
     }
 }
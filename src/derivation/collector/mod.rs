@@ -2,10 +2,13 @@ mod keys_collector;
 mod keys_collector_dependencies;
 mod keys_collector_preprocessor;
 mod keys_collector_state;
+mod profile_derivation_indices;
+mod securified_factor_instances;
 mod used_derivation_indices;
 
 pub use keys_collector::*;
 pub use keys_collector_dependencies::*;
 pub use keys_collector_preprocessor::*;
 pub use keys_collector_state::*;
+pub use profile_derivation_indices::*;
 pub use used_derivation_indices::*;
@@ -0,0 +1,269 @@
+use crate::prelude::*;
+
+/// `UsedDerivationIndices` backed by a `Profile`, returning for each factor
+/// source, network, key kind, entity kind and key space the index after the
+/// highest index used by any factor instance of any account or persona in
+/// the profile, including all factor instances of securified entities and
+/// the registered ROLA factor instances.
+///
+/// Each returned index is reserved for the rest of the session, so that
+/// e.g. creating two accounts in a row does not reuse the same path.
+#[derive(Debug)]
+pub struct ProfileDerivationIndices {
+    /// The next free index per request, advanced as indices are reserved.
+    next: RwLock<HashMap<CreateNextDerivationPathRequest, HDPathValue>>,
+}
+
+impl ProfileDerivationIndices {
+    pub fn new(profile: &Profile) -> Self {
+        let factor_instances = profile
+            .accounts
+            .values()
+            .map(|a| a.security_state())
+            .chain(profile.personas.values().map(|p| p.security_state()))
+            .flat_map(|s| {
+                let mut factor_instances = s.all_factor_instances();
+                factor_instances.extend(s.rola_factor_instances().iter().cloned());
+                factor_instances
+            });
+
+        let mut next = HashMap::<CreateNextDerivationPathRequest, HDPathValue>::new();
+        for factor_instance in factor_instances {
+            let path = factor_instance.derivation_path();
+            let request = CreateNextDerivationPathRequest::new(
+                factor_instance.factor_source_id,
+                path.network_id,
+                path.key_kind,
+                path.entity_kind,
+                KeySpace::of(path.index),
+            );
+            let after = path.index.value + 1;
+            next.entry(request)
+                .and_modify(|n| *n = (*n).max(after))
+                .or_insert(after);
+        }
+
+        Self {
+            next: RwLock::new(next),
+        }
+    }
+}
+
+impl UsedDerivationIndices for ProfileDerivationIndices {
+    fn reserve_derivation_indices_with_request(
+        &self,
+        request: CreateNextDerivationPathRequest,
+        count: usize,
+    ) -> Result<std::ops::Range<HDPathValue>> {
+        let mut next = self.next.write().unwrap();
+        let key_space = request.key_space;
+        let index = next.entry(request).or_insert(key_space.first_index());
+        let reserved = key_space.range(*index, count)?;
+        *index = reserved.end;
+        Ok(reserved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = ProfileDerivationIndices;
+
+    fn next(
+        sut: &Sut,
+        factor_source_id: FactorSourceIDFromHash,
+        entity_kind: CAP26EntityKind,
+        key_space: KeySpace,
+    ) -> HDPathComponent {
+        sut.next_derivation_index_for(
+            factor_source_id,
+            NetworkID::Mainnet,
+            CAP26KeyKind::T9n,
+            entity_kind,
+            key_space,
+        )
+        .unwrap()
+    }
+
+    fn sut() -> Sut {
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let securified = Account::securified_mainnet(2, "Securified", |idx| {
+            let idx = HDPathComponent::securified(idx);
+            MatrixOfFactorInstances::new(
+                [HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                    idx,
+                    FactorSourceIDFromHash::fs0(),
+                )],
+                1,
+                [HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                    idx,
+                    FactorSourceIDFromHash::fs1(),
+                )],
+            )
+            .unwrap()
+        });
+        Sut::new(&Profile::new(
+            HDFactorSource::all(),
+            [
+                &Account::unsecurified_mainnet(0, "Alice", fs0),
+                &Account::unsecurified_mainnet(3, "Bob", fs0),
+                &securified,
+            ],
+            [&Persona::unsecurified_mainnet(0, "Satoshi", fs0)],
+        ))
+    }
+
+    #[test]
+    fn next_after_highest_used_index() {
+        let sut = sut();
+        assert_eq!(
+            next(
+                &sut,
+                FactorSourceIDFromHash::fs0(),
+                CAP26EntityKind::Account,
+                KeySpace::Unsecurified
+            ),
+            HDPathComponent::non_hardened(4)
+        );
+    }
+
+    #[test]
+    fn grouped_by_entity_kind() {
+        let sut = sut();
+        assert_eq!(
+            next(
+                &sut,
+                FactorSourceIDFromHash::fs0(),
+                CAP26EntityKind::Identity,
+                KeySpace::Unsecurified
+            ),
+            HDPathComponent::non_hardened(1)
+        );
+    }
+
+    #[test]
+    fn grouped_by_factor_source() {
+        let sut = sut();
+        assert_eq!(
+            next(
+                &sut,
+                FactorSourceIDFromHash::fs2(),
+                CAP26EntityKind::Account,
+                KeySpace::Unsecurified
+            ),
+            HDPathComponent::non_hardened(0)
+        );
+    }
+
+    #[test]
+    fn securified_key_space_includes_matrix_factor_instances() {
+        let sut = sut();
+        assert_eq!(
+            next(
+                &sut,
+                FactorSourceIDFromHash::fs1(),
+                CAP26EntityKind::Account,
+                KeySpace::Securified
+            ),
+            HDPathComponent::securified(3)
+        );
+        assert_eq!(
+            next(
+                &sut,
+                FactorSourceIDFromHash::fs2(),
+                CAP26EntityKind::Account,
+                KeySpace::Securified
+            ),
+            HDPathComponent::securified(0)
+        );
+    }
+
+    #[test]
+    fn reserved_indices_are_not_reused() {
+        let sut = sut();
+        let next = || {
+            next(
+                &sut,
+                FactorSourceIDFromHash::fs0(),
+                CAP26EntityKind::Account,
+                KeySpace::Unsecurified,
+            )
+        };
+        assert_eq!(next(), HDPathComponent::non_hardened(4));
+        assert_eq!(next(), HDPathComponent::non_hardened(5));
+    }
+
+    #[test]
+    fn exhausted_key_space_is_err() {
+        let last = HDPathComponent::non_hardened(BIP32_SECURIFIED_HALF - 1);
+        let sut = Sut::new(&Profile::new(
+            HDFactorSource::all(),
+            [&Account::new(
+                "Last",
+                EntitySecurityState::unsecured(
+                    HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                        last,
+                        FactorSourceIDFromHash::fs0(),
+                    ),
+                ),
+            )],
+            [],
+        ));
+        assert_eq!(
+            sut.next_derivation_index_for(
+                FactorSourceIDFromHash::fs0(),
+                NetworkID::Mainnet,
+                CAP26KeyKind::T9n,
+                CAP26EntityKind::Account,
+                KeySpace::Unsecurified,
+            ),
+            Err(CommonError::KeySpaceExhausted)
+        );
+    }
+
+    #[test]
+    fn reserved_ranges_are_not_reused() {
+        let sut = sut();
+        let request = CreateNextDerivationPathRequest::new(
+            FactorSourceIDFromHash::fs0(),
+            NetworkID::Mainnet,
+            CAP26KeyKind::T9n,
+            CAP26EntityKind::Account,
+            KeySpace::Unsecurified,
+        );
+        assert_eq!(
+            sut.reserve_derivation_indices_with_request(request.clone(), 3),
+            Ok(4..7)
+        );
+        assert_eq!(
+            sut.next_derivation_index_with_request(request),
+            Ok(HDPathComponent::non_hardened(7))
+        );
+    }
+
+    #[test]
+    fn rola_after_highest_registered_rola_index() {
+        let sut = sut();
+        assert_eq!(
+            sut.next_derivation_index_for(
+                FactorSourceIDFromHash::fs0(),
+                NetworkID::Mainnet,
+                CAP26KeyKind::Rola,
+                CAP26EntityKind::Account,
+                KeySpace::Unsecurified,
+            ),
+            Ok(HDPathComponent::non_hardened(4))
+        );
+        assert_eq!(
+            sut.next_derivation_index_for(
+                FactorSourceIDFromHash::fs1(),
+                NetworkID::Mainnet,
+                CAP26KeyKind::Rola,
+                CAP26EntityKind::Account,
+                KeySpace::Securified,
+            ),
+            Ok(HDPathComponent::securified(3))
+        );
+    }
+}
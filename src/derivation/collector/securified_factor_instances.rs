@@ -0,0 +1,403 @@
+use crate::prelude::*;
+
+impl KeysCollector {
+    /// Derives a factor instance in `KeySpace::Securified` with each of
+    /// `factor_sources`, at the next derivation index according to
+    /// `used_derivation_indices`, in a single session so that each factor
+    /// source is prompted once.
+    ///
+    /// Returns `Err(CommonError::FailedToDeriveFactorInstances)` if any
+    /// factor source was skipped or failed.
+    pub async fn derive_securified_factor_instances(
+        factor_sources: IndexSet<HDFactorSource>,
+        network_id: NetworkID,
+        entity_kind: CAP26EntityKind,
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<IndexMap<FactorSourceIDFromHash, HierarchicalDeterministicFactorInstance>> {
+        Self::derive_securified_factor_instances_of_key_kinds(
+            factor_sources,
+            network_id,
+            entity_kind,
+            &[CAP26KeyKind::T9n],
+            used_derivation_indices,
+            interactors,
+        )
+        .await
+        .map(|instances| {
+            instances
+                .into_iter()
+                .map(|((id, _), instance)| (id, instance))
+                .collect()
+        })
+    }
+
+    /// Derives a factor instance in `KeySpace::Securified` for each of
+    /// `key_kinds` with each of `factor_sources`, at the next derivation
+    /// index according to `used_derivation_indices`, in a single session so
+    /// that each factor source is prompted once.
+    ///
+    /// Returns `Err(CommonError::FailedToDeriveFactorInstances)` if any
+    /// factor source was skipped or failed.
+    pub async fn derive_securified_factor_instances_of_key_kinds(
+        factor_sources: IndexSet<HDFactorSource>,
+        network_id: NetworkID,
+        entity_kind: CAP26EntityKind,
+        key_kinds: &[CAP26KeyKind],
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<
+        IndexMap<(FactorSourceIDFromHash, CAP26KeyKind), HierarchicalDeterministicFactorInstance>,
+    > {
+        let derivation_paths = factor_sources
+            .iter()
+            .map(|f| {
+                let paths = key_kinds
+                    .iter()
+                    .map(|key_kind| {
+                        used_derivation_indices.next_derivation_path(
+                            f.factor_source_id(),
+                            network_id,
+                            *key_kind,
+                            entity_kind,
+                            KeySpace::Securified,
+                        )
+                    })
+                    .collect::<Result<IndexSet<_>>>()?;
+                Ok((f.factor_source_id(), paths))
+            })
+            .collect::<Result<IndexMap<_, _>>>()?;
+
+        let collector = Self::new(factor_sources.clone(), derivation_paths, interactors);
+        let outcome = collector.collect_keys().await;
+
+        factor_sources
+            .iter()
+            .cartesian_product(key_kinds)
+            .map(|(f, key_kind)| {
+                let id = f.factor_source_id();
+                outcome
+                    .factors_by_source
+                    .get(&id)
+                    .and_then(|instances| {
+                        instances
+                            .iter()
+                            .find(|i| i.derivation_path().key_kind == *key_kind)
+                    })
+                    .map(|instance| ((id, *key_kind), instance.clone()))
+                    .ok_or(CommonError::FailedToDeriveFactorInstances)
+            })
+            .collect()
+    }
+}
+
+/// The network of `entity`, read from its current factor instances.
+///
+/// Returns `Err(CommonError::EntityWithoutFactorInstances)` if `entity` has
+/// none, e.g. if it is securified with an empty access rule.
+fn network_of(entity: &impl IsEntity) -> Result<NetworkID> {
+    entity
+        .security_state()
+        .all_factor_instances()
+        .first()
+        .map(|f| f.derivation_path().network_id)
+        .ok_or(CommonError::EntityWithoutFactorInstances)
+}
+
+impl MatrixOfFactorSources {
+    /// The `MatrixOfFactorInstances` of this matrix for `entity`, derived
+    /// in a single `KeysCollector` session.
+    pub async fn derive_factor_instances_for(
+        &self,
+        entity: &impl IsEntity,
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<MatrixOfFactorInstances> {
+        let factor_sources = self.all_factors().cloned().collect::<IndexSet<_>>();
+        let instances = KeysCollector::derive_securified_factor_instances(
+            factor_sources,
+            network_of(entity)?,
+            entity_kind_of(entity),
+            used_derivation_indices,
+            interactors,
+        )
+        .await?;
+        Ok(self.map(|f| instances[&f.factor_source_id()].clone()))
+    }
+}
+
+impl MatrixOfFactorSources {
+    /// The `MatrixOfFactorInstances` of this matrix for `entity`, taken from
+    /// `cache`, only deriving (in a single `KeysCollector` session) with the
+    /// factor sources for which `cache` has run out.
+    pub async fn factor_instances_from_cache_for(
+        &self,
+        entity: &impl IsEntity,
+        cache: &FactorInstancesCache,
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<MatrixOfFactorInstances> {
+        let factor_sources = self.all_factors().cloned().collect::<IndexSet<_>>();
+        let instances = cache
+            .take_many_or_derive(
+                factor_sources,
+                network_of(entity)?,
+                entity_kind_of(entity),
+                KeySpace::Securified,
+                used_derivation_indices,
+                interactors,
+            )
+            .await?;
+        Ok(self.map(|f| instances[&f.factor_source_id()].clone()))
+    }
+}
+
+impl SecurityStructureOfFactorSources {
+    /// The `SecurityStructureOfFactorInstances` of this structure for
+    /// `entity`, derived in a single `KeysCollector` session, so that each
+    /// factor source is prompted once even if it is used in several roles.
+    pub async fn derive_factor_instances_for(
+        &self,
+        entity: &impl IsEntity,
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<SecurityStructureOfFactorInstances> {
+        let instances = KeysCollector::derive_securified_factor_instances(
+            self.all_factor_sources(),
+            network_of(entity)?,
+            entity_kind_of(entity),
+            used_derivation_indices,
+            interactors,
+        )
+        .await?;
+        Ok(self.map_factor_sources(|f| instances[&f.factor_source_id()].clone()))
+    }
+
+    /// The securified `EntitySecurityState` of this structure for `entity`,
+    /// with a ROLA factor instance registered for each of its factor
+    /// sources, all derived in a single `KeysCollector` session, so that
+    /// the entity can still authenticate once securified.
+    pub async fn derive_security_state_for(
+        &self,
+        entity: &impl IsEntity,
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<EntitySecurityState> {
+        let instances = KeysCollector::derive_securified_factor_instances_of_key_kinds(
+            self.all_factor_sources(),
+            network_of(entity)?,
+            entity_kind_of(entity),
+            &[CAP26KeyKind::T9n, CAP26KeyKind::Rola],
+            used_derivation_indices,
+            interactors,
+        )
+        .await?;
+        let structure = self
+            .map_factor_sources(|f| instances[&(f.factor_source_id(), CAP26KeyKind::T9n)].clone());
+        let rola_factor_instances = instances
+            .iter()
+            .filter(|((_, key_kind), _)| *key_kind == CAP26KeyKind::Rola)
+            .map(|(_, instance)| instance.clone());
+        Ok(EntitySecurityState::securified(structure)
+            .with_rola_factor_instances(rola_factor_instances))
+    }
+
+    /// The factor sources of all roles, each listed once.
+    fn all_factor_sources(&self) -> IndexSet<HDFactorSource> {
+        [
+            &self.primary_role,
+            &self.recovery_role,
+            &self.confirmation_role,
+        ]
+        .into_iter()
+        .flat_map(|r| r.factors())
+        .collect()
+    }
+
+    fn map_factor_sources(
+        &self,
+        instance_of: impl Fn(&HDFactorSource) -> HierarchicalDeterministicFactorInstance,
+    ) -> SecurityStructureOfFactorInstances {
+        SecurityStructure {
+            primary_role: self.primary_role.map(&instance_of),
+            recovery_role: self.recovery_role.map(&instance_of),
+            confirmation_role: self.confirmation_role.map(&instance_of),
+        }
+    }
+}
+
+fn entity_kind_of<E: IsEntity>(_entity: &E) -> CAP26EntityKind {
+    E::kind()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn entity_without_factor_instances_is_err() {
+        let entity = Account::new("Empty", AccessRule::AnyOf(Vec::new()));
+        let matrix = MatrixOfFactorSources::single_threshold(HDFactorSource::fs1()).unwrap();
+        assert_eq!(
+            matrix
+                .derive_factor_instances_for(
+                    &entity,
+                    &StatelessDummyIndices,
+                    Arc::new(TestDerivationInteractors::default()),
+                )
+                .await,
+            Err(CommonError::EntityWithoutFactorInstances)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn matrix_of_factor_instances_matches_sources() {
+        let (fs0, fs1, fs3) = (
+            HDFactorSource::fs0(),
+            HDFactorSource::fs1(),
+            HDFactorSource::fs3(),
+        );
+        let matrix =
+            MatrixOfFactorSources::new([fs0.clone(), fs3.clone()], 1, [fs1.clone()]).unwrap();
+        let instances = matrix
+            .derive_factor_instances_for(
+                &Account::a0(),
+                &StatelessDummyIndices,
+                Arc::new(TestDerivationInteractors::default()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(instances.threshold, 1);
+        assert_eq!(
+            instances
+                .threshold_factors
+                .iter()
+                .map(|w| w.factor.factor_source_id)
+                .collect_vec(),
+            vec![fs0.factor_source_id(), fs3.factor_source_id()]
+        );
+        assert_eq!(
+            instances
+                .override_factors
+                .iter()
+                .map(|f| f.factor_source_id)
+                .collect_vec(),
+            vec![fs1.factor_source_id()]
+        );
+        assert!(instances.all_factors().all(|f| f.derivation_path()
+            == DerivationPath::account_tx(
+                NetworkID::Mainnet,
+                HDPathComponent::non_hardened(BIP32_SECURIFIED_HALF)
+            )));
+    }
+
+    #[actix_rt::test]
+    async fn factor_source_used_in_several_roles_is_prompted_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static PROMPTS: AtomicUsize = AtomicUsize::new(0);
+
+        let interactor = TestDerivationSerialInteractor::new(|request| {
+            PROMPTS.fetch_add(1, Ordering::SeqCst);
+            Ok(request
+                .derivation_paths
+                .into_iter()
+                .map(|p| {
+                    HierarchicalDeterministicFactorInstance::derive(p, &request.factor_source_id)
+                })
+                .collect())
+        });
+        let (ledger, arculus) = (HDFactorSource::fs1(), HDFactorSource::fs3());
+        let structure = SecurityStructureOfFactorSources::new(
+            MatrixOfFactorSources::new([ledger.clone()], 1, []).unwrap(),
+            MatrixOfFactorSources::new([ledger.clone(), arculus.clone()], 2, []).unwrap(),
+            MatrixOfFactorSources::override_only([arculus]).unwrap(),
+        );
+        let instances = structure
+            .derive_factor_instances_for(
+                &Persona::p0(),
+                &StatelessDummyIndices,
+                Arc::new(TestDerivationInteractors::new(
+                    TestDerivationParallelInteractor::default(),
+                    interactor,
+                )),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(PROMPTS.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            instances.primary_role.factors(),
+            instances
+                .recovery_role
+                .factors()
+                .into_iter()
+                .filter(|f| f.factor_source_id == ledger.factor_source_id())
+                .collect::<IndexSet<_>>()
+        );
+        assert!(instances
+            .recovery_role
+            .factors()
+            .iter()
+            .all(|f| f.derivation_path().entity_kind == CAP26EntityKind::Identity));
+    }
+
+    #[actix_rt::test]
+    async fn failing_factor_source_fails() {
+        let matrix =
+            MatrixOfFactorSources::override_only([HDFactorSource::fs1(), HDFactorSource::fs3()])
+                .unwrap();
+        let result = matrix
+            .derive_factor_instances_for(
+                &Account::a0(),
+                &StatelessDummyIndices,
+                Arc::new(TestDerivationInteractors::fail()),
+            )
+            .await;
+        assert_eq!(result, Err(CommonError::FailedToDeriveFactorInstances));
+    }
+
+    #[actix_rt::test]
+    async fn matrix_of_factor_instances_from_cache() {
+        let (fs1, fs3) = (fs_at(1), fs_at(3));
+        let matrix = MatrixOfFactorSources::new([fs1.clone()], 1, [fs3.clone()]).unwrap();
+        let cache = FactorInstancesCache::new(2);
+        cache
+            .refill(
+                &IndexSet::from_iter([fs1.clone(), fs3.clone()]),
+                NetworkID::Mainnet,
+                &StatelessDummyIndices,
+                Arc::new(TestDerivationInteractors::default()),
+            )
+            .await
+            .unwrap();
+
+        let instances = matrix
+            .factor_instances_from_cache_for(
+                &Account::a0(),
+                &cache,
+                &StatelessDummyIndices,
+                Arc::new(TestDerivationInteractors::fail()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            instances.threshold_factors[0].factor.derivation_path(),
+            DerivationPath::account_tx(NetworkID::Mainnet, HDPathComponent::securified(0))
+        );
+        assert_eq!(
+            instances.override_factors[0].factor_source_id,
+            fs3.factor_source_id()
+        );
+        assert_eq!(
+            cache.cached_count(&FactorInstancesCacheKey::new(
+                fs1.factor_source_id(),
+                NetworkID::Mainnet,
+                CAP26EntityKind::Account,
+                KeySpace::Securified
+            )),
+            1
+        );
+    }
+}
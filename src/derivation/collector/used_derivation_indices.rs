@@ -10,7 +10,46 @@ pub enum KeySpace {
     Securified,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+impl KeySpace {
+    /// The key space which `index` is in.
+    pub fn of(index: HDPathComponent) -> Self {
+        if index.value >= BIP32_SECURIFIED_HALF {
+            Self::Securified
+        } else {
+            Self::Unsecurified
+        }
+    }
+
+    /// The first index in this key space.
+    pub fn first_index(&self) -> HDPathValue {
+        match self {
+            Self::Unsecurified => 0,
+            Self::Securified => BIP32_SECURIFIED_HALF,
+        }
+    }
+
+    /// The index after the last index in this key space.
+    pub fn end_index(&self) -> HDPathValue {
+        match self {
+            Self::Unsecurified => BIP32_SECURIFIED_HALF,
+            Self::Securified => BIP32_HARDENED,
+        }
+    }
+
+    /// The range of `count` indices starting at `start`, or
+    /// `Err(CommonError::KeySpaceExhausted)` if it does not fit in this key
+    /// space.
+    pub fn range(&self, start: HDPathValue, count: usize) -> Result<Range<HDPathValue>> {
+        HDPathValue::try_from(count)
+            .ok()
+            .and_then(|count| start.checked_add(count))
+            .filter(|end| start >= self.first_index() && *end <= self.end_index())
+            .map(|end| start..end)
+            .ok_or(CommonError::KeySpaceExhausted)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct CreateNextDerivationPathRequest {
     pub factor_source_id: FactorSourceIDFromHash,
     pub network_id: NetworkID,
@@ -38,10 +77,27 @@ impl CreateNextDerivationPathRequest {
 }
 
 pub trait UsedDerivationIndices {
+    /// Reserves and returns the next `count` indices for `request`.
+    ///
+    /// Returns `Err(CommonError::KeySpaceExhausted)` if the key space of
+    /// `request` does not have `count` indices left.
+    fn reserve_derivation_indices_with_request(
+        &self,
+        request: CreateNextDerivationPathRequest,
+        count: usize,
+    ) -> Result<Range<HDPathValue>>;
+
+    /// Reserves and returns the next index for `request`.
+    ///
+    /// Returns `Err(CommonError::KeySpaceExhausted)` if the key space of
+    /// `request` has no index left.
     fn next_derivation_index_with_request(
         &self,
         request: CreateNextDerivationPathRequest,
-    ) -> HDPathComponent;
+    ) -> Result<HDPathComponent> {
+        self.reserve_derivation_indices_with_request(request, 1)
+            .map(|reserved| HDPathComponent::non_hardened(reserved.start))
+    }
 
     fn next_derivation_index_for(
         &self,
@@ -50,7 +106,7 @@ pub trait UsedDerivationIndices {
         key_kind: CAP26KeyKind,
         entity_kind: CAP26EntityKind,
         key_space: KeySpace,
-    ) -> HDPathComponent {
+    ) -> Result<HDPathComponent> {
         let request = CreateNextDerivationPathRequest::new(
             factor_source_id,
             network_id,
@@ -68,14 +124,19 @@ pub trait UsedDerivationIndices {
         key_kind: CAP26KeyKind,
         entity_kind: CAP26EntityKind,
         key_space: KeySpace,
-    ) -> DerivationPath {
+    ) -> Result<DerivationPath> {
         let index = self.next_derivation_index_for(
             factor_source_id,
             network_id,
             key_kind,
             entity_kind,
             key_space,
-        );
-        DerivationPath::new(network_id, entity_kind, key_kind, index)
+        )?;
+        Ok(DerivationPath::new(
+            network_id,
+            entity_kind,
+            key_kind,
+            index,
+        ))
     }
 }
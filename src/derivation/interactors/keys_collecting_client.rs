@@ -2,11 +2,15 @@ use crate::prelude::*;
 
 pub struct KeysCollectingClient {
     interactor: KeyDerivationInteractor,
+    retry_policy: RetryPolicy,
 }
 
 impl KeysCollectingClient {
-    pub fn new(interactor: KeyDerivationInteractor) -> Self {
-        Self { interactor }
+    pub fn new(interactor: KeyDerivationInteractor, retry_policy: RetryPolicy) -> Self {
+        Self {
+            interactor,
+            retry_policy,
+        }
     }
 
     pub async fn use_factor_sources(
@@ -16,28 +20,96 @@ impl KeysCollectingClient {
     ) -> Result<()> {
         match &self.interactor {
             KeyDerivationInteractor::Parallel(interactor) => {
-                // Prepare the request for the interactor
-                let request = collector.request_for_parallel_interactor(
-                    factor_sources
-                        .into_iter()
-                        .map(|f| f.factor_source_id())
-                        .collect(),
-                );
-                let response = interactor.derive(request).await?;
-                collector.process_batch_response(response);
+                let mut remaining = factor_sources
+                    .into_iter()
+                    .map(|f| f.factor_source_id())
+                    .collect::<IndexSet<_>>();
+                let mut attempt = 1;
+                while let Some(kind) = remaining.first().map(|f| f.kind) {
+                    // Prepare the request for the interactor
+                    let request = collector.request_for_parallel_interactor(remaining.clone());
+                    let mut response = match interactor.derive(request).await {
+                        Ok(response) => response,
+                        Err(error) => match self
+                            .retry_policy
+                            .after_failure(kind, &remaining, &error, attempt)
+                            .await
+                        {
+                            AfterFailure::Retry => {
+                                attempt += 1;
+                                continue;
+                            }
+                            AfterFailure::Skip => {
+                                ParallelBatchDerivationResponse::skipped(remaining)
+                            }
+                            AfterFailure::Fail => return Err(error),
+                        },
+                    };
+
+                    // Only the factor sources which failed are retried.
+                    let mut retry = IndexSet::new();
+                    for (id, outcome) in response.per_factor_source.iter_mut() {
+                        let FactorSourceOutcome::Failed(error) = outcome else {
+                            continue;
+                        };
+                        match self
+                            .retry_policy
+                            .after_failure(kind, &IndexSet::just(*id), error, attempt)
+                            .await
+                        {
+                            AfterFailure::Retry => _ = retry.insert(*id),
+                            AfterFailure::Skip => *outcome = FactorSourceOutcome::Skipped,
+                            AfterFailure::Fail => {}
+                        }
+                    }
+                    response
+                        .per_factor_source
+                        .retain(|id, _| !retry.contains(id));
+                    collector.process_parallel_batch_response(response)?;
+
+                    remaining = retry;
+                    attempt += 1;
+                }
             }
 
             KeyDerivationInteractor::Serial(interactor) => {
                 for factor_source in factor_sources {
-                    // Prepare the request for the interactor
-                    let request =
-                        collector.request_for_serial_interactor(&factor_source.factor_source_id());
+                    let factor_source_id = factor_source.factor_source_id();
 
-                    // Produce the results from the interactor
-                    let response = interactor.derive(request).await?;
+                    // Prepare the request for the interactor, reused if we retry.
+                    let request = collector.request_for_serial_interactor(&factor_source_id);
 
-                    // Report the results back to the collector
-                    collector.process_batch_response(response);
+                    // Produce the results from the interactor
+                    let mut attempt = 1;
+                    loop {
+                        let error = match interactor.derive(request.clone()).await {
+                            Ok(response) => {
+                                // Report the results back to the collector
+                                collector.process_batch_response(response)?;
+                                break;
+                            }
+                            Err(error) => error,
+                        };
+                        match self
+                            .retry_policy
+                            .after_failure(
+                                factor_source_id.kind,
+                                &IndexSet::just(factor_source_id),
+                                &error,
+                                attempt,
+                            )
+                            .await
+                        {
+                            AfterFailure::Retry => attempt += 1,
+                            AfterFailure::Skip => {
+                                collector.process_parallel_batch_response(
+                                    ParallelBatchDerivationResponse::skipped([factor_source_id]),
+                                )?;
+                                break;
+                            }
+                            AfterFailure::Fail => return Err(error),
+                        }
+                    }
                 }
             }
         }
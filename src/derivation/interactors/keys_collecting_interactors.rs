@@ -1,7 +1,7 @@
 use crate::prelude::*;
 
 /// A collection of "interactors" which can derive keys.
-pub trait KeysCollectingInteractors {
+pub trait KeysCollectingInteractors: Send + Sync {
     fn interactor_for(&self, kind: FactorSourceKind) -> KeyDerivationInteractor;
 }
 
@@ -66,16 +66,47 @@ impl BatchDerivationResponse {
     }
 }
 
+/// The response of a parallel batch derivation request, containing the
+/// outcome of each factor source, since the user might have skipped some of
+/// the factor sources, or some of them might have failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParallelBatchDerivationResponse {
+    pub per_factor_source: IndexMap<
+        FactorSourceIDFromHash,
+        FactorSourceOutcome<IndexSet<HierarchicalDeterministicFactorInstance>>,
+    >,
+}
+impl ParallelBatchDerivationResponse {
+    pub fn new(
+        per_factor_source: IndexMap<
+            FactorSourceIDFromHash,
+            FactorSourceOutcome<IndexSet<HierarchicalDeterministicFactorInstance>>,
+        >,
+    ) -> Self {
+        Self { per_factor_source }
+    }
+
+    /// Every factor source with id in `factor_source_ids` skipped.
+    pub fn skipped(factor_source_ids: impl IntoIterator<Item = FactorSourceIDFromHash>) -> Self {
+        Self::new(
+            factor_source_ids
+                .into_iter()
+                .map(|id| (id, FactorSourceOutcome::Skipped))
+                .collect(),
+        )
+    }
+}
+
 #[async_trait::async_trait]
-pub trait DeriveKeyWithFactorParallelInteractor {
+pub trait DeriveKeyWithFactorParallelInteractor: Send + Sync {
     async fn derive(
         &self,
         request: ParallelBatchKeyDerivationRequest,
-    ) -> Result<BatchDerivationResponse>;
+    ) -> Result<ParallelBatchDerivationResponse>;
 }
 
 #[async_trait::async_trait]
-pub trait DeriveKeyWithFactorSerialInteractor {
+pub trait DeriveKeyWithFactorSerialInteractor: Send + Sync {
     async fn derive(
         &self,
         request: SerialBatchKeyDerivationRequest,
@@ -1,5 +1,7 @@
+mod cache;
 mod collector;
 mod interactors;
 
+pub use cache::*;
 pub use collector::*;
 pub use interactors::*;
@@ -3,12 +3,14 @@
 #![feature(iter_repeat_n)]
 
 mod derivation;
+mod securify;
 mod signing;
 mod testing;
 mod types;
 
 pub mod prelude {
     pub use crate::derivation::*;
+    pub use crate::securify::*;
     pub use crate::signing::*;
     pub use crate::testing::*;
     pub use crate::types::*;
@@ -17,13 +19,13 @@ pub mod prelude {
     pub use derive_getters::Getters;
     pub use indexmap::{IndexMap, IndexSet};
     pub use itertools::Itertools;
-    pub use std::cell::RefCell;
+    pub use serde::{Deserialize, Serialize};
     pub use std::time::SystemTime;
     pub use uuid::Uuid;
 
     pub use std::{
         collections::{BTreeMap, BTreeSet, HashMap, HashSet},
-        sync::Arc,
+        sync::{Arc, RwLock},
     };
 }
 
@@ -0,0 +1,3 @@
+mod pending_securification;
+
+pub use pending_securification::*;
@@ -0,0 +1,281 @@
+use crate::prelude::*;
+
+/// An unsecurified entity for which the factor instances of its new
+/// `SecurityStructureOfFactorInstances`, and its ROLA factor instances, have
+/// been derived, but whose securify
+/// transaction intent has not been signed yet. Until it has been signed the
+/// entity in the profile stays unsecurified, so a failed signing leaves the
+/// profile unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingSecurification<E: IsEntity> {
+    /// The entity, still unsecurified.
+    pub entity: E,
+
+    /// The securified security state the entity will have, with a ROLA
+    /// factor instance registered for each factor source of its structure.
+    pub security_state: EntitySecurityState,
+
+    /// The transaction securifying the entity, which must be signed with
+    /// the current unsecurified factor instance of the entity.
+    pub intent: TransactionIntent,
+}
+
+impl<E: IsEntity> PendingSecurification<E> {
+    /// Derives the factor instances of all roles of `structure` for `entity`,
+    /// and a ROLA factor instance for each of its factor sources, and builds
+    /// the securify transaction intent.
+    ///
+    /// Returns `Err(CommonError::EntityAlreadySecurified)` if `entity` is
+    /// already securified.
+    pub async fn new(
+        entity: E,
+        structure: &SecurityStructureOfFactorSources,
+        used_derivation_indices: &impl UsedDerivationIndices,
+        interactors: Arc<dyn KeysCollectingInteractors>,
+    ) -> Result<Self> {
+        if let EntitySecurityState::Securified { .. } = entity.security_state() {
+            return Err(CommonError::EntityAlreadySecurified);
+        }
+        let security_state = structure
+            .derive_security_state_for(&entity, used_derivation_indices, interactors)
+            .await?;
+        let intent = match entity.address() {
+            AddressOfAccountOrPersona::Account(a) => TransactionIntent::new([a], []),
+            AddressOfAccountOrPersona::Identity(i) => TransactionIntent::new([], [i]),
+        };
+        Ok(Self {
+            entity,
+            security_state,
+            intent,
+        })
+    }
+
+    /// Signs the securify transaction intent with the current unsecurified
+    /// factor instance of the entity in `profile`, and if successful updates
+    /// the entity in `profile` to be securified and returns it.
+    ///
+    /// Returns `Err(CommonError::EntityAlreadySecurified)` if the entity in
+    /// `profile` has been securified in the meantime and
+    /// `Err(CommonError::EntityChanged)` if it has otherwise changed since
+    /// this securification was started, without signing.
+    ///
+    /// Returns `Err(CommonError::SecurifyTransactionNotSigned)` and leaves
+    /// `profile` unchanged if the intent was not signed.
+    pub async fn sign(
+        self,
+        profile: &mut Profile,
+        interactors: Arc<dyn SignatureCollectingInteractors>,
+    ) -> Result<E> {
+        let current = profile.entity_by_address(&self.entity.address())?;
+        if let EntitySecurityState::Securified { .. } = current.security_state() {
+            return Err(CommonError::EntityAlreadySecurified);
+        }
+        if current != self.entity.clone().into() {
+            return Err(CommonError::EntityChanged);
+        }
+        let collector =
+            SignaturesCollector::new(IndexSet::just(self.intent), interactors, profile)?;
+        let outcome = collector.collect_signatures().await;
+        if !outcome.successful() {
+            return Err(CommonError::SecurifyTransactionNotSigned);
+        }
+        let securified = self.entity.with_security_state(self.security_state);
+        profile.update_entity(securified.clone())?;
+        Ok(securified)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn structure() -> SecurityStructureOfFactorSources {
+        SecurityStructure::new(
+            MatrixOfFactorSources::new([fs_at(1), fs_at(3)], 2, [fs_at(4)]).unwrap(),
+            MatrixOfFactorSources::threshold_only([fs_at(1), fs_at(5)], 2).unwrap(),
+            MatrixOfFactorSources::single_override(fs_at(4)).unwrap(),
+        )
+    }
+
+    async fn pending<E: IsEntity>(entity: E) -> Result<PendingSecurification<E>> {
+        PendingSecurification::new(
+            entity,
+            &structure(),
+            &StatelessDummyIndices,
+            Arc::new(TestDerivationInteractors::default()),
+        )
+        .await
+    }
+
+    #[actix_rt::test]
+    async fn securify_account() {
+        let account = Account::a0();
+        let mut profile = Profile::new(HDFactorSource::all(), [&account], []);
+        let pending = pending(account.clone()).await.unwrap();
+        assert_eq!(
+            pending.intent.addresses_of_accounts_requiring_auth(),
+            vec![account.entity_address()]
+        );
+
+        let securified = pending
+            .clone()
+            .sign(
+                &mut profile,
+                Arc::new(TestSignatureCollectingInteractors::new(
+                    SimulatedUser::prudent_no_fail(),
+                )),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(securified.entity_address(), account.entity_address());
+        assert_eq!(securified.security_state(), pending.security_state);
+        let EntitySecurityState::Securified { structure, .. } = pending.security_state else {
+            panic!("Expected securified")
+        };
+        assert_ne!(structure.primary_role, structure.recovery_role);
+        assert_eq!(
+            profile.account_by_address(account.entity_address()),
+            Ok(securified)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn securify_persona() {
+        let persona = Persona::p0();
+        let mut profile = Profile::new(HDFactorSource::all(), [], [&persona]);
+        let securified = pending(persona.clone())
+            .await
+            .unwrap()
+            .sign(
+                &mut profile,
+                Arc::new(TestSignatureCollectingInteractors::new(
+                    SimulatedUser::prudent_no_fail(),
+                )),
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            securified.security_state(),
+            EntitySecurityState::Securified { .. }
+        ));
+        assert_eq!(
+            profile.persona_by_address(persona.entity_address()),
+            Ok(securified)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn failed_signing_leaves_profile_unchanged() {
+        let account = Account::a0();
+        let mut profile = Profile::new(HDFactorSource::all(), [&account], []);
+        let result = pending(account.clone())
+            .await
+            .unwrap()
+            .sign(
+                &mut profile,
+                Arc::new(TestSignatureCollectingInteractors::new(
+                    SimulatedUser::prudent_with_failures(
+                        SimulatedFailures::with_simulated_failures([FactorSourceIDFromHash::fs0()]),
+                    ),
+                )),
+            )
+            .await;
+        assert_eq!(result, Err(CommonError::SecurifyTransactionNotSigned));
+        assert_eq!(
+            profile.account_by_address(account.entity_address()),
+            Ok(account)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn securified_entity_cannot_be_securified_again() {
+        assert_eq!(
+            pending(Account::a6()).await,
+            Err(CommonError::EntityAlreadySecurified)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn changed_entity_is_not_signed_nor_updated() {
+        let account = Account::a0();
+        let pending = pending(account.clone()).await.unwrap();
+        let changed = account.with_security_state(EntitySecurityState::unsecured(
+            HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                HDPathComponent::non_hardened(1),
+                FactorSourceIDFromHash::fs0(),
+            ),
+        ));
+        let mut profile = Profile::new(HDFactorSource::all(), [&changed], []);
+        assert_eq!(
+            pending
+                .sign(
+                    &mut profile,
+                    Arc::new(TestSignatureCollectingInteractors::new(
+                        SimulatedUser::prudent_no_fail(),
+                    )),
+                )
+                .await,
+            Err(CommonError::EntityChanged)
+        );
+        assert_eq!(
+            profile.account_by_address(account.entity_address()),
+            Ok(changed)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn entity_securified_in_the_meantime_is_not_updated() {
+        let account = Account::a0();
+        let pending = pending(account.clone()).await.unwrap();
+        let securified = account.with_security_state(pending.security_state.clone());
+        let mut profile = Profile::new(HDFactorSource::all(), [&securified], []);
+        assert_eq!(
+            pending
+                .sign(
+                    &mut profile,
+                    Arc::new(TestSignatureCollectingInteractors::new(
+                        SimulatedUser::prudent_no_fail(),
+                    )),
+                )
+                .await,
+            Err(CommonError::EntityAlreadySecurified)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn securified_entity_can_authenticate() {
+        let account = Account::a0();
+        let mut profile = Profile::new(HDFactorSource::all(), [&account], []);
+        let securified = pending(account.clone())
+            .await
+            .unwrap()
+            .sign(
+                &mut profile,
+                Arc::new(TestSignatureCollectingInteractors::new(
+                    SimulatedUser::prudent_no_fail(),
+                )),
+            )
+            .await
+            .unwrap();
+        assert_eq!(securified.security_state().rola_factor_instances().len(), 4);
+
+        let proofs = SignaturesCollector::authenticating(
+            AuthIntent::new(AuthChallenge::sample(), [account.entity_address()], []),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+            &profile,
+        )
+        .unwrap()
+        .collect_auth_proofs()
+        .await
+        .unwrap();
+
+        assert_eq!(proofs.len(), 1);
+        assert!(proofs[0]
+            .signatures
+            .iter()
+            .all(|s| s.derivation_path().key_kind == CAP26KeyKind::Rola));
+    }
+}
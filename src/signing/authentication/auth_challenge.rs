@@ -0,0 +1,123 @@
+use crate::prelude::*;
+
+/// A challenge from a dApp which the user answers by signing it with the
+/// ROLA keys of the accounts and personas the user logs in with, proving
+/// ownership of them.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, std::hash::Hash, Debug)]
+pub struct AuthChallenge {
+    /// A random nonce generated by the dApp, making each challenge unique.
+    pub nonce: [u8; 32],
+    /// The origin, i.e. the website, of the dApp.
+    pub origin: String,
+    /// The address of the dApp definition of the dApp.
+    pub dapp_definition_address: AccountAddress,
+}
+
+impl AuthChallenge {
+    /// The prefix of the ROLA payload, the byte of ASCII `R`.
+    const ROLA_PREFIX: u8 = 0x52;
+
+    pub fn new(
+        nonce: [u8; 32],
+        origin: impl AsRef<str>,
+        dapp_definition_address: AccountAddress,
+    ) -> Self {
+        Self {
+            nonce,
+            origin: origin.as_ref().to_owned(),
+            dapp_definition_address,
+        }
+    }
+
+    /// The bytes which are hashed, being the `ROLA_PREFIX` followed by
+    /// the nonce, the length of the dApp definition address, the dApp
+    /// definition address and the origin.
+    pub fn payload(&self) -> Vec<u8> {
+        let dapp_definition_address = self.dapp_definition_address.to_string();
+        let mut payload = vec![Self::ROLA_PREFIX];
+        payload.extend(self.nonce);
+        payload.push(dapp_definition_address.len() as u8);
+        payload.extend(dapp_definition_address.as_bytes());
+        payload.extend(self.origin.as_bytes());
+        payload
+    }
+
+    /// The hash of the `payload` of this challenge, which is what gets signed.
+    pub fn hash(&self) -> AuthIntentHash {
+        AuthIntentHash::new(Hash::digest(self.payload()))
+    }
+}
+
+impl HasSampleValues for AuthChallenge {
+    fn sample() -> Self {
+        Self::new(
+            [0xde; 32],
+            "https://dashboard.radixdlt.com",
+            AccountAddress::sample(),
+        )
+    }
+    fn sample_other() -> Self {
+        Self::new(
+            [0xab; 32],
+            "https://stokenet-dashboard.radixdlt.com",
+            AccountAddress::sample_other(),
+        )
+    }
+}
+
+/// The hash of an `AuthChallenge`, identifying an authentication signing
+/// request, as opposed to an `IntentHash` identifying a transaction signing
+/// request.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, std::hash::Hash, Debug)]
+pub struct AuthIntentHash {
+    hash: Hash,
+}
+
+impl AuthIntentHash {
+    fn new(hash: Hash) -> Self {
+        Self { hash }
+    }
+}
+
+impl SignableID for AuthIntentHash {
+    fn hash_to_sign(&self) -> &Hash {
+        &self.hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = AuthChallenge;
+
+    #[test]
+    fn equality() {
+        assert_eq!(Sut::sample(), Sut::sample());
+        assert_eq!(Sut::sample_other(), Sut::sample_other());
+    }
+
+    #[test]
+    fn inequality() {
+        assert_ne!(Sut::sample(), Sut::sample_other());
+    }
+
+    #[test]
+    fn hash_is_deterministic() {
+        assert_eq!(Sut::sample().hash(), Sut::sample().hash());
+    }
+
+    #[test]
+    fn hash_depends_on_origin() {
+        let mut other = Sut::sample();
+        other.origin = "https://evil.com".to_owned();
+        assert_ne!(Sut::sample().hash(), other.hash());
+    }
+
+    #[test]
+    fn payload_starts_with_rola_prefix() {
+        let payload = Sut::sample().payload();
+        assert_eq!(payload[0], 0x52);
+        assert_eq!(&payload[1..33], &[0xde; 32]);
+    }
+}
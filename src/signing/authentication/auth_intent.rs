@@ -0,0 +1,127 @@
+use crate::prelude::*;
+
+/// A request from a dApp to log in with some accounts and personas, which
+/// is answered by signing the `challenge` with the ROLA keys of each entity.
+#[derive(Clone, PartialEq, Eq, std::hash::Hash, Debug)]
+pub struct AuthIntent {
+    pub challenge: AuthChallenge,
+    pub accounts: Vec<AccountAddress>,
+    pub personas: Vec<IdentityAddress>,
+}
+
+impl AuthIntent {
+    pub fn new(
+        challenge: AuthChallenge,
+        accounts: impl IntoIterator<Item = AccountAddress>,
+        personas: impl IntoIterator<Item = IdentityAddress>,
+    ) -> Self {
+        Self {
+            challenge,
+            accounts: accounts.into_iter().collect_vec(),
+            personas: personas.into_iter().collect_vec(),
+        }
+    }
+}
+
+impl Signable for AuthIntent {
+    type ID = AuthIntentHash;
+
+    fn get_id(&self) -> AuthIntentHash {
+        self.challenge.hash()
+    }
+
+    fn addresses_of_accounts_requiring_auth(&self) -> Vec<AccountAddress> {
+        self.accounts.clone()
+    }
+
+    fn addresses_of_personas_requiring_auth(&self) -> Vec<IdentityAddress> {
+        self.personas.clone()
+    }
+}
+
+impl TXToSign<AuthIntentHash> {
+    /// The entities of `intent` found in `profile`, using their registered
+    /// ROLA factor instances instead of their transaction signing ones.
+    ///
+    /// Returns `Err(CommonError::UnknownFactorInstance)` if any entity has
+    /// no ROLA factor instance registered for one of its factor sources.
+    pub fn extracting_rola_from_auth_intent_and_profile(
+        intent: &AuthIntent,
+        profile: &Profile,
+    ) -> Result<Self> {
+        let tx = Self::extracting_from_signable_and_profile(intent, profile)?;
+        let entities = tx
+            .entities_requiring_auth()
+            .into_iter()
+            .map(|e| e.with_key_kind(CAP26KeyKind::Rola))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::with(tx.payload_id.clone(), entities))
+    }
+}
+
+impl SignaturesCollector<AuthIntentHash> {
+    /// Collects signatures of the challenge of `intent` by the ROLA factor
+    /// instances of its entities in `profile`, using the same `interactors`
+    /// as transaction signing, with requests identified by `AuthIntentHash`.
+    pub fn authenticating(
+        intent: AuthIntent,
+        interactors: Arc<dyn SignatureCollectingInteractors<AuthIntentHash>>,
+        profile: &Profile,
+    ) -> Result<Self> {
+        Self::with_signers_extraction(
+            profile.factor_sources.clone(),
+            IndexSet::just(intent),
+            interactors,
+            |i| TXToSign::extracting_rola_from_auth_intent_and_profile(&i, profile),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = AuthIntent;
+
+    #[test]
+    fn id_is_challenge_hash() {
+        let sut = Sut::new(AuthChallenge::sample(), [], []);
+        assert_eq!(sut.get_id(), AuthChallenge::sample().hash());
+    }
+
+    #[test]
+    fn extracting_uses_rola_factor_instances() {
+        let account = Account::a6();
+        let persona = Persona::p0();
+        let profile = Profile::new(HDFactorSource::all(), [&account], [&persona]);
+        let sut = Sut::new(
+            AuthChallenge::sample(),
+            [account.entity_address()],
+            [persona.entity_address()],
+        );
+        let tx = TXToSign::extracting_rola_from_auth_intent_and_profile(&sut, &profile).unwrap();
+        let entities = tx.entities_requiring_auth();
+        assert_eq!(
+            entities.iter().map(|e| e.address()).collect_vec(),
+            vec![account.address(), persona.address()]
+        );
+        assert!(entities
+            .iter()
+            .flat_map(|e| e.security_state().all_factor_instances())
+            .all(|f| f.derivation_path().key_kind == CAP26KeyKind::Rola));
+    }
+
+    #[test]
+    fn extracting_unknown_account_fails() {
+        let profile = Profile::new(HDFactorSource::all(), [], []);
+        let sut = Sut::new(
+            AuthChallenge::sample(),
+            [Account::a0().entity_address()],
+            [],
+        );
+        assert_eq!(
+            TXToSign::extracting_rola_from_auth_intent_and_profile(&sut, &profile),
+            Err(CommonError::UnknownAccount)
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::prelude::*;
+
+/// A proof that `owner` controls its ROLA keys, being signatures of an
+/// `AuthChallenge` by the ROLA factor instances of `owner`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AuthProof {
+    pub owner: AddressOfAccountOrPersona,
+    pub signatures: IndexSet<HDSignature<AuthIntentHash>>,
+}
+
+impl AuthProof {
+    pub fn new(
+        owner: AddressOfAccountOrPersona,
+        signatures: IndexSet<HDSignature<AuthIntentHash>>,
+    ) -> Self {
+        Self { owner, signatures }
+    }
+}
+
+impl SignaturesCollector<AuthIntentHash> {
+    /// Collects signatures of the challenge and returns a proof per entity,
+    /// or `CommonError::FailedToAuthenticate` if any entity failed to sign.
+    pub async fn collect_auth_proofs(&self) -> Result<Vec<AuthProof>> {
+        let outcome = self.collect_signatures().await;
+        if !outcome.successful() {
+            return Err(CommonError::FailedToAuthenticate);
+        }
+        let mut signatures_per_owner =
+            IndexMap::<AddressOfAccountOrPersona, IndexSet<HDSignature<AuthIntentHash>>>::new();
+        for signature in outcome.signatures_of_successful_transactions() {
+            signatures_per_owner
+                .entry(signature.owned_factor_instance().owner.clone())
+                .or_default()
+                .insert(signature);
+        }
+        Ok(signatures_per_owner
+            .into_iter()
+            .map(|(owner, signatures)| AuthProof::new(owner, signatures))
+            .collect_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collector(
+        intent: AuthIntent,
+        profile: &Profile,
+        user: SimulatedUser,
+    ) -> SignaturesCollector<AuthIntentHash> {
+        SignaturesCollector::authenticating(
+            intent,
+            Arc::new(TestSignatureCollectingInteractors::new(user)),
+            profile,
+        )
+        .unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn proofs_are_signed_with_rola_keys() {
+        let account = Account::a6();
+        let persona = Persona::p0();
+        let profile = Profile::new(HDFactorSource::all(), [&account], [&persona]);
+        let challenge = AuthChallenge::sample();
+        let collector = collector(
+            AuthIntent::new(
+                challenge.clone(),
+                [account.entity_address()],
+                [persona.entity_address()],
+            ),
+            &profile,
+            SimulatedUser::prudent_no_fail(),
+        );
+
+        let proofs = collector.collect_auth_proofs().await.unwrap();
+
+        assert_eq!(
+            proofs
+                .iter()
+                .map(|p| p.owner.clone())
+                .collect::<HashSet<_>>(),
+            HashSet::from([account.address(), persona.address()])
+        );
+        assert!(proofs.iter().flat_map(|p| p.signatures.clone()).all(|s| {
+            s.is_valid()
+                && *s.payload_id() == challenge.hash()
+                && s.derivation_path().key_kind == CAP26KeyKind::Rola
+        }));
+    }
+
+    #[actix_rt::test]
+    async fn failing_factor_source_fails_authentication() {
+        let account = Account::a0();
+        let profile = Profile::new(HDFactorSource::all(), [&account], []);
+        let collector = collector(
+            AuthIntent::new(AuthChallenge::sample(), [account.entity_address()], []),
+            &profile,
+            SimulatedUser::prudent_with_failures(SimulatedFailures::with_simulated_failures([
+                FactorSourceIDFromHash::fs0(),
+            ])),
+        );
+
+        assert_eq!(
+            collector.collect_auth_proofs().await,
+            Err(CommonError::FailedToAuthenticate)
+        );
+    }
+}
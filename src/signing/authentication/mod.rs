@@ -0,0 +1,7 @@
+mod auth_challenge;
+mod auth_intent;
+mod auth_proof;
+
+pub use auth_challenge::*;
+pub use auth_intent::*;
+pub use auth_proof::*;
@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::prelude::*;
+
+/// A cheaply cloneable handle which can be used to cancel a signing session,
+/// e.g. when the user closes the signing sheet, or from within an interactor.
+///
+/// All clones share the same underlying flag, so cancelling any clone cancels
+/// the session of the `SignaturesCollector` it was given to.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the signing session, the collector will not prompt the user
+    /// for any more factor sources.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst)
+    }
+
+    /// Returns `true` if `cancel` has been called on this handle or any of
+    /// its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = CancellationHandle;
+
+    #[test]
+    fn not_cancelled_by_default() {
+        assert!(!Sut::new().is_cancelled());
+    }
+
+    #[test]
+    fn cancelling_a_clone_cancels_original() {
+        let sut = Sut::new();
+        sut.clone().cancel();
+        assert!(sut.is_cancelled());
+    }
+}
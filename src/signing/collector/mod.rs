@@ -1,7 +1,13 @@
+mod cancellation_handle;
 mod signatures_collector;
 mod signatures_collector_dependencies;
 mod signatures_collector_preprocessor;
 mod signatures_collector_state;
+mod signing_plan;
+mod signing_progress_observer;
 
+pub use cancellation_handle::*;
 pub use signatures_collector::*;
 pub use signatures_collector_preprocessor::*;
+pub use signing_plan::*;
+pub use signing_progress_observer::*;
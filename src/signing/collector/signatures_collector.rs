@@ -13,23 +13,25 @@ use super::{
 /// By increasing friction order we mean, the quickest and easiest to use FactorSourceKind
 /// is last; namely `DeviceFactorSource`, and the most tedious FactorSourceKind is
 /// first; namely `LedgerFactorSource`, which user might also lack access to.
-pub struct SignaturesCollector {
+pub struct SignaturesCollector<ID: SignableID = IntentHash> {
     /// Stateless immutable values used by the collector to gather signatures
     /// from factor sources.
-    dependencies: SignaturesCollectorDependencies,
+    dependencies: SignaturesCollectorDependencies<ID>,
 
     /// Mutable internal state of the collector which builds up the list
-    /// of signatures from each used factor source.
-    state: RefCell<SignaturesCollectorState>,
+    /// of signatures from each used factor source, behind a lock so that
+    /// the collector is `Send + Sync` and can be used on multi-threaded
+    /// runtimes.
+    state: RwLock<SignaturesCollectorState<ID>>,
 }
 
-impl SignaturesCollector {
+impl<ID: SignableID> SignaturesCollector<ID> {
     /// Used by our tests. But Sargon will typically wanna use `SignaturesCollector::new` and passing
     /// it a
     pub(crate) fn with(
         all_factor_sources_in_profile: IndexSet<HDFactorSource>,
-        transactions: IndexSet<TXToSign>,
-        interactors: Arc<dyn SignatureCollectingInteractors>,
+        transactions: IndexSet<TXToSign<ID>>,
+        interactors: Arc<dyn SignatureCollectingInteractors<ID>>,
     ) -> Self {
         let preprocessor = SignaturesCollectorPreprocessor::new(transactions);
         let (petitions, factors) = preprocessor.preprocess(all_factor_sources_in_profile);
@@ -39,54 +41,205 @@ impl SignaturesCollector {
 
         Self {
             dependencies,
-            state: RefCell::new(state),
+            state: RwLock::new(state),
         }
     }
 
-    pub fn with_signers_extraction<F>(
+    pub fn with_signers_extraction<S, F>(
         all_factor_sources_in_profile: IndexSet<HDFactorSource>,
-        transactions: IndexSet<TransactionIntent>,
-        interactors: Arc<dyn SignatureCollectingInteractors>,
+        transactions: IndexSet<S>,
+        interactors: Arc<dyn SignatureCollectingInteractors<ID>>,
         extract_signers: F,
     ) -> Result<Self>
     where
-        F: Fn(TransactionIntent) -> Result<TXToSign>,
+        S: Signable<ID = ID>,
+        F: Fn(S) -> Result<TXToSign<ID>>,
     {
         let transactions = transactions
             .into_iter()
             .map(extract_signers)
-            .collect::<Result<IndexSet<TXToSign>>>()?;
+            .collect::<Result<IndexSet<TXToSign<ID>>>>()?;
 
         let collector = Self::with(all_factor_sources_in_profile, transactions, interactors);
 
         Ok(collector)
     }
 
-    pub fn new(
-        transactions: IndexSet<TransactionIntent>,
-        interactors: Arc<dyn SignatureCollectingInteractors>,
+    /// Uses `cancellation_handle` to cancel the signing session, instead of
+    /// the one created by default, allowing e.g. interactors and the host app
+    /// to share the same handle.
+    pub fn with_cancellation_handle(mut self, cancellation_handle: CancellationHandle) -> Self {
+        self.dependencies.cancellation_handle = cancellation_handle;
+        self
+    }
+
+    /// Uses factor sources in the order of kinds decided by `policy`, instead
+    /// of `FrictionOrderPolicy::MostFrictionFirst`.
+    pub fn with_friction_order(mut self, policy: FrictionOrderPolicy) -> Self {
+        let factors_of_kind = std::mem::take(&mut self.dependencies.factors_of_kind);
+        self.dependencies.factors_of_kind = policy.sort(factors_of_kind);
+        self
+    }
+
+    /// Retries factor sources which failed according to `policy`, instead of
+    /// giving up on them immediately.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.dependencies.retry_policy = policy;
+        self
+    }
+
+    /// Prompts the user only for the factor sources selected by `policy`,
+    /// instead of `FactorSelectionPolicy::AllReferenced`.
+    pub fn with_factor_selection(mut self, policy: FactorSelectionPolicy) -> Self {
+        self.dependencies.factor_selection = policy;
+        self
+    }
+
+    /// Handles transactions failing according to `policy`, instead of
+    /// `BatchSigningPolicy::BestEffort`.
+    pub fn with_batch_signing_policy(mut self, policy: BatchSigningPolicy) -> Self {
+        self.dependencies.batch_signing = policy;
+        self
+    }
+
+    /// Notifies `observer` about the progress of the session.
+    pub fn with_progress_observer(
+        mut self,
+        observer: Arc<dyn SigningProgressObserver<ID>>,
+    ) -> Self {
+        self.dependencies.progress_observer = Some(observer);
+        self
+    }
+
+    /// A handle which can be used to cancel the signing session, after which
+    /// the collector stops prompting for more factor sources.
+    pub fn cancellation_handle(&self) -> CancellationHandle {
+        self.dependencies.cancellation_handle.clone()
+    }
+
+    /// Collects signatures of `transactions`, which can be any kind of
+    /// `Signable` payload, e.g. `TransactionIntent`s, by the entities in
+    /// `profile` requiring auth.
+    pub fn new<S: Signable<ID = ID>>(
+        transactions: IndexSet<S>,
+        interactors: Arc<dyn SignatureCollectingInteractors<ID>>,
         profile: &Profile,
     ) -> Result<Self> {
         Self::with_signers_extraction(
             profile.factor_sources.clone(),
             transactions,
             interactors,
-            |i| TXToSign::extracting_from_intent_and_profile(&i, profile),
+            |s| TXToSign::extracting_from_signable_and_profile(&s, profile),
         )
     }
+
+    /// Rebuilds a collector from a `snapshot` of an in-progress session,
+    /// which will continue with the factor sources which have neither
+    /// signed nor been skipped yet.
+    ///
+    /// The policies are not part of the snapshot, since e.g. `RetryPolicy`
+    /// holds a hook, so the resumed collector uses the default policies.
+    /// Callers must re-apply every `with_*` builder of the original session,
+    /// i.e. `with_friction_order`, `with_factor_selection`,
+    /// `with_batch_signing_policy` and `with_retry_policy`, otherwise the
+    /// resumed session might have a different outcome.
+    pub fn resume(
+        snapshot: impl AsRef<[u8]>,
+        all_factor_sources_in_profile: IndexSet<HDFactorSource>,
+        interactors: Arc<dyn SignatureCollectingInteractors<ID>>,
+    ) -> Result<Self>
+    where
+        ID: serde::de::DeserializeOwned,
+    {
+        let petitions = Petitions::from_snapshot(snapshot)?;
+
+        let used_factor_sources = petitions.used_factor_sources();
+        let remaining_factor_sources = petitions
+            .factor_to_txid
+            .keys()
+            .filter(|id| !used_factor_sources.contains(*id))
+            .map(|id| {
+                all_factor_sources_in_profile
+                    .iter()
+                    .find(|f| f.factor_source_id() == *id)
+                    .cloned()
+                    .ok_or(CommonError::UnknownFactorSource)
+            })
+            .collect::<Result<HashSet<HDFactorSource>>>()?;
+
+        let factors = sort_group_factors(remaining_factor_sources);
+        let dependencies = SignaturesCollectorDependencies::new(interactors, factors);
+        let state = SignaturesCollectorState::new(petitions);
+
+        Ok(Self {
+            dependencies,
+            state: RwLock::new(state),
+        })
+    }
+
+    /// A snapshot of the full state of the session, in a versioned byte
+    /// format, which can be used to `resume` the session later.
+    pub fn snapshot(&self) -> Vec<u8> {
+        self.state.read().unwrap().petitions.snapshot()
+    }
+
+    /// A preview of which factor sources the user will be asked to sign with,
+    /// in which order, and what is at stake for each of them, without
+    /// calling any interactor.
+    ///
+    /// With `FactorSelectionPolicy::MinimumFriction` only the minimum
+    /// friction set is included, fallbacks are not.
+    pub fn preview(&self) -> SigningPlan<ID> {
+        let factors_of_kind = match self.dependencies.factor_selection {
+            FactorSelectionPolicy::AllReferenced => self
+                .dependencies
+                .factors_of_kind
+                .iter()
+                .cloned()
+                .collect_vec(),
+            FactorSelectionPolicy::MinimumFriction => {
+                self.minimum_friction_factors_of_kind(&IndexSet::new())
+            }
+        };
+        let per_factor_source = factors_of_kind
+            .iter()
+            .flat_map(|f| f.factor_source_ids())
+            .map(|id| (id, self.plan_for_factor_source(&id)))
+            .collect();
+        SigningPlan {
+            factors_of_kind,
+            per_factor_source,
+        }
+    }
+
+    fn plan_for_factor_source(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> FactorSourceSigningPlan<ID> {
+        let entities_per_transaction = self
+            .state
+            .read()
+            .unwrap()
+            .petitions
+            .entities_requiring_factor_source(factor_source_id);
+        FactorSourceSigningPlan {
+            factor_source_id: *factor_source_id,
+            entities_per_transaction,
+            invalid_transactions_if_skipped: self.invalid_transactions_if_skipped(factor_source_id),
+        }
+    }
 }
 
-impl TXToSign {
-    pub fn extracting_from_intent_and_profile(
-        intent: &TransactionIntent,
+impl<ID: SignableID> TXToSign<ID> {
+    pub fn extracting_from_signable_and_profile(
+        signable: &impl Signable<ID = ID>,
         profile: &Profile,
     ) -> Result<Self> {
-        let intent_hash = intent.intent_hash.clone();
-        let summary = intent.manifest_summary();
+        let payload_id = signable.get_id();
         let mut entities_requiring_auth: IndexSet<AccountOrPersona> = IndexSet::new();
 
-        let accounts = summary
-            .addresses_of_accounts_requiring_auth
+        let accounts = signable
+            .addresses_of_accounts_requiring_auth()
             .into_iter()
             .map(|a| profile.account_by_address(a))
             .collect::<Result<Vec<_>>>()?;
@@ -98,8 +251,8 @@ impl TXToSign {
                 .collect_vec(),
         );
 
-        let personas = summary
-            .addresses_of_personas_requiring_auth
+        let personas = signable
+            .addresses_of_personas_requiring_auth()
             .into_iter()
             .map(|a| profile.persona_by_address(a))
             .collect::<Result<Vec<_>>>()?;
@@ -111,24 +264,38 @@ impl TXToSign {
                 .collect_vec(),
         );
 
-        Ok(Self::with(intent_hash, entities_requiring_auth))
+        Ok(Self::with(payload_id, entities_requiring_auth).with_role(signable.role()))
     }
 }
 
-impl SignaturesCollector {
-    /// If all transactions already would fail, or if all transactions already are done, then
-    /// no point in continuing.
+impl<ID: SignableID> SignaturesCollector<ID> {
+    /// If all transactions already would fail, or if all transactions already are done, or
+    /// if the session has been cancelled, then no point in continuing. With
+    /// `BatchSigningPolicy::AllOrNothing` it suffices that any transaction would fail.
     ///
     /// `Ok(true)` means "continue", `Ok(false)` means "stop, we are done". `Err(_)` means "stop, we have failed".
     pub(crate) fn continue_if_necessary(&self) -> Result<bool> {
+        if self.is_cancelled() {
+            return Ok(false);
+        }
         self.state
-            .borrow()
+            .read()
+            .unwrap()
             .petitions
-            .borrow()
-            .continue_if_necessary()
+            .continue_if_necessary(self.dependencies.batch_signing)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.dependencies.cancellation_handle.is_cancelled()
     }
 
-    fn get_interactor(&self, kind: FactorSourceKind) -> SigningInteractor {
+    fn notify(&self, event: impl FnOnce(&dyn SigningProgressObserver<ID>)) {
+        if let Some(observer) = &self.dependencies.progress_observer {
+            event(observer.as_ref())
+        }
+    }
+
+    fn get_interactor(&self, kind: FactorSourceKind) -> SigningInteractor<ID> {
         self.dependencies.interactors.interactor_for(kind)
     }
 
@@ -138,23 +305,92 @@ impl SignaturesCollector {
     ) -> Result<()> {
         let interactor = self.get_interactor(factor_sources_of_kind.kind);
 
-        let client = SignWithFactorClient::new(interactor);
+        self.notify(|o| {
+            o.did_start_kind(
+                factor_sources_of_kind.kind,
+                &factor_sources_of_kind
+                    .factor_source_ids()
+                    .into_iter()
+                    .collect(),
+            )
+        });
+
+        let client = SignWithFactorClient::new(interactor, self.dependencies.retry_policy.clone());
 
-        let result = client
+        // The client marks the factor sources which failed as failed, so
+        // that the outcome tells why transactions failed, and we continue
+        // with the next kind.
+        client
             .use_factor_sources(factor_sources_of_kind.factor_sources(), self)
             .await;
+        Ok(())
+    }
 
-        match result {
-            Ok(_) => {}
-            Err(_) => self.process_batch_response(SignWithFactorSourceOrSourcesOutcome::Skipped {
-                ids_of_skipped_factors_sources: factor_sources_of_kind.factor_source_ids(),
-            }),
+    async fn sign_with_factors(&self) -> Result<()> {
+        match self.dependencies.factor_selection {
+            FactorSelectionPolicy::AllReferenced => self.sign_with_all_factors().await,
+            FactorSelectionPolicy::MinimumFriction => {
+                self.sign_with_minimum_friction_factors().await
+            }
+        }
+    }
+
+    /// The factor sources of the minimum friction set among those which have
+    /// been neither prompted nor used yet, grouped by kind in friction
+    /// order.
+    fn minimum_friction_factors_of_kind(
+        &self,
+        prompted: &IndexSet<FactorSourceIDFromHash>,
+    ) -> Vec<FactorSourcesOfKind> {
+        let state = self.state.read().unwrap();
+        let used = state.petitions.used_factor_sources();
+        let candidates = self
+            .dependencies
+            .factors_of_kind
+            .iter()
+            .flat_map(|f| f.factor_source_ids())
+            .filter(|id| !prompted.contains(id) && !used.contains(id))
+            .collect::<IndexSet<_>>();
+        let Some(selected) = state.petitions.minimum_friction_factor_sources(&candidates) else {
+            return Vec::new();
+        };
+        self.dependencies
+            .factors_of_kind
+            .iter()
+            .filter_map(|f| {
+                FactorSourcesOfKind::new(
+                    f.kind(),
+                    f.factor_sources()
+                        .into_iter()
+                        .filter(|s| selected.contains(&s.factor_source_id())),
+                )
+                .ok()
+            })
+            .collect()
+    }
+
+    /// Prompts only for the minimum friction set of factor sources, one kind
+    /// at a time, re-selecting after each kind so that other factor sources
+    /// are prompted as fallbacks only when needed, e.g. after a skip.
+    async fn sign_with_minimum_friction_factors(&self) -> Result<()> {
+        let mut prompted = IndexSet::new();
+        while self.continue_if_necessary()? {
+            let Some(factor_sources_of_kind) = self
+                .minimum_friction_factors_of_kind(&prompted)
+                .into_iter()
+                .next()
+            else {
+                break;
+            };
+            prompted.extend(factor_sources_of_kind.factor_source_ids());
+            self.sign_with_factors_of_kind(factor_sources_of_kind)
+                .await?;
         }
         Ok(())
     }
 
     /// In decreasing "friction order"
-    async fn sign_with_factors(&self) -> Result<()> {
+    async fn sign_with_all_factors(&self) -> Result<()> {
         let factors_of_kind = self.dependencies.factors_of_kind.clone();
         for factor_sources_of_kind in factors_of_kind.into_iter() {
             if !self.continue_if_necessary()? {
@@ -167,64 +403,76 @@ impl SignaturesCollector {
     }
 }
 
-impl SignaturesCollector {
+impl<ID: SignableID> SignaturesCollector<ID> {
     fn input_for_interactor(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
-    ) -> BatchTXBatchKeySigningRequest {
+    ) -> Option<BatchTXBatchKeySigningRequest<ID>> {
         self.state
-            .borrow()
+            .read()
+            .unwrap()
             .petitions
-            .borrow()
             .input_for_interactor(factor_source_id)
     }
 
+    /// `None` if nothing is left to sign with `factor_source_id`, since all
+    /// entities and transactions needing it have already finished, in
+    /// which case the factor source should not be used at all.
     pub(crate) fn request_for_serial_interactor(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
-    ) -> SerialBatchSigningRequest {
-        let batch_signing_request = self.input_for_interactor(factor_source_id);
+    ) -> Option<SerialBatchSigningRequest<ID>> {
+        let batch_signing_request = self.input_for_interactor(factor_source_id)?;
 
-        SerialBatchSigningRequest::new(
+        Some(SerialBatchSigningRequest::new(
             batch_signing_request,
             self.invalid_transactions_if_skipped(factor_source_id)
                 .into_iter()
                 .collect_vec(),
-        )
+        ))
     }
 
+    /// Only includes the factor sources of `factor_source_ids` which have
+    /// something left to sign, `None` if none of them has.
     pub(crate) fn request_for_parallel_interactor(
         &self,
         factor_source_ids: IndexSet<FactorSourceIDFromHash>,
-    ) -> ParallelBatchSigningRequest {
+    ) -> Option<ParallelBatchSigningRequest<ID>> {
         let per_factor_source = factor_source_ids
-            .clone()
             .iter()
-            .map(|fid| (*fid, self.input_for_interactor(fid)))
-            .collect::<IndexMap<FactorSourceIDFromHash, BatchTXBatchKeySigningRequest>>();
+            .filter_map(|fid| Some((*fid, self.input_for_interactor(fid)?)))
+            .collect::<IndexMap<FactorSourceIDFromHash, BatchTXBatchKeySigningRequest<ID>>>();
 
-        let invalid_transactions_if_skipped =
-            self.invalid_transactions_if_skipped_factor_sources(factor_source_ids);
+        if per_factor_source.is_empty() {
+            return None;
+        }
+
+        let invalid_transactions_if_skipped = self.invalid_transactions_if_skipped_factor_sources(
+            per_factor_source.keys().cloned().collect(),
+        );
 
         // Prepare the request for the interactor
-        ParallelBatchSigningRequest::new(per_factor_source, invalid_transactions_if_skipped)
+        Some(ParallelBatchSigningRequest::new(
+            per_factor_source,
+            invalid_transactions_if_skipped,
+        ))
     }
 
     pub(super) fn invalid_transactions_if_skipped(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
-    ) -> IndexSet<InvalidTransactionIfSkipped> {
+    ) -> IndexSet<InvalidTransactionIfSkipped<ID>> {
         self.state
-            .borrow()
+            .read()
+            .unwrap()
             .petitions
-            .borrow()
             .invalid_transactions_if_skipped(factor_source_id)
     }
 
     fn invalid_transactions_if_skipped_factor_sources(
         &self,
         factor_source_ids: IndexSet<FactorSourceIDFromHash>,
-    ) -> IndexSet<InvalidTransactionIfSkipped> {
+    ) -> IndexSet<InvalidTransactionIfSkipped<ID>> {
         factor_source_ids
             .into_iter()
             .flat_map(|f| self.invalid_transactions_if_skipped(&f))
@@ -233,20 +481,77 @@ impl SignaturesCollector {
 
     pub(crate) fn process_batch_response(
         &self,
-        response: SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>,
-    ) {
-        let state = self.state.borrow_mut();
-        let petitions = state.petitions.borrow_mut();
-        petitions.process_batch_response(response)
+        response: SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse<ID>>,
+    ) -> Result<()> {
+        self.update_petitions(|p| p.process_batch_response(response))
+    }
+
+    pub(crate) fn process_parallel_batch_response(
+        &self,
+        response: ParallelBatchSigningResponse<ID>,
+    ) -> Result<()> {
+        self.update_petitions(|p| p.process_parallel_batch_response(response))
+    }
+
+    fn update_petitions(
+        &self,
+        update: impl FnOnce(&mut Petitions<ID>) -> Result<()>,
+    ) -> Result<()> {
+        let (before, after, result) = {
+            let mut state = self.state.write().unwrap();
+            let before = state.petitions.progress();
+            let result = update(&mut state.petitions);
+            (before, state.petitions.progress(), result)
+        };
+        self.notify_progress(before, after);
+        result
+    }
+
+    fn notify_progress(&self, before: PetitionsProgress<ID>, after: PetitionsProgress<ID>) {
+        self.notify(|o| {
+            after
+                .signed_factor_sources
+                .difference(&before.signed_factor_sources)
+                .for_each(|f| o.factor_source_did_sign(f));
+
+            after
+                .skipped_factor_sources
+                .difference(&before.skipped_factor_sources)
+                .for_each(|f| o.factor_source_was_skipped(f));
+
+            after
+                .failed_factor_sources
+                .iter()
+                .filter(|(f, _)| !before.failed_factor_sources.contains_key(*f))
+                .for_each(|(f, e)| o.factor_source_did_fail(f, e));
+
+            for ((payload_id, entity), status) in after.entities.iter() {
+                if before.entities.get(&(payload_id.clone(), entity.clone())) != Some(status) {
+                    o.entity_status_did_change(payload_id, entity, *status)
+                }
+            }
+
+            for (payload_id, status) in after.transactions.iter() {
+                if let PetitionFactorsStatus::Finished(finished) = status {
+                    if before.transactions.get(payload_id) != Some(status) {
+                        o.transaction_did_finish(payload_id, *finished)
+                    }
+                }
+            }
+        })
     }
 
-    fn outcome(self) -> SignaturesOutcome {
-        let state = self.state.borrow_mut();
-        let petitions = state.petitions.borrow_mut();
-        let expected_number_of_transactions = petitions.txid_to_petition.borrow().len();
-        drop(petitions);
-        drop(state);
-        let outcome = self.state.into_inner().petitions.into_inner().outcome();
+    fn outcome(&self) -> SignaturesOutcome<ID> {
+        let aborted = self.is_cancelled();
+        let petitions = self.state.read().unwrap().petitions.clone();
+        // Only aborted if cancellation stopped us from using more factor sources.
+        let aborted = aborted
+            && matches!(
+                petitions.continue_if_necessary(self.dependencies.batch_signing),
+                Ok(true)
+            );
+        let expected_number_of_transactions = petitions.txid_to_petition.len();
+        let outcome = petitions.outcome(aborted, self.dependencies.batch_signing);
         assert_eq!(
             outcome.failed_transactions().len() + outcome.successful_transactions().len(),
             expected_number_of_transactions
@@ -255,22 +560,24 @@ impl SignaturesCollector {
     }
 }
 
-impl SignaturesCollector {
-    pub async fn collect_signatures(self) -> SignaturesOutcome {
+impl<ID: SignableID> SignaturesCollector<ID> {
+    /// Takes `&self` so that the host app can take a `snapshot` of the
+    /// session, e.g. from another task, while signatures are being collected.
+    pub async fn collect_signatures(&self) -> SignaturesOutcome<ID> {
         _ = self
             .sign_with_factors() // in decreasing "friction order"
             .await
-            .inspect_err(|e| eprintln!("Failed to use factor sources: {:#?}", e));
+            .inspect_err(|e| self.notify(|o| o.did_fail(e)));
 
         self.outcome()
     }
 }
 
 #[cfg(test)]
-impl SignaturesCollector {
+impl<ID: SignableID> SignaturesCollector<ID> {
     /// Used by tests
-    pub(crate) fn petitions(self) -> Petitions {
-        self.state.into_inner().petitions.into_inner()
+    pub(crate) fn petitions(self) -> Petitions<ID> {
+        self.state.into_inner().unwrap().petitions
     }
 }
 
@@ -321,6 +628,1386 @@ mod tests {
         assert!(outcome.successful())
     }
 
+    struct ForgingInteractor;
+
+    #[async_trait::async_trait]
+    impl SignWithFactorSerialInteractor for ForgingInteractor {
+        async fn sign(
+            &self,
+            request: SerialBatchSigningRequest,
+        ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>> {
+            let signatures = request
+                .input
+                .per_transaction
+                .into_iter()
+                .flat_map(|r| r.signature_inputs())
+                .map(|i| HDSignature::produced_signing_with_input_by(i, &EmulatedHDSigner))
+                .collect::<IndexSet<_>>();
+            Ok(SignWithFactorSourceOrSourcesOutcome::signed(
+                BatchSigningResponse::new(IndexMap::from_iter([(
+                    request.input.factor_source_id,
+                    signatures,
+                )])),
+            ))
+        }
+    }
+
+    impl SignatureCollectingInteractors for ForgingInteractor {
+        fn interactor_for(&self, _kind: FactorSourceKind) -> SigningInteractor {
+            SigningInteractor::serial(Arc::new(ForgingInteractor))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn forged_signatures_are_rejected() {
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a0()])),
+            Arc::new(ForgingInteractor),
+        );
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+        assert!(outcome.all_signatures().is_empty());
+        assert!(outcome.skipped_factor_sources().is_empty());
+        assert_eq!(
+            outcome.failed_factor_sources(),
+            IndexMap::<_, _>::from_iter([(
+                FactorSourceIDFromHash::fs0(),
+                CommonError::InvalidSignature
+            )])
+        );
+    }
+
+    /// Signs with the first factor source it is prompted for, and then
+    /// cancels the session, emulating a user closing the signing sheet.
+    struct CancellingInteractor {
+        cancellation_handle: CancellationHandle,
+    }
+
+    #[async_trait::async_trait]
+    impl SignWithFactorSerialInteractor for CancellingInteractor {
+        async fn sign(
+            &self,
+            request: SerialBatchSigningRequest,
+        ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>> {
+            let signatures = request
+                .input
+                .per_transaction
+                .into_iter()
+                .flat_map(|r| r.signature_inputs())
+                .map(HDSignature::produced_signing_with_input)
+                .collect::<IndexSet<_>>();
+            self.cancellation_handle.cancel();
+            Ok(SignWithFactorSourceOrSourcesOutcome::signed(
+                BatchSigningResponse::new(IndexMap::from_iter([(
+                    request.input.factor_source_id,
+                    signatures,
+                )])),
+            ))
+        }
+    }
+
+    impl SignatureCollectingInteractors for CancellingInteractor {
+        fn interactor_for(&self, _kind: FactorSourceKind) -> SigningInteractor {
+            SigningInteractor::serial(Arc::new(CancellingInteractor {
+                cancellation_handle: self.cancellation_handle.clone(),
+            }))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn cancelled_by_interactor_is_aborted_and_keeps_signatures() {
+        let cancellation_handle = CancellationHandle::new();
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a0(), Account::a1()])),
+            Arc::new(CancellingInteractor {
+                cancellation_handle: cancellation_handle.clone(),
+            }),
+        )
+        .with_cancellation_handle(cancellation_handle);
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.aborted());
+        assert!(!outcome.successful());
+        assert!(outcome.skipped_factor_sources().is_empty());
+        // Ledger is used before Device, so only Bob has signed.
+        assert_eq!(
+            outcome
+                .all_signatures()
+                .into_iter()
+                .map(|s| s.owned_factor_instance().owner.clone())
+                .collect_vec(),
+            vec![Account::a1().address()]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn cancelled_before_start_is_aborted() {
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a0()])),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+        );
+        collector.cancellation_handle().cancel();
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.aborted());
+        assert!(outcome.all_signatures().is_empty());
+        assert!(outcome.skipped_factor_sources().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn not_aborted_if_cancelled_when_already_finished() {
+        let cancellation_handle = CancellationHandle::new();
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a0()])),
+            Arc::new(CancellingInteractor {
+                cancellation_handle: cancellation_handle.clone(),
+            }),
+        )
+        .with_cancellation_handle(cancellation_handle.clone());
+        let outcome = collector.collect_signatures().await;
+        assert!(cancellation_handle.is_cancelled());
+        assert!(!outcome.aborted());
+        assert!(outcome.successful());
+    }
+
+    #[test]
+    fn default_friction_order() {
+        use FactorSourceKind::*;
+        let sut = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a6()])),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+        );
+        assert_eq!(
+            sut.dependencies
+                .factors_of_kind
+                .iter()
+                .map(|f| f.kind)
+                .collect_vec(),
+            vec![Ledger, Arculus, Yubikey, Device]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn least_friction_first_signs_with_device_first() {
+        let cancellation_handle = CancellationHandle::new();
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a0(), Account::a1()])),
+            Arc::new(CancellingInteractor {
+                cancellation_handle: cancellation_handle.clone(),
+            }),
+        )
+        .with_cancellation_handle(cancellation_handle)
+        .with_friction_order(FrictionOrderPolicy::LeastFrictionFirst);
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.aborted());
+        // Device is used before Ledger, so only Alice has signed.
+        assert_eq!(
+            outcome
+                .all_signatures()
+                .into_iter()
+                .map(|s| s.owned_factor_instance().owner.clone())
+                .collect_vec(),
+            vec![Account::a0().address()]
+        );
+    }
+
+    fn transactions_for_resume() -> IndexSet<TXToSign> {
+        IndexSet::from_iter([
+            TXToSign::new([Account::a0(), Account::a1()]),
+            TXToSign::new([Account::a6()]),
+            TXToSign::new([Persona::p2(), Persona::p6()]),
+        ])
+    }
+
+    fn prudent_collector(transactions: IndexSet<TXToSign>) -> SignaturesCollector {
+        SignaturesCollector::with(
+            HDFactorSource::all(),
+            transactions,
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+        )
+    }
+
+    /// Snapshot of a session which was cancelled after the first factor source.
+    async fn snapshot_of_interrupted_session(transactions: IndexSet<TXToSign>) -> Vec<u8> {
+        let cancellation_handle = CancellationHandle::new();
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            transactions,
+            Arc::new(CancellingInteractor {
+                cancellation_handle: cancellation_handle.clone(),
+            }),
+        )
+        .with_cancellation_handle(cancellation_handle);
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.aborted());
+        assert!(!outcome.all_signatures().is_empty());
+        collector.snapshot()
+    }
+
+    #[actix_rt::test]
+    async fn resumed_session_has_same_outcome_as_uninterrupted() {
+        let transactions = transactions_for_resume();
+        let uninterrupted = prudent_collector(transactions.clone())
+            .collect_signatures()
+            .await;
+
+        let resumed = SignaturesCollector::resume(
+            snapshot_of_interrupted_session(transactions).await,
+            HDFactorSource::all(),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+        )
+        .unwrap()
+        .collect_signatures()
+        .await;
+
+        assert!(resumed.successful());
+        assert_eq!(resumed, uninterrupted);
+    }
+
+    #[actix_rt::test]
+    async fn resumed_session_does_not_reuse_factor_sources() {
+        let sut = SignaturesCollector::<IntentHash>::resume(
+            snapshot_of_interrupted_session(transactions_for_resume()).await,
+            HDFactorSource::all(),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+        )
+        .unwrap();
+        let remaining = sut
+            .dependencies
+            .factors_of_kind
+            .iter()
+            .flat_map(|f| f.factor_source_ids())
+            .collect::<IndexSet<_>>();
+        // fs1 is the first Ledger, which the interrupted session signed with.
+        assert!(!remaining.contains(&FactorSourceIDFromHash::fs1()));
+        assert!(remaining.contains(&FactorSourceIDFromHash::fs0()));
+    }
+
+    #[test]
+    fn resumed_session_uses_reapplied_policies() {
+        let sut = SignaturesCollector::<IntentHash>::resume(
+            prudent_collector(transactions_for_resume()).snapshot(),
+            HDFactorSource::all(),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+        )
+        .unwrap()
+        .with_friction_order(FrictionOrderPolicy::LeastFrictionFirst)
+        .with_factor_selection(FactorSelectionPolicy::MinimumFriction);
+        assert_eq!(
+            sut.dependencies.factors_of_kind.first().unwrap().kind,
+            FactorSourceKind::Device
+        );
+        assert_eq!(
+            sut.dependencies.factor_selection,
+            FactorSelectionPolicy::MinimumFriction
+        );
+    }
+
+    #[actix_rt::test]
+    async fn resumed_all_or_nothing_session_has_same_outcome_as_uninterrupted() {
+        // The interrupted session signs with the Ledger fs1 of a1, the
+        // resumed one fails with the Device fs0 of a0.
+        let transactions = IndexSet::from_iter([
+            TXToSign::new([Account::a0()]),
+            TXToSign::new([Account::a1()]),
+        ]);
+        let failures =
+            || SimulatedFailures::with_simulated_failures([FactorSourceIDFromHash::fs0()]);
+        let uninterrupted =
+            SignaturesCollector::test_prudent_with_failures(transactions.clone(), failures())
+                .with_batch_signing_policy(BatchSigningPolicy::AllOrNothing)
+                .collect_signatures()
+                .await;
+
+        let resumed = SignaturesCollector::resume(
+            snapshot_of_interrupted_session(transactions).await,
+            HDFactorSource::all(),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_with_failures(failures()),
+            )),
+        )
+        .unwrap()
+        .with_batch_signing_policy(BatchSigningPolicy::AllOrNothing)
+        .collect_signatures()
+        .await;
+
+        assert!(resumed.successful_transactions().is_empty());
+        assert_eq!(resumed.failed_transactions().len(), 2);
+        assert_eq!(resumed, uninterrupted);
+    }
+
+    #[test]
+    fn snapshot_of_new_session_resumes_to_equal_state() {
+        let sut = prudent_collector(transactions_for_resume());
+        let resumed = SignaturesCollector::resume(
+            sut.snapshot(),
+            HDFactorSource::all(),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+        )
+        .unwrap();
+        assert_eq!(resumed.petitions(), sut.petitions());
+    }
+
+    #[test]
+    fn resume_with_unknown_factor_source_fails() {
+        let res = SignaturesCollector::<IntentHash>::resume(
+            prudent_collector(transactions_for_resume()).snapshot(),
+            IndexSet::just(HDFactorSource::fs0()),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+        );
+        assert!(matches!(res, Err(CommonError::UnknownFactorSource)));
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    enum ProgressEvent {
+        StartKind(FactorSourceKind, IndexSet<FactorSourceIDFromHash>),
+        Signed(FactorSourceIDFromHash),
+        Skipped(FactorSourceIDFromHash),
+        FactorSourceFailed(FactorSourceIDFromHash, CommonError),
+        EntityStatus(AddressOfAccountOrPersona, PetitionFactorsStatus),
+        TransactionFinished(PetitionFactorsStatusFinished),
+        Failed(CommonError),
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: std::sync::Mutex<Vec<ProgressEvent>>,
+    }
+
+    impl RecordingObserver {
+        fn record(&self, event: ProgressEvent) {
+            self.events.lock().unwrap().push(event)
+        }
+
+        fn events(&self) -> Vec<ProgressEvent> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    impl SigningProgressObserver for RecordingObserver {
+        fn did_start_kind(
+            &self,
+            kind: FactorSourceKind,
+            factor_source_ids: &IndexSet<FactorSourceIDFromHash>,
+        ) {
+            self.record(ProgressEvent::StartKind(kind, factor_source_ids.clone()))
+        }
+
+        fn factor_source_did_sign(&self, factor_source_id: &FactorSourceIDFromHash) {
+            self.record(ProgressEvent::Signed(*factor_source_id))
+        }
+
+        fn factor_source_was_skipped(&self, factor_source_id: &FactorSourceIDFromHash) {
+            self.record(ProgressEvent::Skipped(*factor_source_id))
+        }
+
+        fn factor_source_did_fail(
+            &self,
+            factor_source_id: &FactorSourceIDFromHash,
+            error: &CommonError,
+        ) {
+            self.record(ProgressEvent::FactorSourceFailed(
+                *factor_source_id,
+                error.clone(),
+            ))
+        }
+
+        fn entity_status_did_change(
+            &self,
+            _payload_id: &IntentHash,
+            entity: &AddressOfAccountOrPersona,
+            status: PetitionFactorsStatus,
+        ) {
+            self.record(ProgressEvent::EntityStatus(entity.clone(), status))
+        }
+
+        fn transaction_did_finish(
+            &self,
+            _payload_id: &IntentHash,
+            status: PetitionFactorsStatusFinished,
+        ) {
+            self.record(ProgressEvent::TransactionFinished(status))
+        }
+
+        fn did_fail(&self, error: &CommonError) {
+            self.record(ProgressEvent::Failed(error.clone()))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn progress_observer_prudent_user() {
+        use FactorSourceKind::*;
+        use PetitionFactorsStatus::*;
+        use PetitionFactorsStatusFinished::*;
+        use ProgressEvent::*;
+
+        let observer = Arc::new(RecordingObserver::default());
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a0(), Account::a1()])),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+        )
+        .with_progress_observer(observer.clone());
+
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful());
+
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let fs1 = FactorSourceIDFromHash::fs1();
+        assert_eq!(
+            observer.events(),
+            vec![
+                StartKind(Ledger, IndexSet::just(fs1)),
+                Signed(fs1),
+                EntityStatus(Account::a1().address(), Finished(Success)),
+                StartKind(Device, IndexSet::just(fs0)),
+                Signed(fs0),
+                EntityStatus(Account::a0().address(), Finished(Success)),
+                TransactionFinished(Success),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn progress_observer_lazy_user() {
+        use FactorSourceKind::*;
+        use PetitionFactorsStatus::*;
+        use PetitionFactorsStatusFinished::*;
+        use ProgressEvent::*;
+
+        let observer = Arc::new(RecordingObserver::default());
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a0()])),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::lazy_always_skip_no_fail(),
+            )),
+        )
+        .with_progress_observer(observer.clone());
+
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+
+        let fs0 = FactorSourceIDFromHash::fs0();
+        assert_eq!(
+            observer.events(),
+            vec![
+                StartKind(Device, IndexSet::just(fs0)),
+                Skipped(fs0),
+                EntityStatus(Account::a0().address(), Finished(Fail)),
+                TransactionFinished(Fail),
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn progress_observer_failing_factor_source() {
+        use FactorSourceKind::*;
+        use PetitionFactorsStatus::*;
+        use PetitionFactorsStatusFinished::*;
+        use ProgressEvent::*;
+
+        let observer = Arc::new(RecordingObserver::default());
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let collector = SignaturesCollector::test_prudent_with_failures(
+            [TXToSign::new([Account::a0()])],
+            SimulatedFailures::with_simulated_failures([fs0]),
+        )
+        .with_progress_observer(observer.clone());
+
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+
+        assert_eq!(
+            observer.events(),
+            vec![
+                StartKind(Device, IndexSet::just(fs0)),
+                FactorSourceFailed(fs0, CommonError::Failure),
+                EntityStatus(Account::a0().address(), Finished(Fail)),
+                TransactionFinished(Fail),
+            ]
+        );
+    }
+
+    fn account_with_distinct_roles() -> Account {
+        let fi = |id: FactorSourceIDFromHash| {
+            HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                HDPathComponent::securified(0),
+                id,
+            )
+        };
+        Account::new(
+            "Roles",
+            SecurityStructure::new(
+                MatrixOfFactorInstances::single_threshold(fi(FactorSourceIDFromHash::fs0()))
+                    .unwrap(),
+                MatrixOfFactorInstances::single_threshold(fi(FactorSourceIDFromHash::fs1()))
+                    .unwrap(),
+                MatrixOfFactorInstances::single_override(fi(FactorSourceIDFromHash::fs5()))
+                    .unwrap(),
+            ),
+        )
+    }
+
+    async fn factor_sources_signing_with_role(role: RoleKind) -> IndexSet<FactorSourceIDFromHash> {
+        let collector = SignaturesCollector::test_prudent([TXToSign::new([
+            account_with_distinct_roles(),
+        ])
+        .with_role(role)]);
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful());
+        outcome
+            .all_signatures()
+            .into_iter()
+            .map(|s| s.factor_source_id())
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn primary_role_is_used_by_default() {
+        let collector =
+            SignaturesCollector::test_prudent([TXToSign::new([account_with_distinct_roles()])]);
+        let outcome = collector.collect_signatures().await;
+        assert_eq!(
+            outcome
+                .all_signatures()
+                .into_iter()
+                .map(|s| s.factor_source_id())
+                .collect::<IndexSet<_>>(),
+            IndexSet::just(FactorSourceIDFromHash::fs0())
+        );
+    }
+
+    #[actix_rt::test]
+    async fn signs_with_factors_of_requested_role() {
+        assert_eq!(
+            factor_sources_signing_with_role(RoleKind::Primary).await,
+            IndexSet::just(FactorSourceIDFromHash::fs0())
+        );
+        assert_eq!(
+            factor_sources_signing_with_role(RoleKind::Recovery).await,
+            IndexSet::just(FactorSourceIDFromHash::fs1())
+        );
+        assert_eq!(
+            factor_sources_signing_with_role(RoleKind::Confirmation).await,
+            IndexSet::just(FactorSourceIDFromHash::fs5())
+        );
+    }
+
+    #[actix_rt::test]
+    async fn signs_with_access_rule_tree() {
+        let fi = |id: FactorSourceIDFromHash| {
+            HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                HDPathComponent::securified(0),
+                id,
+            )
+        };
+        let entity = Account::new(
+            "Enterprise",
+            AccessRule::AnyOf(vec![
+                AccessRule::all_of(
+                    [FactorSourceIDFromHash::fs1(), FactorSourceIDFromHash::fs3()].map(fi),
+                ),
+                AccessRule::count_of(
+                    2,
+                    [FactorSourceIDFromHash::fs0(), FactorSourceIDFromHash::fs5()].map(fi),
+                ),
+            ]),
+        );
+        let collector = SignaturesCollector::test_prudent_with_failures(
+            [TXToSign::new([entity])],
+            SimulatedFailures::with_simulated_failures([FactorSourceIDFromHash::fs1()]),
+        );
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful());
+        assert_eq!(
+            outcome
+                .all_signatures()
+                .into_iter()
+                .map(|s| s.factor_source_id())
+                .collect::<IndexSet<_>>(),
+            IndexSet::<FactorSourceIDFromHash>::from_iter([
+                FactorSourceIDFromHash::fs3(),
+                FactorSourceIDFromHash::fs5(),
+                FactorSourceIDFromHash::fs0(),
+            ])
+        );
+    }
+
+    /// A collector for a transaction of `Account::a1` and one of
+    /// `Account::a7`, with Ledger fs1 failing, and the Ledgers used in the
+    /// order `first_ledger`, `second_ledger`.
+    fn collector_with_failing_ledger(
+        first_ledger: FactorSourceIDFromHash,
+        second_ledger: FactorSourceIDFromHash,
+    ) -> SignaturesCollector {
+        let factor_sources = HDFactorSource::all()
+            .into_iter()
+            .map(|mut f| {
+                if f.factor_source_id() == second_ledger {
+                    f.last_used = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1);
+                }
+                f
+            })
+            .collect::<IndexSet<_>>();
+        assert!(factor_sources
+            .iter()
+            .any(|f| f.factor_source_id() == first_ledger));
+        SignaturesCollector::with(
+            factor_sources,
+            IndexSet::from_iter([
+                TXToSign::new([Account::a1()]),
+                TXToSign::new([Account::a7()]),
+            ]),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_with_failures(SimulatedFailures::with_simulated_failures([
+                    FactorSourceIDFromHash::fs1(),
+                ])),
+            )),
+        )
+    }
+
+    fn signed_factor_sources(outcome: &SignaturesOutcome) -> IndexSet<FactorSourceIDFromHash> {
+        outcome
+            .all_signatures()
+            .into_iter()
+            .map(|s| s.factor_source_id())
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn failure_of_serial_interactor_does_not_fail_used_factor_sources() {
+        let (fs1, fs2) = (FactorSourceIDFromHash::fs1(), FactorSourceIDFromHash::fs2());
+        let outcome = collector_with_failing_ledger(fs2, fs1)
+            .collect_signatures()
+            .await;
+
+        assert!(!outcome.successful());
+        assert_eq!(outcome.skipped_factor_sources(), IndexSet::new());
+        assert_eq!(
+            outcome.failed_factor_sources(),
+            IndexMap::<FactorSourceIDFromHash, CommonError>::from_iter([(
+                fs1,
+                CommonError::Failure
+            )])
+        );
+        assert!(signed_factor_sources(&outcome).contains(&fs2));
+        assert_eq!(outcome.successful_transactions().len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn failure_of_serial_interactor_still_uses_other_factor_sources_of_kind() {
+        let (fs1, fs2) = (FactorSourceIDFromHash::fs1(), FactorSourceIDFromHash::fs2());
+        let outcome = collector_with_failing_ledger(fs1, fs2)
+            .collect_signatures()
+            .await;
+
+        assert!(!outcome.successful());
+        assert_eq!(outcome.skipped_factor_sources(), IndexSet::new());
+        assert_eq!(
+            outcome.failed_factor_sources(),
+            IndexMap::<FactorSourceIDFromHash, CommonError>::from_iter([(
+                fs1,
+                CommonError::Failure
+            )])
+        );
+        // The second Ledger is still prompted, and signs.
+        assert!(signed_factor_sources(&outcome).contains(&fs2));
+        assert_eq!(outcome.successful_transactions().len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn best_effort_continues_after_failed_transaction() {
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let fs1 = FactorSourceIDFromHash::fs1();
+        let tx0 = TXToSign::new([Account::a0()]);
+        let tx1 = TXToSign::new([Account::a1()]);
+        let collector = SignaturesCollector::test_prudent_with_failures(
+            [tx0.clone(), tx1.clone()],
+            SimulatedFailures::with_simulated_failures([fs1]),
+        );
+
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+        assert_eq!(
+            outcome
+                .successful_transactions()
+                .into_iter()
+                .map(|t| t.payload_id)
+                .collect_vec(),
+            vec![tx0.payload_id]
+        );
+        assert_eq!(
+            outcome
+                .failed_transactions()
+                .into_iter()
+                .map(|t| t.payload_id)
+                .collect_vec(),
+            vec![tx1.payload_id]
+        );
+        assert_eq!(
+            outcome
+                .all_signatures()
+                .into_iter()
+                .map(|s| s.factor_source_id())
+                .collect_vec(),
+            vec![fs0]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn all_or_nothing_stops_prompting_after_failed_transaction() {
+        let fs1 = FactorSourceIDFromHash::fs1();
+        let collector = SignaturesCollector::test_prudent_with_failures(
+            [
+                TXToSign::new([Account::a0()]),
+                TXToSign::new([Account::a1()]),
+            ],
+            SimulatedFailures::with_simulated_failures([fs1]),
+        )
+        .with_batch_signing_policy(BatchSigningPolicy::AllOrNothing);
+
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+        assert!(outcome.successful_transactions().is_empty());
+        assert_eq!(outcome.failed_transactions().len(), 2);
+        // Device factor source fs0 is never prompted, Ledger fs1 has failed.
+        assert!(outcome.all_signatures().is_empty());
+        assert_eq!(
+            outcome.failed_factor_sources().keys().collect_vec(),
+            vec![&fs1]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn all_or_nothing_fails_already_signed_transactions() {
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let fs1 = FactorSourceIDFromHash::fs1();
+        let collector = SignaturesCollector::test_prudent_with_failures(
+            [
+                TXToSign::new([Account::a0()]),
+                TXToSign::new([Account::a1()]),
+            ],
+            SimulatedFailures::with_simulated_failures([fs0]),
+        )
+        .with_batch_signing_policy(BatchSigningPolicy::AllOrNothing);
+
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful_transactions().is_empty());
+        assert_eq!(outcome.failed_transactions().len(), 2);
+        // Signatures of the transaction signed before the failure are kept.
+        assert_eq!(
+            outcome
+                .all_signatures()
+                .into_iter()
+                .map(|s| s.factor_source_id())
+                .collect_vec(),
+            vec![fs1]
+        );
+    }
+
+    /// Fails the first `failures` times it is asked to sign, then signs,
+    /// recording every request it receives.
+    #[derive(Default)]
+    struct FlakyInteractor {
+        failures: usize,
+
+        /// Whether failures are reported as `Ok(Failed)` instead of `Err`.
+        reports_failures: bool,
+
+        /// Cancelled when failing, if set.
+        cancellation_handle: Option<CancellationHandle>,
+
+        requests: std::sync::Mutex<Vec<BatchTXBatchKeySigningRequest>>,
+    }
+
+    impl FlakyInteractor {
+        fn new(failures: usize) -> Arc<Self> {
+            Arc::new(Self {
+                failures,
+                ..Default::default()
+            })
+        }
+
+        fn reporting_failures(failures: usize) -> Arc<Self> {
+            Arc::new(Self {
+                failures,
+                reports_failures: true,
+                ..Default::default()
+            })
+        }
+
+        fn cancelling(failures: usize, cancellation_handle: CancellationHandle) -> Arc<Self> {
+            Arc::new(Self {
+                failures,
+                cancellation_handle: Some(cancellation_handle),
+                ..Default::default()
+            })
+        }
+
+        fn requests(&self) -> Vec<BatchTXBatchKeySigningRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SignWithFactorSerialInteractor for FlakyInteractor {
+        async fn sign(
+            &self,
+            request: SerialBatchSigningRequest,
+        ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>> {
+            let attempt = {
+                let mut requests = self.requests.lock().unwrap();
+                requests.push(request.input.clone());
+                requests.len()
+            };
+            if attempt <= self.failures {
+                if let Some(cancellation_handle) = &self.cancellation_handle {
+                    cancellation_handle.cancel();
+                }
+                if self.reports_failures {
+                    return Ok(SignWithFactorSourceOrSourcesOutcome::failed(
+                        IndexSet::just(request.input.factor_source_id),
+                        CommonError::Failure,
+                    ));
+                }
+                return Err(CommonError::Failure);
+            }
+            let signatures = request
+                .input
+                .per_transaction
+                .into_iter()
+                .flat_map(|r| r.signature_inputs())
+                .map(HDSignature::produced_signing_with_input)
+                .collect::<IndexSet<_>>();
+            Ok(SignWithFactorSourceOrSourcesOutcome::signed(
+                BatchSigningResponse::new(IndexMap::from_iter([(
+                    request.input.factor_source_id,
+                    signatures,
+                )])),
+            ))
+        }
+    }
+
+    struct FlakyInteractors(Arc<FlakyInteractor>);
+
+    impl SignatureCollectingInteractors for FlakyInteractors {
+        fn interactor_for(&self, _kind: FactorSourceKind) -> SigningInteractor {
+            SigningInteractor::serial(self.0.clone())
+        }
+    }
+
+    struct AlwaysSkip;
+
+    #[async_trait::async_trait]
+    impl RetryOrSkipHook for AlwaysSkip {
+        async fn retry_or_skip(
+            &self,
+            _factor_source_ids: &IndexSet<FactorSourceIDFromHash>,
+            _error: &CommonError,
+            _attempt: usize,
+        ) -> RetryOrSkip {
+            RetryOrSkip::Skip
+        }
+    }
+
+    fn flaky_collector(interactor: Arc<FlakyInteractor>) -> SignaturesCollector {
+        SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a1()])),
+            Arc::new(FlakyInteractors(interactor)),
+        )
+    }
+
+    #[actix_rt::test]
+    async fn retry_policy_retries_with_same_request() {
+        let interactor = FlakyInteractor::new(2);
+        let collector = flaky_collector(interactor.clone())
+            .with_retry_policy(RetryPolicy::new().with_max_attempts(FactorSourceKind::Ledger, 3));
+
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful());
+        assert_eq!(outcome.all_signatures().len(), 1);
+        assert!(outcome.failed_factor_sources().is_empty());
+
+        let requests = interactor.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|r| *r == requests[0]));
+    }
+
+    #[actix_rt::test]
+    async fn retry_policy_gives_up_after_max_attempts() {
+        let interactor = FlakyInteractor::new(2);
+        let collector = flaky_collector(interactor.clone())
+            .with_retry_policy(RetryPolicy::new().with_max_attempts(FactorSourceKind::Ledger, 2));
+
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+        assert_eq!(
+            outcome.failed_factor_sources(),
+            IndexMap::<_, _>::from_iter([(FactorSourceIDFromHash::fs1(), CommonError::Failure)])
+        );
+        assert_eq!(interactor.requests().len(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn retry_policy_hook_can_skip() {
+        let interactor = FlakyInteractor::new(1);
+        let collector = flaky_collector(interactor.clone()).with_retry_policy(
+            RetryPolicy::new()
+                .with_max_attempts(FactorSourceKind::Ledger, 3)
+                .with_hook(Arc::new(AlwaysSkip)),
+        );
+
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+        assert!(outcome.failed_factor_sources().is_empty());
+        assert_eq!(
+            outcome.skipped_factor_sources(),
+            IndexSet::just(FactorSourceIDFromHash::fs1())
+        );
+        assert_eq!(interactor.requests().len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn retry_policy_retries_reported_failures() {
+        let interactor = FlakyInteractor::reporting_failures(2);
+        let collector = flaky_collector(interactor.clone())
+            .with_retry_policy(RetryPolicy::new().with_max_attempts(FactorSourceKind::Ledger, 3));
+
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful());
+        assert!(outcome.failed_factor_sources().is_empty());
+        assert_eq!(interactor.requests().len(), 3);
+    }
+
+    #[actix_rt::test]
+    async fn retry_policy_reported_failure_fails_after_max_attempts() {
+        let interactor = FlakyInteractor::reporting_failures(2);
+        let collector = flaky_collector(interactor.clone())
+            .with_retry_policy(RetryPolicy::new().with_max_attempts(FactorSourceKind::Ledger, 2));
+
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+        assert_eq!(
+            outcome.failed_factor_sources(),
+            IndexMap::<_, _>::from_iter([(FactorSourceIDFromHash::fs1(), CommonError::Failure)])
+        );
+        assert_eq!(interactor.requests().len(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn cancelled_session_is_not_retried() {
+        let cancellation_handle = CancellationHandle::new();
+        let interactor = FlakyInteractor::cancelling(1, cancellation_handle.clone());
+        let collector = flaky_collector(interactor.clone())
+            .with_cancellation_handle(cancellation_handle)
+            .with_retry_policy(
+                RetryPolicy::new()
+                    .with_max_attempts(FactorSourceKind::Ledger, 3)
+                    .with_hook(Arc::new(AlwaysSkip)),
+            );
+
+        // The hook would skip, but is never asked since the session has
+        // been cancelled.
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.skipped_factor_sources().is_empty());
+        assert_eq!(
+            outcome.failed_factor_sources(),
+            IndexMap::<_, _>::from_iter([(FactorSourceIDFromHash::fs1(), CommonError::Failure)])
+        );
+        assert_eq!(interactor.requests().len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn retry_policy_parallel_fails_after_max_attempts() {
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let collector = SignaturesCollector::test_prudent_with_failures(
+            [TXToSign::new([Account::a0()])],
+            SimulatedFailures::with_simulated_failures([fs0]),
+        )
+        .with_retry_policy(RetryPolicy::new().with_max_attempts(FactorSourceKind::Device, 3));
+
+        // Simulated failures are permanent, so all attempts fail.
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+        assert!(outcome.all_signatures().is_empty());
+        assert_eq!(
+            outcome.failed_factor_sources(),
+            IndexMap::<_, _>::from_iter([(fs0, CommonError::Failure)])
+        );
+    }
+
+    #[actix_rt::test]
+    async fn requests_exclude_finished_entities() {
+        let interactor = FlakyInteractor::new(0);
+        // a6 is finished by its override factor Ledger fs1, which a1 also
+        // needs, thus only a0 is left for Device fs0, and the Arculus and
+        // Yubikey factor sources of a6 are not needed at all.
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a1(), Account::a6(), Account::a0()])),
+            Arc::new(FlakyInteractors(interactor.clone())),
+        );
+
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful());
+
+        let requests = interactor.requests();
+        assert_eq!(
+            requests.iter().map(|r| r.factor_source_id).collect_vec(),
+            vec![FactorSourceIDFromHash::fs1(), FactorSourceIDFromHash::fs0()]
+        );
+        assert_eq!(
+            requests[1]
+                .per_transaction
+                .iter()
+                .flat_map(|r| r.signature_inputs())
+                .map(|i| i.owned_factor_instance.owner)
+                .collect_vec(),
+            vec![Account::a0().address()]
+        );
+    }
+
+    #[test]
+    fn preview_does_not_call_interactor() {
+        let interactor = FlakyInteractor::new(0);
+        let tx = TXToSign::new([Account::a0(), Account::a1()]);
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(tx.clone()),
+            Arc::new(FlakyInteractors(interactor.clone())),
+        );
+
+        let plan = collector.preview();
+        assert!(interactor.requests().is_empty());
+
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let fs1 = FactorSourceIDFromHash::fs1();
+        assert_eq!(
+            plan.factors_of_kind.iter().map(|f| f.kind()).collect_vec(),
+            vec![FactorSourceKind::Ledger, FactorSourceKind::Device]
+        );
+        assert_eq!(
+            plan.per_factor_source.keys().cloned().collect_vec(),
+            vec![fs1, fs0]
+        );
+
+        let ledger = plan.per_factor_source.get(&fs1).unwrap();
+        assert_eq!(
+            ledger.entities_per_transaction,
+            IndexMap::<_, _>::from_iter([(
+                tx.payload_id.clone(),
+                IndexSet::just(Account::a1().address())
+            )])
+        );
+        assert_eq!(
+            ledger.invalid_transactions_if_skipped,
+            IndexSet::just(InvalidTransactionIfSkipped::new(
+                tx.payload_id.clone(),
+                [Account::a1().address()]
+            ))
+        );
+    }
+
+    #[test]
+    fn preview_respects_friction_order() {
+        let collector =
+            SignaturesCollector::test_prudent([TXToSign::new([Account::a0(), Account::a1()])])
+                .with_friction_order(FrictionOrderPolicy::LeastFrictionFirst);
+
+        assert_eq!(
+            collector
+                .preview()
+                .factors_of_kind
+                .iter()
+                .map(|f| f.kind())
+                .collect_vec(),
+            vec![FactorSourceKind::Device, FactorSourceKind::Ledger]
+        );
+    }
+
+    #[test]
+    fn preview_lists_transactions_failing_if_skipped() {
+        // a6 is 2/3 threshold with override factors, thus no single factor
+        // source is needed, whereas a7 is 5/5 threshold, thus every one of
+        // its factor sources is needed. They share no factor source.
+        let tx = TXToSign::new([Account::a6(), Account::a7()]);
+        let collector = SignaturesCollector::test_prudent([tx.clone()]);
+
+        let plan = collector.preview();
+        assert_eq!(plan.per_factor_source.len(), 10);
+        for f in plan.per_factor_source.values() {
+            let entities = f.entities_per_transaction.get(&tx.payload_id).unwrap();
+            assert_eq!(entities.len(), 1);
+            if entities.contains(&Account::a7().address()) {
+                assert_eq!(
+                    f.invalid_transactions_if_skipped,
+                    IndexSet::just(InvalidTransactionIfSkipped::new(
+                        tx.payload_id.clone(),
+                        [Account::a7().address()]
+                    ))
+                );
+            } else {
+                assert!(f.invalid_transactions_if_skipped.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn preview_with_minimum_friction_lists_only_selected() {
+        let collector =
+            SignaturesCollector::test_prudent([TXToSign::new([Account::a6(), Account::a1()])])
+                .with_factor_selection(FactorSelectionPolicy::MinimumFriction);
+
+        let plan = collector.preview();
+        assert_eq!(
+            plan.per_factor_source.keys().cloned().collect_vec(),
+            vec![FactorSourceIDFromHash::fs1()]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn minimum_friction_prompts_only_selected() {
+        let collector =
+            SignaturesCollector::test_prudent([TXToSign::new([Account::a6(), Account::a1()])])
+                .with_factor_selection(FactorSelectionPolicy::MinimumFriction);
+
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful());
+        assert_eq!(
+            outcome
+                .all_signatures()
+                .into_iter()
+                .map(|s| s.factor_source_id())
+                .collect::<IndexSet<_>>(),
+            IndexSet::just(FactorSourceIDFromHash::fs1())
+        );
+    }
+
+    #[actix_rt::test]
+    async fn minimum_friction_falls_back_when_selected_fails() {
+        let fs0 = FactorSourceIDFromHash::fs0();
+        let collector = SignaturesCollector::test_prudent_with_failures(
+            [TXToSign::new([Account::a6()])],
+            SimulatedFailures::with_simulated_failures([fs0]),
+        )
+        .with_factor_selection(FactorSelectionPolicy::MinimumFriction);
+
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful());
+        assert_eq!(
+            outcome.failed_factor_sources().keys().collect_vec(),
+            vec![&fs0]
+        );
+
+        // Yubikey fs5 and Device fs0 were selected, after fs0 failed one of
+        // the Arculus cards is the cheapest fallback, Ledger is never used.
+        let kinds = outcome
+            .all_signatures()
+            .into_iter()
+            .map(|s| s.factor_source_id().kind)
+            .sorted()
+            .collect_vec();
+        assert_eq!(
+            kinds,
+            vec![FactorSourceKind::Arculus, FactorSourceKind::Yubikey]
+        );
+    }
+
+    /// Signs every request, but also replays all signatures it has
+    /// produced for earlier requests, i.e. signatures of factor sources
+    /// which have already been used.
+    #[derive(Default)]
+    struct ReplayingInteractor {
+        produced: std::sync::Mutex<IndexMap<FactorSourceIDFromHash, IndexSet<HDSignature>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl SignWithFactorSerialInteractor for ReplayingInteractor {
+        async fn sign(
+            &self,
+            request: SerialBatchSigningRequest,
+        ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>> {
+            let signatures = request
+                .input
+                .per_transaction
+                .into_iter()
+                .flat_map(|r| r.signature_inputs())
+                .map(HDSignature::produced_signing_with_input)
+                .collect::<IndexSet<_>>();
+            let mut produced = self.produced.lock().unwrap();
+            let mut response = produced.clone();
+            response.insert(request.input.factor_source_id, signatures.clone());
+            produced.insert(request.input.factor_source_id, signatures);
+            Ok(SignWithFactorSourceOrSourcesOutcome::signed(
+                BatchSigningResponse::new(response),
+            ))
+        }
+    }
+
+    struct ReplayingInteractors(Arc<ReplayingInteractor>);
+
+    impl SignatureCollectingInteractors for ReplayingInteractors {
+        fn interactor_for(&self, _kind: FactorSourceKind) -> SigningInteractor {
+            SigningInteractor::serial(self.0.clone())
+        }
+    }
+
+    #[actix_rt::test]
+    async fn replayed_signatures_fail_factor_source_instead_of_panicking() {
+        let fs1 = FactorSourceIDFromHash::fs1();
+        let fs2 = FactorSourceIDFromHash::fs2();
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::just(TXToSign::new([Account::a1(), Account::a7()])),
+            Arc::new(ReplayingInteractors(Arc::default())),
+        );
+
+        let outcome = collector.collect_signatures().await;
+        assert!(!outcome.successful());
+
+        // Order of factor sources of same kind is not deterministic, the
+        // first Ledger signs, the second replays the signatures of the first
+        // and thus fails.
+        let failed = outcome.failed_factor_sources();
+        assert_eq!(failed.len(), 1);
+        let (failed_id, error) = failed.first().unwrap();
+        assert!([fs1, fs2].contains(failed_id));
+        assert_eq!(*error, CommonError::FactorSourceAlreadyUsed);
+
+        let signed = outcome
+            .all_signatures()
+            .into_iter()
+            .map(|s| s.factor_source_id())
+            .collect::<IndexSet<_>>();
+        assert_eq!(signed.len(), 1);
+        assert!([fs1, fs2].contains(&signed[0]) && signed[0] != *failed_id);
+    }
+
+    /// Signs every request, but the first response also contains an entry
+    /// for a factor source which was never requested.
+    #[derive(Default)]
+    struct BogusFirstResponseInteractor {
+        responded: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl SignWithFactorSerialInteractor for BogusFirstResponseInteractor {
+        async fn sign(
+            &self,
+            request: SerialBatchSigningRequest,
+        ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>> {
+            let signatures = request
+                .input
+                .per_transaction
+                .into_iter()
+                .flat_map(|r| r.signature_inputs())
+                .map(HDSignature::produced_signing_with_input)
+                .collect::<IndexSet<_>>();
+            let mut response = IndexMap::from_iter([(request.input.factor_source_id, signatures)]);
+            if !self
+                .responded
+                .swap(true, std::sync::atomic::Ordering::SeqCst)
+            {
+                response.insert(FactorSourceIDFromHash::fs9(), IndexSet::new());
+            }
+            Ok(SignWithFactorSourceOrSourcesOutcome::signed(
+                BatchSigningResponse::new(response),
+            ))
+        }
+    }
+
+    struct BogusFirstResponseInteractors(Arc<BogusFirstResponseInteractor>);
+
+    impl SignatureCollectingInteractors for BogusFirstResponseInteractors {
+        fn interactor_for(&self, _kind: FactorSourceKind) -> SigningInteractor {
+            SigningInteractor::serial(self.0.clone())
+        }
+    }
+
+    #[actix_rt::test]
+    async fn bogus_response_fails_only_its_factor_source() {
+        let fs1 = FactorSourceIDFromHash::fs1();
+        let fs2 = FactorSourceIDFromHash::fs2();
+        let collector = SignaturesCollector::with(
+            HDFactorSource::all(),
+            IndexSet::from_iter([
+                TXToSign::new([Account::a1()]),
+                TXToSign::new([Account::unsecurified_mainnet(8, "Hank", fs2)]),
+            ]),
+            Arc::new(BogusFirstResponseInteractors(Arc::default())),
+        );
+
+        let outcome = collector.collect_signatures().await;
+
+        // Order of factor sources of same kind is not deterministic, the
+        // first Ledger fails, the second is still prompted and signs.
+        let failed = outcome.failed_factor_sources();
+        assert_eq!(failed.len(), 1);
+        let (failed_id, error) = failed.first().unwrap();
+        assert!([fs1, fs2].contains(failed_id));
+        assert_eq!(*error, CommonError::UnknownFactorSource);
+        assert_eq!(signed_factor_sources(&outcome).len(), 1);
+        assert!(!signed_factor_sources(&outcome).contains(failed_id));
+        assert_eq!(outcome.successful_transactions().len(), 1);
+    }
+
+    #[test]
+    fn is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SignaturesCollector>();
+        assert_send_sync::<Petitions>();
+        assert_send_sync::<PetitionEntity>();
+        assert_send_sync::<PetitionFactors>();
+        assert_send_sync::<SimulatedUser>();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn collect_signatures_on_multi_threaded_runtime() {
+        let handles = [Account::a0(), Account::a1(), Account::a6(), Account::a7()]
+            .into_iter()
+            .map(|account| {
+                let collector = SignaturesCollector::with(
+                    HDFactorSource::all(),
+                    IndexSet::just(TXToSign::new([account])),
+                    Arc::new(TestSignatureCollectingInteractors::new(
+                        SimulatedUser::prudent_no_fail(),
+                    )),
+                );
+                tokio::spawn(async move { collector.collect_signatures().await })
+            })
+            .collect_vec();
+
+        for handle in handles {
+            let outcome = handle.await.unwrap();
+            assert!(outcome.successful());
+            assert!(outcome.all_signatures().iter().all(|s| s.is_valid()));
+        }
+    }
+
     #[test]
     fn test_profile() {
         let factor_sources = &HDFactorSource::all();
@@ -357,12 +2044,11 @@ mod tests {
 
         let petitions = collector.petitions();
 
-        assert_eq!(petitions.txid_to_petition.borrow().len(), 4);
+        assert_eq!(petitions.txid_to_petition.len(), 4);
 
         {
-            let petitions_ref = petitions.txid_to_petition.borrow();
-            let petition = petitions_ref.get(&t3.intent_hash).unwrap();
-            let for_entities = petition.for_entities.borrow().clone();
+            let petition = petitions.txid_to_petition.get(&t3.intent_hash).unwrap();
+            let for_entities = petition.for_entities.clone();
             let pet6 = for_entities.get(&a6.address()).unwrap();
 
             let paths6 = pet6
@@ -395,41 +2081,33 @@ mod tests {
             AddressOfAccountOrPersona,
             HashSet<FactorSourceIDFromHash>,
         >| {
-            let petitions_ref = petitions.txid_to_petition.borrow();
-            let petition = petitions_ref.get(&t.intent_hash).unwrap();
-            assert_eq!(petition.intent_hash, t.intent_hash);
+            let petition = petitions.txid_to_petition.get(&t.intent_hash).unwrap();
+            assert_eq!(petition.payload_id, t.intent_hash);
 
             let mut addresses = threshold_factors.keys().collect::<HashSet<_>>();
             addresses.extend(override_factors.keys().collect::<HashSet<_>>());
 
             assert_eq!(
-                petition
-                    .for_entities
-                    .borrow()
-                    .keys()
-                    .collect::<HashSet<_>>(),
+                petition.for_entities.keys().collect::<HashSet<_>>(),
                 addresses
             );
 
             assert!(petition
                 .for_entities
-                .borrow()
                 .iter()
                 .all(|(a, p)| { p.entity == *a }));
 
             assert!(petition
                 .for_entities
-                .borrow()
                 .iter()
-                .all(|(_, p)| { p.intent_hash == t.intent_hash }));
+                .all(|(_, p)| { p.payload_id == t.intent_hash }));
 
-            for (k, v) in petition.for_entities.borrow().iter() {
+            for (k, v) in petition.for_entities.iter() {
                 let threshold = threshold_factors.get(k);
                 if let Some(actual_threshold) = &v.threshold_factors {
                     let threshold = threshold.unwrap().clone();
                     assert_eq!(
                         actual_threshold
-                            .borrow()
                             .factor_instances()
                             .into_iter()
                             .map(|f| f.factor_source_id)
@@ -445,7 +2123,6 @@ mod tests {
                     let override_ = override_.unwrap().clone();
                     assert_eq!(
                         actual_override
-                            .borrow()
                             .factor_instances()
                             .into_iter()
                             .map(|f| f.factor_source_id)
@@ -1,8 +1,8 @@
 use crate::prelude::*;
 
-pub(super) struct SignaturesCollectorDependencies {
+pub(super) struct SignaturesCollectorDependencies<ID: SignableID = IntentHash> {
     /// A collection of "interactors" used to sign with factor sources.
-    pub(super) interactors: Arc<dyn SignatureCollectingInteractors>,
+    pub(super) interactors: Arc<dyn SignatureCollectingInteractors<ID>>,
 
     /// Factor sources grouped by kind, sorted according to "friction order",
     /// that is, we want to control which FactorSourceKind users sign with
@@ -14,16 +14,39 @@ pub(super) struct SignaturesCollectorDependencies {
     /// computer and thus unable to make a connection between the Radix Wallet
     /// and a Ledger device.
     pub(super) factors_of_kind: IndexSet<FactorSourcesOfKind>,
+
+    /// Handle used by the host app or interactors to cancel the signing
+    /// session.
+    pub(super) cancellation_handle: CancellationHandle,
+
+    /// Optional observer notified about the progress of the session.
+    pub(super) progress_observer: Option<Arc<dyn SigningProgressObserver<ID>>>,
+
+    /// How many times to retry factor sources which failed.
+    pub(super) retry_policy: RetryPolicy,
+
+    /// Which of the factor sources in `factors_of_kind` to prompt the user
+    /// for.
+    pub(super) factor_selection: FactorSelectionPolicy,
+
+    /// Whether to keep prompting for the other transactions once some
+    /// transaction has failed.
+    pub(super) batch_signing: BatchSigningPolicy,
 }
 
-impl SignaturesCollectorDependencies {
+impl<ID: SignableID> SignaturesCollectorDependencies<ID> {
     pub fn new(
-        interactors: Arc<dyn SignatureCollectingInteractors>,
+        interactors: Arc<dyn SignatureCollectingInteractors<ID>>,
         factors_of_kind: IndexSet<FactorSourcesOfKind>,
     ) -> Self {
         Self {
             interactors,
             factors_of_kind,
+            cancellation_handle: CancellationHandle::new(),
+            progress_observer: None,
+            retry_policy: RetryPolicy::default(),
+            factor_selection: FactorSelectionPolicy::default(),
+            batch_signing: BatchSigningPolicy::default(),
         }
     }
 }
@@ -1,7 +1,7 @@
 use crate::prelude::*;
 
-pub struct SignaturesCollectorPreprocessor {
-    transactions: IndexSet<TXToSign>,
+pub struct SignaturesCollectorPreprocessor<ID: SignableID = IntentHash> {
+    transactions: IndexSet<TXToSign<ID>>,
 }
 
 pub fn sort_group_factors(
@@ -12,41 +12,36 @@ pub fn sort_group_factors(
         .into_grouping_map_by(|x| x.factor_source_kind())
         .collect::<IndexSet<HDFactorSource>>();
 
-    let mut factors_of_kind = factors_of_kind
+    let factors_of_kind = factors_of_kind
         .into_iter()
-        .map(|(k, v)| (k, v.into_iter().sorted().collect::<IndexSet<_>>()))
-        .collect::<IndexMap<FactorSourceKind, IndexSet<HDFactorSource>>>();
+        .map(|(k, v)| FactorSourcesOfKind::new(k, v.into_iter().sorted()).unwrap())
+        .collect::<IndexSet<_>>();
 
-    factors_of_kind.sort_keys();
-
-    factors_of_kind
-        .into_iter()
-        .map(|(k, v)| FactorSourcesOfKind::new(k, v).unwrap())
-        .collect::<IndexSet<_>>()
+    FrictionOrderPolicy::default().sort(factors_of_kind)
 }
 
-impl SignaturesCollectorPreprocessor {
-    pub(super) fn new(transactions: IndexSet<TXToSign>) -> Self {
+impl<ID: SignableID> SignaturesCollectorPreprocessor<ID> {
+    pub(crate) fn new(transactions: IndexSet<TXToSign<ID>>) -> Self {
         Self { transactions }
     }
 
-    pub(super) fn preprocess(
+    pub(crate) fn preprocess(
         self,
         all_factor_sources_in_profile: IndexSet<HDFactorSource>,
-    ) -> (Petitions, IndexSet<FactorSourcesOfKind>) {
+    ) -> (Petitions<ID>, IndexSet<FactorSourcesOfKind>) {
         let transactions = self.transactions;
-        let mut petitions_for_all_transactions = IndexMap::<IntentHash, PetitionTransaction>::new();
+        let mut petitions_for_all_transactions = IndexMap::<ID, PetitionTransaction<ID>>::new();
 
         let all_factor_sources_in_profile = all_factor_sources_in_profile
             .into_iter()
             .map(|f| (f.factor_source_id(), f))
             .collect::<HashMap<FactorSourceIDFromHash, HDFactorSource>>();
 
-        let mut factor_to_payloads = HashMap::<FactorSourceIDFromHash, IndexSet<IntentHash>>::new();
+        let mut factor_to_payloads = HashMap::<FactorSourceIDFromHash, IndexSet<ID>>::new();
 
         let mut used_factor_sources = HashSet::<HDFactorSource>::new();
 
-        let mut use_factor_in_tx = |id: &FactorSourceIDFromHash, txid: &IntentHash| {
+        let mut use_factor_in_tx = |id: &FactorSourceIDFromHash, txid: &ID| {
             if let Some(ref mut txids) = factor_to_payloads.get_mut(id) {
                 txids.insert(txid.clone());
             } else {
@@ -65,36 +60,33 @@ impl SignaturesCollectorPreprocessor {
 
         for transaction in transactions.into_iter() {
             let mut petitions_for_entities =
-                HashMap::<AddressOfAccountOrPersona, PetitionEntity>::new();
+                HashMap::<AddressOfAccountOrPersona, PetitionEntity<ID>>::new();
 
             for entity in transaction.entities_requiring_auth() {
                 let address = entity.address();
                 match entity.security_state() {
-                    EntitySecurityState::Securified(sec) => {
-                        let primary_role_matrix = sec;
-
-                        let mut add = |factors: Vec<HierarchicalDeterministicFactorInstance>| {
-                            factors.into_iter().for_each(|f| {
-                                let factor_source_id = f.factor_source_id;
-                                use_factor_in_tx(&factor_source_id, &transaction.intent_hash);
-                            })
-                        };
-
-                        add(primary_role_matrix.override_factors.clone());
-                        add(primary_role_matrix.threshold_factors.clone());
-                        let petition = PetitionEntity::new_securified(
-                            transaction.intent_hash.clone(),
+                    EntitySecurityState::Securified { structure: sec, .. } => {
+                        let role_rule = sec.role(transaction.role).clone();
+
+                        role_rule.factors().into_iter().for_each(|f| {
+                            use_factor_in_tx(&f.factor_source_id, &transaction.payload_id);
+                        });
+                        let petition = PetitionEntity::new_securified_with_rule(
+                            transaction.payload_id.clone(),
                             address.clone(),
-                            primary_role_matrix,
+                            role_rule,
                         );
                         petitions_for_entities.insert(address.clone(), petition);
                     }
-                    EntitySecurityState::Unsecured(uec) => {
+                    EntitySecurityState::Unsecured {
+                        transaction_signing: uec,
+                        ..
+                    } => {
                         let factor_instance = uec;
                         let factor_source_id = factor_instance.factor_source_id;
-                        use_factor_in_tx(&factor_source_id, &transaction.intent_hash);
+                        use_factor_in_tx(&factor_source_id, &transaction.payload_id);
                         let petition = PetitionEntity::new_unsecurified(
-                            transaction.intent_hash.clone(),
+                            transaction.payload_id.clone(),
                             address.clone(),
                             factor_instance,
                         );
@@ -104,9 +96,9 @@ impl SignaturesCollectorPreprocessor {
             }
 
             let petition_of_tx =
-                PetitionTransaction::new(transaction.intent_hash.clone(), petitions_for_entities);
+                PetitionTransaction::new(transaction.payload_id.clone(), petitions_for_entities);
 
-            petitions_for_all_transactions.insert(transaction.intent_hash, petition_of_tx);
+            petitions_for_all_transactions.insert(transaction.payload_id, petition_of_tx);
         }
 
         let factors_of_kind = sort_group_factors(used_factor_sources);
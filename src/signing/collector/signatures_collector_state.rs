@@ -1,14 +1,12 @@
 use crate::prelude::*;
 
 #[derive(derive_more::Debug)]
-#[debug("{:#?}", petitions.borrow())]
-pub(super) struct SignaturesCollectorState {
-    pub(super) petitions: RefCell<Petitions>,
+#[debug("{:#?}", petitions)]
+pub(super) struct SignaturesCollectorState<ID: SignableID = IntentHash> {
+    pub(super) petitions: Petitions<ID>,
 }
-impl SignaturesCollectorState {
-    pub fn new(petitions: Petitions) -> Self {
-        Self {
-            petitions: RefCell::new(petitions),
-        }
+impl<ID: SignableID> SignaturesCollectorState<ID> {
+    pub fn new(petitions: Petitions<ID>) -> Self {
+        Self { petitions }
     }
 }
@@ -0,0 +1,30 @@
+use crate::prelude::*;
+
+/// A preview of a signing session, telling the host app which factor sources
+/// the user will be asked to sign with and in which order, before any prompt
+/// is shown.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningPlan<ID: SignableID = IntentHash> {
+    /// The factor sources grouped by kind, in the order the collector will
+    /// use them.
+    pub factors_of_kind: Vec<FactorSourcesOfKind>,
+
+    /// What is at stake for each factor source in `factors_of_kind`, in the
+    /// same order.
+    pub per_factor_source: IndexMap<FactorSourceIDFromHash, FactorSourceSigningPlan<ID>>,
+}
+
+/// The transactions and entities which need a signature from a factor source,
+/// and which transactions would fail if the user skipped it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FactorSourceSigningPlan<ID: SignableID = IntentHash> {
+    pub factor_source_id: FactorSourceIDFromHash,
+
+    /// The entities needing a signature from this factor source, per
+    /// transaction.
+    pub entities_per_transaction: IndexMap<ID, IndexSet<AddressOfAccountOrPersona>>,
+
+    /// The transactions which would fail if the user skipped this factor
+    /// source, given that no other factor source has signed before it.
+    pub invalid_transactions_if_skipped: IndexSet<InvalidTransactionIfSkipped<ID>>,
+}
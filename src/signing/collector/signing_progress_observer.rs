@@ -0,0 +1,50 @@
+use crate::prelude::*;
+
+/// An observer of the progress of a signing session, e.g. used by the host
+/// app to show live progress in its UI. All methods have empty default
+/// implementations, so an observer only needs to implement the events it is
+/// interested in.
+pub trait SigningProgressObserver<ID: SignableID = IntentHash>: Send + Sync {
+    /// Called when the collector starts using the factor sources of `kind`,
+    /// i.e. right before the user is prompted for them.
+    fn did_start_kind(
+        &self,
+        _kind: FactorSourceKind,
+        _factor_source_ids: &IndexSet<FactorSourceIDFromHash>,
+    ) {
+    }
+
+    /// Called when the factor source with `factor_source_id` has signed.
+    fn factor_source_did_sign(&self, _factor_source_id: &FactorSourceIDFromHash) {}
+
+    /// Called when the factor source with `factor_source_id` has been
+    /// skipped by the user.
+    fn factor_source_was_skipped(&self, _factor_source_id: &FactorSourceIDFromHash) {}
+
+    /// Called when the factor source with `factor_source_id` has failed,
+    /// either because the interactor failed or because its signatures were
+    /// rejected.
+    fn factor_source_did_fail(
+        &self,
+        _factor_source_id: &FactorSourceIDFromHash,
+        _error: &CommonError,
+    ) {
+    }
+
+    /// Called when the status of `entity` in transaction `payload_id`
+    /// changed to `status`.
+    fn entity_status_did_change(
+        &self,
+        _payload_id: &ID,
+        _entity: &AddressOfAccountOrPersona,
+        _status: PetitionFactorsStatus,
+    ) {
+    }
+
+    /// Called when the transaction `payload_id` has finished, successfully
+    /// if it has enough signatures, or failed if it has become invalid.
+    fn transaction_did_finish(&self, _payload_id: &ID, _status: PetitionFactorsStatusFinished) {}
+
+    /// Called if the session stopped early because of `error`.
+    fn did_fail(&self, _error: &CommonError) {}
+}
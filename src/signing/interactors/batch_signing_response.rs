@@ -5,12 +5,12 @@ use crate::prelude::*;
 /// enough keys (derivation paths) needed for it to be valid when submitted to the
 /// Radix network.
 #[derive(Clone, PartialEq, Eq, derive_more::Debug)]
-#[debug("BatchSigningResponse {{ signatures: {:#?} }}", signatures.values().map(|f| format!("{:#?}", f)).join(", "))]
-pub struct BatchSigningResponse {
-    pub signatures: IndexMap<FactorSourceIDFromHash, IndexSet<HDSignature>>,
+#[debug("BatchSigningResponse<ID> {{ signatures: {:#?} }}", signatures.values().map(|f| format!("{:#?}", f)).join(", "))]
+pub struct BatchSigningResponse<ID: SignableID = IntentHash> {
+    pub signatures: IndexMap<FactorSourceIDFromHash, IndexSet<HDSignature<ID>>>,
 }
-impl BatchSigningResponse {
-    pub fn new(signatures: IndexMap<FactorSourceIDFromHash, IndexSet<HDSignature>>) -> Self {
+impl<ID: SignableID> BatchSigningResponse<ID> {
+    pub fn new(signatures: IndexMap<FactorSourceIDFromHash, IndexSet<HDSignature<ID>>>) -> Self {
         Self { signatures }
     }
 }
@@ -1,12 +1,12 @@
 use crate::prelude::*;
 
 /// A batch of keys (derivation paths) all being factor instances of a HDFactorSource
-/// with id `factor_source_id` to sign a single transaction with, which hash
-/// is `intent_hash`.
+/// with id `factor_source_id` to sign a single payload with, which is
+/// identified by `payload_id`.
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
-pub struct BatchKeySigningRequest {
-    /// Hash to sign
-    intent_hash: IntentHash,
+pub struct BatchKeySigningRequest<ID: SignableID = IntentHash> {
+    /// Identifier of the payload whose hash to sign
+    payload_id: ID,
 
     /// ID of factor to use to sign
     pub factor_source_id: FactorSourceIDFromHash,
@@ -16,17 +16,17 @@ pub struct BatchKeySigningRequest {
     owned_factor_instances: Vec<OwnedFactorInstance>,
 }
 
-impl BatchKeySigningRequest {
-    pub fn signature_inputs(&self) -> IndexSet<HDSignatureInput> {
+impl<ID: SignableID> BatchKeySigningRequest<ID> {
+    pub fn signature_inputs(&self) -> IndexSet<HDSignatureInput<ID>> {
         self.owned_factor_instances
             .clone()
             .into_iter()
-            .map(|fi| HDSignatureInput::new(self.intent_hash.clone(), fi))
+            .map(|fi| HDSignatureInput::new(self.payload_id.clone(), fi))
             .collect()
     }
 
     pub fn new(
-        intent_hash: IntentHash,
+        payload_id: ID,
         factor_source_id: FactorSourceIDFromHash,
         owned_factor_instances: IndexSet<OwnedFactorInstance>,
     ) -> Self {
@@ -34,7 +34,7 @@ impl BatchKeySigningRequest {
             .iter()
             .all(|f| f.by_factor_source(factor_source_id)));
         Self {
-            intent_hash,
+            payload_id,
             factor_source_id,
             owned_factor_instances: owned_factor_instances.into_iter().collect_vec(),
         }
@@ -44,18 +44,18 @@ impl BatchKeySigningRequest {
 /// A batch of transactions each batching over multiple keys (derivation paths)
 /// to sign each transaction with.
 #[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
-pub struct BatchTXBatchKeySigningRequest {
+pub struct BatchTXBatchKeySigningRequest<ID: SignableID = IntentHash> {
     /// The ID of the factor source used to sign each per_transaction
     pub factor_source_id: FactorSourceIDFromHash,
 
     // The `factor_source_id` of each item must match `self.factor_source_id`.
-    pub per_transaction: Vec<BatchKeySigningRequest>,
+    pub per_transaction: Vec<BatchKeySigningRequest<ID>>,
 }
 
-impl BatchTXBatchKeySigningRequest {
+impl<ID: SignableID> BatchTXBatchKeySigningRequest<ID> {
     pub fn new(
         factor_source_id: FactorSourceIDFromHash,
-        per_transaction: IndexSet<BatchKeySigningRequest>,
+        per_transaction: IndexSet<BatchKeySigningRequest<ID>>,
     ) -> Self {
         assert!(per_transaction
             .iter()
@@ -1,6 +1,7 @@
 mod batch_signing_response;
 mod batch_tx_batch_key_signing_request;
 mod parallel_batch_signing_request;
+mod parallel_batch_signing_response;
 mod serial_batch_signing_request;
 mod sign_with_factor_client;
 mod sign_with_factor_parallel_interactor;
@@ -11,6 +12,7 @@ mod signing_interactor;
 pub use batch_signing_response::*;
 pub use batch_tx_batch_key_signing_request::*;
 pub use parallel_batch_signing_request::*;
+pub use parallel_batch_signing_response::*;
 pub use serial_batch_signing_request::*;
 pub use sign_with_factor_client::*;
 pub use sign_with_factor_parallel_interactor::*;
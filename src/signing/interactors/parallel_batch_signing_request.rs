@@ -4,20 +4,20 @@ use crate::prelude::*;
 /// (derivations paths).
 #[derive(derive_more::Debug)]
 #[debug("per_factor_source: {:#?}", per_factor_source)]
-pub struct ParallelBatchSigningRequest {
+pub struct ParallelBatchSigningRequest<ID: SignableID = IntentHash> {
     /// Per factor source, a set of transactions to sign, with
     /// multiple derivations paths.
-    pub per_factor_source: IndexMap<FactorSourceIDFromHash, BatchTXBatchKeySigningRequest>,
+    pub per_factor_source: IndexMap<FactorSourceIDFromHash, BatchTXBatchKeySigningRequest<ID>>,
 
     /// A collection of transactions which would be invalid if the user skips
     /// signing with this factor source.
-    pub invalid_transactions_if_skipped: IndexSet<InvalidTransactionIfSkipped>,
+    pub invalid_transactions_if_skipped: IndexSet<InvalidTransactionIfSkipped<ID>>,
 }
 
-impl ParallelBatchSigningRequest {
+impl<ID: SignableID> ParallelBatchSigningRequest<ID> {
     pub fn new(
-        per_factor_source: IndexMap<FactorSourceIDFromHash, BatchTXBatchKeySigningRequest>,
-        invalid_transactions_if_skipped: IndexSet<InvalidTransactionIfSkipped>,
+        per_factor_source: IndexMap<FactorSourceIDFromHash, BatchTXBatchKeySigningRequest<ID>>,
+        invalid_transactions_if_skipped: IndexSet<InvalidTransactionIfSkipped<ID>>,
     ) -> Self {
         Self {
             per_factor_source,
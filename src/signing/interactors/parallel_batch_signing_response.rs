@@ -0,0 +1,71 @@
+use crate::prelude::*;
+
+/// The response of a parallel batch signing request, containing the outcome
+/// of each factor source, since the user might have signed with some of the
+/// factor sources but skipped others, or some of them might have failed.
+#[derive(Clone, PartialEq, Eq, derive_more::Debug)]
+#[debug(
+    "ParallelBatchSigningResponse<ID> {{ per_factor_source: {:#?} }}",
+    per_factor_source
+)]
+pub struct ParallelBatchSigningResponse<ID: SignableID = IntentHash> {
+    pub per_factor_source:
+        IndexMap<FactorSourceIDFromHash, FactorSourceOutcome<IndexSet<HDSignature<ID>>>>,
+}
+
+impl<ID: SignableID> ParallelBatchSigningResponse<ID> {
+    pub fn new(
+        per_factor_source: IndexMap<
+            FactorSourceIDFromHash,
+            FactorSourceOutcome<IndexSet<HDSignature<ID>>>,
+        >,
+    ) -> Self {
+        Self { per_factor_source }
+    }
+
+    /// Every factor source which produced any of `signatures` was used, the
+    /// signatures are grouped by factor source.
+    pub fn signed(signatures: impl IntoIterator<Item = HDSignature<ID>>) -> Self {
+        Self::new(
+            signatures
+                .into_iter()
+                .into_group_map_by(|s| s.factor_source_id())
+                .into_iter()
+                .map(|(k, v)| (k, FactorSourceOutcome::Used(IndexSet::from_iter(v))))
+                .collect(),
+        )
+    }
+
+    /// Every factor source with id in `factor_source_ids` skipped.
+    pub fn skipped(factor_source_ids: impl IntoIterator<Item = FactorSourceIDFromHash>) -> Self {
+        Self::new(
+            factor_source_ids
+                .into_iter()
+                .map(|id| (id, FactorSourceOutcome::Skipped))
+                .collect(),
+        )
+    }
+
+    /// The outcome of each factor source as a separate response, as if
+    /// returned by a serial interactor.
+    pub(crate) fn outcomes(
+        self,
+    ) -> Vec<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse<ID>>> {
+        self.per_factor_source
+            .into_iter()
+            .map(|(id, outcome)| match outcome {
+                FactorSourceOutcome::Used(signatures) => {
+                    SignWithFactorSourceOrSourcesOutcome::signed(BatchSigningResponse::new(
+                        IndexMap::from_iter([(id, signatures)]),
+                    ))
+                }
+                FactorSourceOutcome::Skipped => {
+                    SignWithFactorSourceOrSourcesOutcome::skipped_factor_source(id)
+                }
+                FactorSourceOutcome::Failed(error) => {
+                    SignWithFactorSourceOrSourcesOutcome::failed(IndexSet::just(id), error)
+                }
+            })
+            .collect()
+    }
+}
@@ -4,19 +4,19 @@ use crate::prelude::*;
 /// a collection of transactions to sign with multiple keys (derivation paths),
 /// and a collection of transactions which would be invalid if the user skips
 /// signing with this factor source.
-#[derive(derive_more::Debug)]
+#[derive(Clone, derive_more::Debug)]
 #[debug("input: {:#?}", input)]
-pub struct SerialBatchSigningRequest {
-    pub input: BatchTXBatchKeySigningRequest,
+pub struct SerialBatchSigningRequest<ID: SignableID = IntentHash> {
+    pub input: BatchTXBatchKeySigningRequest<ID>,
     /// A collection of transactions which would be invalid if the user skips
     /// signing with this factor source.
-    pub invalid_transactions_if_skipped: Vec<InvalidTransactionIfSkipped>,
+    pub invalid_transactions_if_skipped: Vec<InvalidTransactionIfSkipped<ID>>,
 }
 
-impl SerialBatchSigningRequest {
+impl<ID: SignableID> SerialBatchSigningRequest<ID> {
     pub fn new(
-        input: BatchTXBatchKeySigningRequest,
-        invalid_transactions_if_skipped: Vec<InvalidTransactionIfSkipped>,
+        input: BatchTXBatchKeySigningRequest<ID>,
+        invalid_transactions_if_skipped: Vec<InvalidTransactionIfSkipped<ID>>,
     ) -> Self {
         Self {
             input,
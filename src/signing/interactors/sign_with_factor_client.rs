@@ -1,31 +1,110 @@
 use crate::prelude::*;
 
-pub struct SignWithFactorClient {
-    interactor: SigningInteractor,
+pub struct SignWithFactorClient<ID: SignableID = IntentHash> {
+    interactor: SigningInteractor<ID>,
+    retry_policy: RetryPolicy,
 }
 
-impl SignWithFactorClient {
-    pub fn new(interactor: SigningInteractor) -> Self {
-        Self { interactor }
+impl<ID: SignableID> SignWithFactorClient<ID> {
+    pub fn new(interactor: SigningInteractor<ID>, retry_policy: RetryPolicy) -> Self {
+        Self {
+            interactor,
+            retry_policy,
+        }
     }
 
+    /// Uses `factor_sources`, all of the same kind, to sign. Factor sources
+    /// which fail, or whose response cannot be processed, are marked as
+    /// failed and the remaining factor sources are still used.
     pub async fn use_factor_sources(
         &self,
         factor_sources: IndexSet<HDFactorSource>,
-        collector: &SignaturesCollector,
-    ) -> Result<()> {
+        collector: &SignaturesCollector<ID>,
+    ) {
         match &self.interactor {
             // Parallel Interactor: Many Factor Sources at once
             SigningInteractor::Parallel(interactor) => {
-                // Prepare the request for the interactor
-                let request = collector.request_for_parallel_interactor(
-                    factor_sources
-                        .into_iter()
-                        .map(|f| f.factor_source_id())
-                        .collect(),
-                );
-                let response = interactor.sign(request).await?;
-                collector.process_batch_response(response);
+                let mut remaining = factor_sources
+                    .into_iter()
+                    .map(|f| f.factor_source_id())
+                    .collect::<IndexSet<_>>();
+                let mut attempt = 1;
+                while let Some(kind) = remaining.first().map(|f| f.kind) {
+                    // Prepare the request for the interactor, factor sources
+                    // with nothing left to sign are not used at all.
+                    let Some(request) =
+                        collector.request_for_parallel_interactor(remaining.clone())
+                    else {
+                        break;
+                    };
+                    let requested = request
+                        .per_factor_source
+                        .keys()
+                        .cloned()
+                        .collect::<IndexSet<_>>();
+                    let mut response = match interactor.sign(request).await {
+                        Ok(response) => response,
+                        Err(error) => match self
+                            .after_failure(collector, kind, &requested, &error, attempt)
+                            .await
+                        {
+                            AfterFailure::Retry => {
+                                remaining = requested;
+                                attempt += 1;
+                                continue;
+                            }
+                            AfterFailure::Skip => {
+                                ParallelBatchSigningResponse::skipped(requested.clone())
+                            }
+                            AfterFailure::Fail => {
+                                fail(collector, requested, error);
+                                break;
+                            }
+                        },
+                    };
+
+                    // Failed factor sources are handled after the others have
+                    // been processed, so that they are not retried if the
+                    // session is already decided.
+                    let failures = response
+                        .per_factor_source
+                        .iter()
+                        .filter_map(|(id, outcome)| match outcome {
+                            FactorSourceOutcome::Failed(error) => Some((*id, error.clone())),
+                            _ => None,
+                        })
+                        .collect::<IndexMap<_, _>>();
+                    response
+                        .per_factor_source
+                        .retain(|id, _| !failures.contains_key(id));
+                    self.process_parallel(collector, response);
+
+                    // Only the factor sources which failed are retried, so
+                    // that no signature is collected twice.
+                    let mut retry = IndexSet::new();
+                    let mut not_retried = IndexMap::new();
+                    for (id, error) in failures {
+                        match self
+                            .after_failure(collector, kind, &IndexSet::just(id), &error, attempt)
+                            .await
+                        {
+                            AfterFailure::Retry => _ = retry.insert(id),
+                            AfterFailure::Skip => {
+                                _ = not_retried.insert(id, FactorSourceOutcome::Skipped)
+                            }
+                            AfterFailure::Fail => {
+                                _ = not_retried.insert(id, FactorSourceOutcome::Failed(error))
+                            }
+                        }
+                    }
+                    self.process_parallel(
+                        collector,
+                        ParallelBatchSigningResponse::new(not_retried),
+                    );
+
+                    remaining = retry;
+                    attempt += 1;
+                }
             }
 
             // Serial Interactor: One Factor Sources at a time
@@ -34,22 +113,125 @@ impl SignWithFactorClient {
             // to skip the next factor source or not.
             SigningInteractor::Serial(interactor) => {
                 for factor_source in factor_sources {
-                    // Prepare the request for the interactor
-                    let request =
-                        collector.request_for_serial_interactor(&factor_source.factor_source_id());
+                    let factor_source_id = factor_source.factor_source_id();
+
+                    // Prepare the request for the interactor, reused if we retry.
+                    // Factor sources with nothing left to sign are not used at all.
+                    let Some(request) = collector.request_for_serial_interactor(&factor_source_id)
+                    else {
+                        continue;
+                    };
 
                     // Produce the results from the interactor
-                    let response = interactor.sign(request).await?;
+                    let mut attempt = 1;
+                    let response = loop {
+                        // An interactor reporting that the factor source
+                        // failed is retried just like one returning an error.
+                        let (error, failed_response) = match interactor.sign(request.clone()).await
+                        {
+                            Ok(response) => {
+                                let SignWithFactorSourceOrSourcesOutcome::Failed { error, .. } =
+                                    &response
+                                else {
+                                    break response;
+                                };
+                                (error.clone(), Some(response))
+                            }
+                            Err(error) => (error, None),
+                        };
+                        match self
+                            .after_failure(
+                                collector,
+                                factor_source_id.kind,
+                                &IndexSet::just(factor_source_id),
+                                &error,
+                                attempt,
+                            )
+                            .await
+                        {
+                            AfterFailure::Retry => attempt += 1,
+                            AfterFailure::Skip => {
+                                break SignWithFactorSourceOrSourcesOutcome::skipped_factor_source(
+                                    factor_source_id,
+                                )
+                            }
+                            // Only this factor source fails, the others of
+                            // this kind are still used.
+                            AfterFailure::Fail => {
+                                break failed_response.unwrap_or_else(|| {
+                                    SignWithFactorSourceOrSourcesOutcome::failed(
+                                        IndexSet::just(factor_source_id),
+                                        error,
+                                    )
+                                })
+                            }
+                        }
+                    };
 
-                    // Report the results back to the collector
-                    collector.process_batch_response(response);
+                    // Report the results back to the collector, a response
+                    // which cannot be processed fails the factor source.
+                    if let Err(error) = collector.process_batch_response(response) {
+                        fail(collector, IndexSet::just(factor_source_id), error);
+                    }
 
-                    if !collector.continue_if_necessary()? {
+                    // Stop if we are done, or if all transactions already
+                    // would fail, the latter is not an error of the interactor.
+                    if !collector.continue_if_necessary().unwrap_or(false) {
                         break;
                     }
                 }
             }
         }
-        Ok(())
     }
+
+    /// What to do after using `factor_source_ids` failed with `error`. A
+    /// session which has been cancelled, or which is already decided, is
+    /// never retried, nor is the user asked whether to retry.
+    async fn after_failure(
+        &self,
+        collector: &SignaturesCollector<ID>,
+        kind: FactorSourceKind,
+        factor_source_ids: &IndexSet<FactorSourceIDFromHash>,
+        error: &CommonError,
+        attempt: usize,
+    ) -> AfterFailure {
+        if !collector.continue_if_necessary().unwrap_or(false) {
+            return AfterFailure::Fail;
+        }
+        self.retry_policy
+            .after_failure(kind, factor_source_ids, error, attempt)
+            .await
+    }
+
+    /// Processes the outcome of each factor source of `response` on its
+    /// own, failing a factor source whose outcome could not be processed
+    /// without affecting the others.
+    fn process_parallel(
+        &self,
+        collector: &SignaturesCollector<ID>,
+        response: ParallelBatchSigningResponse<ID>,
+    ) {
+        for (id, outcome) in response.per_factor_source {
+            let response = ParallelBatchSigningResponse::new(IndexMap::from_iter([(id, outcome)]));
+            if let Err(error) = collector.process_parallel_batch_response(response) {
+                fail(collector, IndexSet::just(id), error);
+            }
+        }
+    }
+}
+
+/// Marks `factor_source_ids`, the factor sources which were being used when
+/// `error` occurred, as failed, so that the outcome tells why transactions
+/// failed.
+fn fail<ID: SignableID>(
+    collector: &SignaturesCollector<ID>,
+    factor_source_ids: IndexSet<FactorSourceIDFromHash>,
+    error: CommonError,
+) {
+    // Factor sources which have already signed or been skipped, e.g. if the
+    // error was that the interactor responded for them twice, cannot fail.
+    _ = collector.process_batch_response(SignWithFactorSourceOrSourcesOutcome::failed(
+        factor_source_ids,
+        error,
+    ));
 }
@@ -13,15 +13,15 @@ use crate::prelude::*;
 /// but rather we iterate through all mnemonics and derive public keys/
 /// or sign a payload with each of them in sequence
 ///
-/// The user does not have the ability to SKIP a certain factor source,
-/// instead either ALL factor sources are used to sign the transactions
-/// or none.
+/// The response contains the outcome of each factor source, i.e. the user
+/// might sign with some of the factor sources and skip others, e.g. sign with
+/// two out of three Yubikeys, and some might fail.
 ///
 /// Example of a Parallel Batch Signing Driver is that for DeviceFactorSource.
 #[async_trait::async_trait]
-pub trait SignWithFactorParallelInteractor {
+pub trait SignWithFactorParallelInteractor<ID: SignableID = IntentHash>: Send + Sync {
     async fn sign(
         &self,
-        request: ParallelBatchSigningRequest,
-    ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>>;
+        request: ParallelBatchSigningRequest<ID>,
+    ) -> Result<ParallelBatchSigningResponse<ID>>;
 }
@@ -15,9 +15,9 @@ use crate::prelude::*;
 /// questions from different security questions factor sources (in fact we
 /// might not even even allow multiple SecurityQuestionsFactorSources to be used).
 #[async_trait::async_trait]
-pub trait SignWithFactorSerialInteractor {
+pub trait SignWithFactorSerialInteractor<ID: SignableID = IntentHash>: Send + Sync {
     async fn sign(
         &self,
-        request: SerialBatchSigningRequest,
-    ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>>;
+        request: SerialBatchSigningRequest<ID>,
+    ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse<ID>>>;
 }
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
 /// A collection of "interactors" which can sign transactions.
-pub trait SignatureCollectingInteractors {
-    fn interactor_for(&self, kind: FactorSourceKind) -> SigningInteractor;
+pub trait SignatureCollectingInteractors<ID: SignableID = IntentHash>: Send + Sync {
+    fn interactor_for(&self, kind: FactorSourceKind) -> SigningInteractor<ID>;
 }
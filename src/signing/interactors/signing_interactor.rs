@@ -1,17 +1,17 @@
 use crate::prelude::*;
 
 /// An interactor which can sign transactions - either in parallel or serially.
-pub enum SigningInteractor {
-    Parallel(Arc<dyn SignWithFactorParallelInteractor>),
-    Serial(Arc<dyn SignWithFactorSerialInteractor>),
+pub enum SigningInteractor<ID: SignableID = IntentHash> {
+    Parallel(Arc<dyn SignWithFactorParallelInteractor<ID>>),
+    Serial(Arc<dyn SignWithFactorSerialInteractor<ID>>),
 }
 
-impl SigningInteractor {
-    pub fn parallel(interactor: Arc<dyn SignWithFactorParallelInteractor>) -> Self {
+impl<ID: SignableID> SigningInteractor<ID> {
+    pub fn parallel(interactor: Arc<dyn SignWithFactorParallelInteractor<ID>>) -> Self {
         Self::Parallel(interactor)
     }
 
-    pub fn serial(interactor: Arc<dyn SignWithFactorSerialInteractor>) -> Self {
+    pub fn serial(interactor: Arc<dyn SignWithFactorSerialInteractor<ID>>) -> Self {
         Self::Serial(interactor)
     }
 }
@@ -1,11 +1,15 @@
+mod authentication;
 mod collector;
 mod interactors;
 mod petition_types;
+mod signable;
 mod signatures_outcome_types;
 mod tx_to_sign;
 
+pub use authentication::*;
 pub use collector::*;
 pub use interactors::*;
 pub use petition_types::*;
+pub use signable::*;
 pub use signatures_outcome_types::*;
 pub use tx_to_sign::*;
@@ -1,5 +1,7 @@
+use crate::prelude::*;
+
 /// A kind of factor list, either threshold, or override kind.
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
 pub enum FactorListKind {
     Threshold,
     Override,
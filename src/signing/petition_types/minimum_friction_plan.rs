@@ -0,0 +1,247 @@
+use crate::prelude::*;
+
+/// Above this many candidate factor sources we do not search for the exact
+/// minimum friction set, since the search is exponential in the number of
+/// candidates, but fall back to a greedy approximation.
+const MAX_CANDIDATES_FOR_EXACT_SEARCH: usize = 12;
+
+/// A search for the set of candidate factor sources with the lowest total
+/// friction which fulfills all entities.
+struct MinimumFrictionSearch<'a, ID: SignableID> {
+    entities: Vec<&'a PetitionEntity<ID>>,
+
+    /// Sorted by increasing friction, so that cheap sets are found early,
+    /// which prunes more expensive branches.
+    candidates: Vec<FactorSourceIDFromHash>,
+
+    best: Option<(usize, IndexSet<FactorSourceIDFromHash>)>,
+}
+
+impl<ID: SignableID> MinimumFrictionSearch<'_, ID> {
+    fn is_fulfilled_by(&self, factor_source_ids: &IndexSet<FactorSourceIDFromHash>) -> bool {
+        self.entities
+            .iter()
+            .all(|e| e.is_fulfilled_if_signed_by(factor_source_ids))
+    }
+
+    /// Branch and bound search for the exact minimum, which in the worst
+    /// case visits all `2^n` subsets of the `n` candidates.
+    fn exact(mut self) -> Option<IndexSet<FactorSourceIDFromHash>> {
+        self.search(0, &mut IndexSet::new(), 0);
+        self.best.map(|(_, factor_sources)| factor_sources)
+    }
+
+    /// Adds candidates in order of increasing friction until all entities
+    /// are fulfilled, then drops the most expensive ones which turned out
+    /// not to be needed. Needs `O(n)` fulfillment checks of the `n`
+    /// candidates, but the result might not be the minimum.
+    fn greedy(self) -> Option<IndexSet<FactorSourceIDFromHash>> {
+        let mut chosen = IndexSet::new();
+        for candidate in self.candidates.iter() {
+            if self.is_fulfilled_by(&chosen) {
+                break;
+            }
+            chosen.insert(*candidate);
+        }
+        if !self.is_fulfilled_by(&chosen) {
+            return None;
+        }
+        for candidate in chosen.clone().iter().rev() {
+            let mut without = chosen.clone();
+            without.shift_remove(candidate);
+            if self.is_fulfilled_by(&without) {
+                chosen = without;
+            }
+        }
+        Some(chosen)
+    }
+
+    fn search(&mut self, index: usize, chosen: &mut IndexSet<FactorSourceIDFromHash>, cost: usize) {
+        if self.best.as_ref().is_some_and(|(best, _)| cost >= *best) {
+            return;
+        }
+        if self.is_fulfilled_by(chosen) {
+            self.best = Some((cost, chosen.clone()));
+            return;
+        }
+        let mut optimistic = chosen.clone();
+        optimistic.extend(self.candidates[index..].iter().cloned());
+        if !self.is_fulfilled_by(&optimistic) {
+            return;
+        }
+        let candidate = self.candidates[index];
+        chosen.insert(candidate);
+        self.search(index + 1, chosen, cost + candidate.kind.friction());
+        chosen.pop();
+        self.search(index + 1, chosen, cost);
+    }
+}
+
+impl<ID: SignableID> Petitions<ID> {
+    /// The set of factor sources among `candidates` with the lowest total
+    /// friction which, if they all signed, would fulfill every entity in
+    /// every transaction which has not finished yet. This is a weighted set
+    /// cover problem, which we solve exactly, since the number of factor
+    /// sources in a signing session is usually small. The exact search is
+    /// exponential in the number of candidates needed by the unfinished
+    /// entities, so above `MAX_CANDIDATES_FOR_EXACT_SEARCH` of them we use a
+    /// greedy approximation instead.
+    ///
+    /// Returns `None` if not even all `candidates` together can fulfill all
+    /// unfinished entities.
+    pub(crate) fn minimum_friction_factor_sources(
+        &self,
+        candidates: &IndexSet<FactorSourceIDFromHash>,
+    ) -> Option<IndexSet<FactorSourceIDFromHash>> {
+        let search = self.minimum_friction_search(candidates);
+        if search.candidates.len() > MAX_CANDIDATES_FOR_EXACT_SEARCH {
+            search.greedy()
+        } else {
+            search.exact()
+        }
+    }
+
+    /// The unfinished entities and the `candidates` needed by any of them.
+    fn minimum_friction_search(
+        &self,
+        candidates: &IndexSet<FactorSourceIDFromHash>,
+    ) -> MinimumFrictionSearch<'_, ID> {
+        let entities = self
+            .txid_to_petition
+            .values()
+            .flat_map(|p| p.for_entities.values())
+            .filter(|e| e.status() == PetitionFactorsStatus::InProgress)
+            .collect_vec();
+
+        let candidates = candidates
+            .iter()
+            .filter(|id| {
+                entities.iter().any(|e| {
+                    e.all_factor_instances()
+                        .iter()
+                        .any(|f| f.factor_instance().factor_source_id == **id)
+                })
+            })
+            .cloned()
+            .sorted_by_key(|id| id.kind.friction())
+            .collect_vec();
+
+        MinimumFrictionSearch {
+            entities,
+            candidates,
+            best: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type F = FactorSourceIDFromHash;
+
+    fn petitions(entities: impl IntoIterator<Item = Account>) -> Petitions {
+        let preprocessor =
+            SignaturesCollectorPreprocessor::new(IndexSet::just(TXToSign::new(entities)));
+        let (petitions, _) = preprocessor.preprocess(HDFactorSource::all());
+        petitions
+    }
+
+    fn all() -> IndexSet<FactorSourceIDFromHash> {
+        HDFactorSource::all()
+            .into_iter()
+            .map(|f| f.factor_source_id())
+            .collect()
+    }
+
+    #[test]
+    fn threshold_of_cheap_factors_is_preferred_over_expensive_override() {
+        // a6: 2 of Device fs0, Arculus fs3 and Yubikey fs5, or override
+        // Ledger fs1 or Arculus fs4.
+        assert_eq!(
+            petitions([Account::a6()]).minimum_friction_factor_sources(&all()),
+            Some(IndexSet::from_iter([F::fs0(), F::fs5()]))
+        );
+    }
+
+    #[test]
+    fn override_is_chosen_when_cheapest() {
+        let candidates = all()
+            .into_iter()
+            .filter(|f| *f != F::fs0())
+            .collect::<IndexSet<_>>();
+        assert_eq!(
+            petitions([Account::a6()]).minimum_friction_factor_sources(&candidates),
+            Some(IndexSet::just(F::fs4()))
+        );
+    }
+
+    #[test]
+    fn factor_source_needed_by_one_entity_covers_another() {
+        // a1 needs Ledger fs1, which is an override factor of a6.
+        assert_eq!(
+            petitions([Account::a6(), Account::a1()]).minimum_friction_factor_sources(&all()),
+            Some(IndexSet::just(F::fs1()))
+        );
+    }
+
+    #[test]
+    fn all_factors_of_full_threshold_are_chosen() {
+        assert_eq!(
+            petitions([Account::a0(), Account::a7()]).minimum_friction_factor_sources(&all()),
+            Some(IndexSet::from_iter([
+                F::fs0(),
+                F::fs2(),
+                F::fs6(),
+                F::fs7(),
+                F::fs8(),
+                F::fs9()
+            ]))
+        );
+    }
+
+    fn greedy(
+        petitions: &Petitions,
+        candidates: &IndexSet<FactorSourceIDFromHash>,
+    ) -> Option<IndexSet<FactorSourceIDFromHash>> {
+        petitions.minimum_friction_search(candidates).greedy()
+    }
+
+    #[test]
+    fn greedy_drops_factor_sources_which_are_not_needed() {
+        // a6: 2 of Device fs0, Arculus fs3 and Yubikey fs5, or override
+        // Ledger fs1 or Arculus fs4. Greedy adds fs0, fs5 and is fulfilled.
+        assert_eq!(
+            greedy(&petitions([Account::a6()]), &all()),
+            Some(IndexSet::from_iter([F::fs0(), F::fs5()]))
+        );
+        // Greedy adds the threshold factors of a6 before Ledger fs1, which
+        // a1 needs, but since fs1 is an override factor of a6 they are all
+        // dropped again.
+        assert_eq!(
+            greedy(&petitions([Account::a6(), Account::a1()]), &all()),
+            Some(IndexSet::just(F::fs1()))
+        );
+    }
+
+    #[test]
+    fn greedy_none_if_candidates_cannot_fulfill() {
+        let candidates = all()
+            .into_iter()
+            .filter(|f| *f != F::fs2())
+            .collect::<IndexSet<_>>();
+        assert_eq!(greedy(&petitions([Account::a7()]), &candidates), None);
+    }
+
+    #[test]
+    fn none_if_candidates_cannot_fulfill() {
+        let candidates = all()
+            .into_iter()
+            .filter(|f| *f != F::fs2())
+            .collect::<IndexSet<_>>();
+        assert_eq!(
+            petitions([Account::a7()]).minimum_friction_factor_sources(&candidates),
+            None
+        );
+    }
+}
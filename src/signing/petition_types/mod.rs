@@ -1,11 +1,15 @@
 mod factor_list_kind;
+mod minimum_friction_plan;
 mod petition_entity;
 mod petition_factors_types;
 mod petition_of_transaction;
 mod petitions;
+mod petitions_progress;
+mod petitions_snapshot;
 
 pub use factor_list_kind::*;
 pub use petition_entity::*;
 pub use petition_factors_types::*;
 pub(crate) use petition_of_transaction::*;
 pub(crate) use petitions::*;
+pub(crate) use petitions_progress::*;
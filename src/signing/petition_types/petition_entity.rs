@@ -3,28 +3,28 @@ use crate::prelude::*;
 /// Petition of signatures from an entity in a transaction.
 /// Essentially a wrapper around a tuple
 /// `{ threshold: PetitionFactors, override: PetitionFactors }`
-#[derive(Clone, PartialEq, Eq, derive_more::Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, derive_more::Debug)]
 #[debug("{}", self.debug_str())]
-pub struct PetitionEntity {
+pub struct PetitionEntity<ID: SignableID = IntentHash> {
     /// The owner of these factors
     pub entity: AddressOfAccountOrPersona,
 
     /// Index and hash of transaction
-    pub intent_hash: IntentHash,
+    pub payload_id: ID,
 
     /// Petition with threshold factors
-    pub threshold_factors: Option<RefCell<PetitionFactors>>,
+    pub threshold_factors: Option<PetitionFactors<ID>>,
 
     /// Petition with override factors
-    pub override_factors: Option<RefCell<PetitionFactors>>,
+    pub override_factors: Option<PetitionFactors<ID>>,
 }
 
-impl PetitionEntity {
+impl<ID: SignableID> PetitionEntity<ID> {
     pub fn new(
-        intent_hash: IntentHash,
+        payload_id: ID,
         entity: AddressOfAccountOrPersona,
-        threshold_factors: impl Into<Option<PetitionFactors>>,
-        override_factors: impl Into<Option<PetitionFactors>>,
+        threshold_factors: impl Into<Option<PetitionFactors<ID>>>,
+        override_factors: impl Into<Option<PetitionFactors<ID>>>,
     ) -> Self {
         let threshold_factors = threshold_factors.into();
         let override_factors = override_factors.into();
@@ -33,32 +33,49 @@ impl PetitionEntity {
         }
         Self {
             entity,
-            intent_hash,
-            threshold_factors: threshold_factors.map(RefCell::new),
-            override_factors: override_factors.map(RefCell::new),
+            payload_id,
+            threshold_factors,
+            override_factors,
         }
     }
 
     pub fn new_securified(
-        intent_hash: IntentHash,
+        payload_id: ID,
         entity: AddressOfAccountOrPersona,
         matrix: MatrixOfFactorInstances,
     ) -> Self {
         Self::new(
-            intent_hash,
+            payload_id,
             entity,
-            PetitionFactors::new_threshold(matrix.threshold_factors, matrix.threshold as i8),
+            PetitionFactors::new_weighted_threshold(
+                matrix.weighted_threshold_factors(),
+                matrix.threshold,
+            ),
             PetitionFactors::new_override(matrix.override_factors),
         )
     }
 
+    /// Petitions a securified entity whose role has the access `rule`, as a
+    /// threshold and override list if the rule is a `MatrixOfFactors`,
+    /// otherwise as a single list which must fulfill `rule`.
+    pub fn new_securified_with_rule(
+        payload_id: ID,
+        entity: AddressOfAccountOrPersona,
+        rule: AccessRule,
+    ) -> Self {
+        match rule.as_matrix() {
+            Some(matrix) => Self::new_securified(payload_id, entity, matrix),
+            None => Self::new(payload_id, entity, PetitionFactors::new_rule(rule), None),
+        }
+    }
+
     pub fn new_unsecurified(
-        intent_hash: IntentHash,
+        payload_id: ID,
         entity: AddressOfAccountOrPersona,
         instance: HierarchicalDeterministicFactorInstance,
     ) -> Self {
         Self::new(
-            intent_hash,
+            payload_id,
             entity,
             PetitionFactors::new_unsecurified(instance),
             None,
@@ -74,7 +91,7 @@ impl PetitionEntity {
     fn union_of<F, T>(&self, map: F) -> IndexSet<T>
     where
         T: Eq + std::hash::Hash + Clone,
-        F: Fn(&PetitionFactors) -> IndexSet<T>,
+        F: Fn(&PetitionFactors<ID>) -> IndexSet<T>,
     {
         self.both(
             |l| map(l),
@@ -105,20 +122,34 @@ impl PetitionEntity {
             .collect::<IndexSet<_>>()
     }
 
-    pub fn all_signatures(&self) -> IndexSet<HDSignature> {
+    pub fn all_failed_factor_sources(&self) -> IndexSet<FactorSourceIDFromHash> {
+        self.union_of(|f| f.all_failed())
+            .into_iter()
+            .map(|f| f.factor_source_id)
+            .collect::<IndexSet<_>>()
+    }
+
+    pub fn all_signatures(&self) -> IndexSet<HDSignature<ID>> {
         self.union_of(|f| f.all_signatures())
     }
 
-    fn with_list<F, T>(list: &Option<RefCell<PetitionFactors>>, map: F) -> Option<T>
+    fn with_list<F, T>(list: &Option<PetitionFactors<ID>>, map: F) -> Option<T>
     where
-        F: Fn(&PetitionFactors) -> T,
+        F: Fn(&PetitionFactors<ID>) -> T,
     {
-        list.as_ref().map(|refcell| map(&refcell.borrow()))
+        list.as_ref().map(map)
+    }
+
+    fn with_list_mut<F, T>(list: &mut Option<PetitionFactors<ID>>, map: F) -> Option<T>
+    where
+        F: Fn(&mut PetitionFactors<ID>) -> T,
+    {
+        list.as_mut().map(map)
     }
 
     fn on_list<F, R>(&self, kind: FactorListKind, r#do: &F) -> Option<R>
     where
-        F: Fn(&PetitionFactors) -> R,
+        F: Fn(&PetitionFactors<ID>) -> R,
     {
         match kind {
             FactorListKind::Threshold => Self::with_list(&self.threshold_factors, r#do),
@@ -126,9 +157,19 @@ impl PetitionEntity {
         }
     }
 
+    fn on_list_mut<F, R>(&mut self, kind: FactorListKind, r#do: &F) -> Option<R>
+    where
+        F: Fn(&mut PetitionFactors<ID>) -> R,
+    {
+        match kind {
+            FactorListKind::Threshold => Self::with_list_mut(&mut self.threshold_factors, r#do),
+            FactorListKind::Override => Self::with_list_mut(&mut self.override_factors, r#do),
+        }
+    }
+
     fn both<F, C, T, R>(&self, r#do: F, combine: C) -> R
     where
-        F: Fn(&PetitionFactors) -> T,
+        F: Fn(&PetitionFactors<ID>) -> T,
         C: Fn(Option<T>, Option<T>) -> R,
     {
         let t = self.on_list(FactorListKind::Threshold, &r#do);
@@ -136,45 +177,102 @@ impl PetitionEntity {
         combine(t, o)
     }
 
-    fn both_void<F, R>(&self, r#do: F)
+    fn both_mut<F, C, T, R>(&mut self, r#do: F, combine: C) -> R
     where
-        F: Fn(&PetitionFactors) -> R,
+        F: Fn(&mut PetitionFactors<ID>) -> T,
+        C: Fn(Option<T>, Option<T>) -> R,
     {
-        self.both(r#do, |_, _| ())
+        let t = self.on_list_mut(FactorListKind::Threshold, &r#do);
+        let o = self.on_list_mut(FactorListKind::Override, &r#do);
+        combine(t, o)
     }
 
-    pub fn skipped_factor_source_if_relevant(&self, factor_source_id: &FactorSourceIDFromHash) {
-        self.both_void(|l| l.skip_if_references(factor_source_id, true));
+    fn both_try_mut<F>(&mut self, r#do: F) -> Result<()>
+    where
+        F: Fn(&mut PetitionFactors<ID>) -> Result<()>,
+    {
+        self.both_mut(r#do, |t, o| {
+            t.unwrap_or(Ok(()))?;
+            o.unwrap_or(Ok(()))
+        })
     }
 
-    /// # Panics
-    /// Panics if this factor source has already been skipped or signed with.
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this factor
+    /// source has already been skipped, failed or signed with, i.e. if
+    /// skipping, failing or signing with it would fail.
+    pub fn validate_use_of_factor_source(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<()> {
+        self.both(
+            |l| l.validate_use_of_factor_source_if_relevant(factor_source_id),
+            |t, o| {
+                t.unwrap_or(Ok(()))?;
+                o.unwrap_or(Ok(()))
+            },
+        )
+    }
+
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this factor
+    /// source has already been skipped, failed or signed with.
+    pub fn skipped_factor_source_if_relevant(
+        &mut self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<()> {
+        self.both_try_mut(|l| l.skip_if_references(factor_source_id, false))
+    }
+
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this factor
+    /// source has already been skipped, failed or signed with.
+    pub fn failed_factor_source_if_relevant(
+        &mut self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<()> {
+        self.both_try_mut(|l| l.fail_if_references(factor_source_id))
+    }
+
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this factor
+    /// source has already been skipped, failed or signed with, or
+    /// `Err(CommonError::UnexpectedSignature)` if the signature was produced
+    /// by a factor instance not known to this petition.
     ///
-    /// Or panics if the factor source is not known to this petition.
-    pub fn add_signature(&self, signature: HDSignature) {
-        self.both(|l| l.add_signature_if_relevant(&signature), |t, o| {
-            match (t, o) {
-                (Some(true), Some(true)) => {
-                    unreachable!("Matrix of FactorInstances does not allow for a factor to be present in both threshold and override list, thus this will never happen.")
-                }
-                (Some(false), Some(false)) => panic!("Factor source not found in any of the lists."),
-                (None, None) => panic!("Programmer error! Must have at least one factors list."), 
-                _ => (),
+    /// # Panics
+    /// Panics if this petition has no factors lists.
+    pub fn add_signature(&mut self, signature: HDSignature<ID>) -> Result<()> {
+        let (t, o) = self.both_mut(|l| l.add_signature_if_relevant(&signature), |t, o| (t, o));
+        match (t.transpose()?, o.transpose()?) {
+            (Some(true), Some(true)) => {
+                unreachable!("Matrix of FactorInstances does not allow for a factor to be present in both threshold and override list, thus this will never happen.")
             }
-        })
+            (None, None) => panic!("Programmer error! Must have at least one factors list."),
+            (Some(true), _) | (_, Some(true)) => Ok(()),
+            _ => Err(CommonError::UnexpectedSignature),
+        }
+    }
+
+    /// Whether this entity would be fulfilled if the factor sources with
+    /// `factor_source_ids`, which have not been used yet, signed.
+    pub fn is_fulfilled_if_signed_by(
+        &self,
+        factor_source_ids: &IndexSet<FactorSourceIDFromHash>,
+    ) -> bool {
+        self.both(
+            |l| l.is_fulfilled_if_signed_by(factor_source_ids),
+            |t, o| t.unwrap_or(false) || o.unwrap_or(false),
+        )
     }
 
     pub fn invalid_transactions_if_skipped(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
-    ) -> IndexSet<InvalidTransactionIfSkipped> {
+    ) -> IndexSet<InvalidTransactionIfSkipped<ID>> {
         let skip_status = self.status_if_skipped_factor_source(factor_source_id);
         match skip_status {
             PetitionFactorsStatus::Finished(finished_reason) => match finished_reason {
                 PetitionFactorsStatusFinished::Fail => {
-                    let intent_hash = self.intent_hash.clone();
+                    let payload_id = self.payload_id.clone();
                     let invalid_transaction =
-                        InvalidTransactionIfSkipped::new(intent_hash, vec![self.entity.clone()]);
+                        InvalidTransactionIfSkipped::new(payload_id, vec![self.entity.clone()]);
                     IndexSet::from_iter([invalid_transaction])
                 }
                 PetitionFactorsStatusFinished::Success => IndexSet::new(),
@@ -183,22 +281,11 @@ impl PetitionEntity {
         }
     }
 
-    /// `Ok(true)` means "continue", `Ok(false)` means "stop, we are done". `Err(_)` means "stop, we have failed".
-    pub(super) fn continue_if_necessary(&self) -> Result<bool> {
-        match self.status() {
-            PetitionFactorsStatus::InProgress => Ok(true),
-            PetitionFactorsStatus::Finished(PetitionFactorsStatusFinished::Fail) => {
-                Err(CommonError::Failure)
-            }
-            PetitionFactorsStatus::Finished(PetitionFactorsStatusFinished::Success) => Ok(false),
-        }
-    }
-
     pub fn status_if_skipped_factor_source(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
     ) -> PetitionFactorsStatus {
-        let simulation = self.clone();
+        let mut simulation = self.clone();
         simulation
             .did_skip_if_relevant(factor_source_id, true)
             .unwrap();
@@ -206,20 +293,19 @@ impl PetitionEntity {
     }
 
     pub fn did_skip_if_relevant(
-        &self,
+        &mut self,
         factor_source_id: &FactorSourceIDFromHash,
         simulated: bool,
     ) -> Result<()> {
-        self.both_void(|l| l.did_skip_if_relevant(factor_source_id, simulated));
-        Ok(())
+        self.both_try_mut(|l| l.did_skip_if_relevant(factor_source_id, simulated))
     }
 
     pub fn status(&self) -> PetitionFactorsStatus {
         use PetitionFactorsStatus::*;
         use PetitionFactorsStatusFinished::*;
 
-        let maybe_threshold = self.threshold_factors.as_ref().map(|t| t.borrow().status());
-        let maybe_override = self.override_factors.as_ref().map(|o| o.borrow().status());
+        let maybe_threshold = self.threshold_factors.as_ref().map(|t| t.status());
+        let maybe_override = self.override_factors.as_ref().map(|o| o.status());
 
         match (maybe_threshold, maybe_override) {
             (None, None) => panic!("Programmer error! Should have at least one factors list."),
@@ -240,33 +326,34 @@ impl PetitionEntity {
     fn debug_str(&self) -> String {
         let thres: String = self
             .threshold_factors
-            .clone()
-            .map(|f| format!("threshold_factors {:#?}", f.borrow()))
+            .as_ref()
+            .map(|f| format!("threshold_factors {:#?}", f))
             .unwrap_or_default();
 
         let overr: String = self
             .override_factors
-            .clone()
-            .map(|f| format!("override_factors {:#?}", f.borrow()))
+            .as_ref()
+            .map(|f| format!("override_factors {:#?}", f))
             .unwrap_or_default();
 
         format!(
-            "intent_hash: {:#?}, entity: {:#?}, {:#?}{:#?}",
-            self.intent_hash, self.entity, thres, overr
+            "payload_id: {:#?}, entity: {:#?}, {:#?}{:#?}",
+            self.payload_id, self.entity, thres, overr
         )
     }
 }
 
-impl PetitionEntity {
-    fn from_entity(entity: impl Into<AccountOrPersona>, intent_hash: IntentHash) -> Self {
+impl<ID: SignableID> PetitionEntity<ID> {
+    fn from_entity(entity: impl Into<AccountOrPersona>, payload_id: ID) -> Self {
         let entity = entity.into();
         match entity.security_state() {
-            EntitySecurityState::Securified(matrix) => {
-                Self::new_securified(intent_hash, entity.address(), matrix)
-            }
-            EntitySecurityState::Unsecured(factor) => {
-                Self::new_unsecurified(intent_hash, entity.address(), factor)
+            EntitySecurityState::Securified { structure, .. } => {
+                Self::new_securified_with_rule(payload_id, entity.address(), structure.primary_role)
             }
+            EntitySecurityState::Unsecured {
+                transaction_signing: factor,
+                ..
+            } => Self::new_unsecurified(payload_id, entity.address(), factor),
         }
     }
 }
@@ -288,7 +375,7 @@ mod tests {
 
     #[test]
     fn debug() {
-        pretty_assertions::assert_eq!(format!("{:?}", Sut::sample()), "intent_hash: TXID(\"dedede\"), entity: acco_Grace, \"threshold_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Device:00000000-0000-0000-0000-000000000000, derivation_path: 0/A/tx/6,\\n    factor_source_id: Arculus:00000000-0000-0000-0000-000000000003, derivation_path: 0/A/tx/6,\\n    factor_source_id: Yubikey:00000000-0000-0000-0000-000000000005, derivation_path: 0/A/tx/6,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\"\"override_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Ledger:00000000-0000-0000-0000-000000000001, derivation_path: 0/A/tx/6,\\n    factor_source_id: Arculus:00000000-0000-0000-0000-000000000004, derivation_path: 0/A/tx/6,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\"");
+        pretty_assertions::assert_eq!(format!("{:?}", Sut::sample()), "payload_id: TXID(\"dedede\"), entity: acco_Grace, \"threshold_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Device:00000000-0000-0000-0000-000000000000, derivation_path: 0/A/tx/6,\\n    factor_source_id: Arculus:00000000-0000-0000-0000-000000000003, derivation_path: 0/A/tx/6,\\n    factor_source_id: Yubikey:00000000-0000-0000-0000-000000000005, derivation_path: 0/A/tx/6,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\"\"override_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Ledger:00000000-0000-0000-0000-000000000001, derivation_path: 0/A/tx/6,\\n    factor_source_id: Arculus:00000000-0000-0000-0000-000000000004, derivation_path: 0/A/tx/6,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\"");
     }
 
     #[test]
@@ -303,29 +390,33 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Factor source not found in any of the lists.")]
     fn cannot_add_unrelated_signature() {
-        let sut = Sut::sample();
-        sut.add_signature(HDSignature::sample());
+        let mut sut = Sut::sample();
+        assert_eq!(
+            sut.add_signature(HDSignature::sample()),
+            Err(CommonError::UnexpectedSignature)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "A factor MUST NOT be present in both threshold AND override list.")]
     fn factor_should_not_be_used_in_both_lists() {
-        Account::securified_mainnet(0, "Jane Doe", |idx| {
-            let fi = HierarchicalDeterministicFactorInstance::f(CAP26EntityKind::Account, idx);
+        let fi = HierarchicalDeterministicFactorInstance::f(CAP26EntityKind::Account, 0);
+        assert_eq!(
             MatrixOfFactorInstances::new(
                 [FactorSourceIDFromHash::fs0()].map(&fi),
                 1,
                 [FactorSourceIDFromHash::fs0()].map(&fi),
-            )
-        });
+            ),
+            Err(CommonError::InvalidMatrixOfFactors(vec![
+                MatrixOfFactorsViolation::FactorInBothLists(FactorSourceIDFromHash::fs0()),
+                MatrixOfFactorsViolation::DeviceOnlyOverrideFactor
+            ]))
+        );
     }
 
     #[test]
-    #[should_panic]
     fn cannot_add_same_signature_twice() {
-        let intent_hash = IntentHash::sample();
+        let payload_id = IntentHash::sample();
         let entity = Account::securified_mainnet(0, "Jane Doe", |idx| {
             let fi = HierarchicalDeterministicFactorInstance::f(CAP26EntityKind::Account, idx);
             MatrixOfFactorInstances::new(
@@ -333,10 +424,11 @@ mod tests {
                 1,
                 [FactorSourceIDFromHash::fs1()].map(&fi),
             )
+            .unwrap()
         });
-        let sut = Sut::from_entity(entity.clone(), intent_hash.clone());
+        let mut sut = Sut::from_entity(entity.clone(), payload_id.clone());
         let sign_input = HDSignatureInput::new(
-            intent_hash,
+            payload_id,
             OwnedFactorInstance::new(
                 entity.address(),
                 HierarchicalDeterministicFactorInstance::mainnet_tx_account(
@@ -347,16 +439,19 @@ mod tests {
         );
         let signature = HDSignature::produced_signing_with_input(sign_input);
 
-        sut.add_signature(signature.clone());
-        sut.add_signature(signature.clone());
+        sut.add_signature(signature.clone()).unwrap();
+        assert_eq!(
+            sut.add_signature(signature.clone()),
+            Err(CommonError::FactorSourceAlreadyUsed)
+        );
     }
 
     #[test]
     fn invalid_transactions_if_skipped_success() {
-        let sut = Sut::sample();
+        let mut sut = Sut::sample();
         sut.add_signature(HDSignature::produced_signing_with_input(
             HDSignatureInput::new(
-                sut.intent_hash.clone(),
+                sut.payload_id.clone(),
                 OwnedFactorInstance::new(
                     sut.entity.clone(),
                     HierarchicalDeterministicFactorInstance::mainnet_tx_account(
@@ -365,7 +460,8 @@ mod tests {
                     ),
                 ),
             ),
-        ));
+        ))
+        .unwrap();
         let can_skip = |f: FactorSourceIDFromHash| {
             assert!(sut
                 // Already signed with override factor `FactorSourceIDFromHash::fs1()`. Thus
@@ -379,6 +475,131 @@ mod tests {
         can_skip(FactorSourceIDFromHash::fs5());
     }
 
+    /// "(Ledger AND Arculus) OR 2-of-3 of Device, Ledger and Yubikey"
+    fn petition_with_rule() -> Sut {
+        let fi = |f: FactorSourceIDFromHash| {
+            HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                HDPathComponent::securified(0),
+                f,
+            )
+        };
+        let rule = AccessRule::AnyOf(vec![
+            AccessRule::all_of(
+                [FactorSourceIDFromHash::fs1(), FactorSourceIDFromHash::fs3()].map(fi),
+            ),
+            AccessRule::count_of(
+                2,
+                [
+                    FactorSourceIDFromHash::fs0(),
+                    FactorSourceIDFromHash::fs2(),
+                    FactorSourceIDFromHash::fs5(),
+                ]
+                .map(fi),
+            ),
+        ]);
+        Sut::new_securified_with_rule(
+            IntentHash::sample(),
+            AddressOfAccountOrPersona::sample(),
+            rule,
+        )
+    }
+
+    #[test]
+    fn status_if_skipped_with_rule() {
+        use PetitionFactorsStatus::*;
+        use PetitionFactorsStatusFinished::*;
+        let mut sut = petition_with_rule();
+        assert_eq!(sut.status(), InProgress);
+
+        sut.did_skip_if_relevant(&FactorSourceIDFromHash::fs1(), false)
+            .unwrap();
+        assert_eq!(
+            sut.status_if_skipped_factor_source(&FactorSourceIDFromHash::fs3()),
+            InProgress
+        );
+        sut.did_skip_if_relevant(&FactorSourceIDFromHash::fs0(), false)
+            .unwrap();
+        assert_eq!(
+            sut.status_if_skipped_factor_source(&FactorSourceIDFromHash::fs2()),
+            Finished(Fail)
+        );
+        assert_eq!(
+            sut.invalid_transactions_if_skipped(&FactorSourceIDFromHash::fs5()),
+            IndexSet::just(InvalidTransactionIfSkipped::new(
+                IntentHash::sample(),
+                vec![AddressOfAccountOrPersona::sample()]
+            ))
+        );
+        assert!(sut
+            .invalid_transactions_if_skipped(&FactorSourceIDFromHash::fs3())
+            .is_empty());
+    }
+
+    #[test]
+    fn rule_fulfilled_by_all_of_branch() {
+        let sut = petition_with_rule();
+        assert!(!sut.is_fulfilled_if_signed_by(&IndexSet::from_iter([
+            FactorSourceIDFromHash::fs1(),
+            FactorSourceIDFromHash::fs0(),
+        ])));
+        assert!(sut.is_fulfilled_if_signed_by(&IndexSet::from_iter([
+            FactorSourceIDFromHash::fs1(),
+            FactorSourceIDFromHash::fs3(),
+        ])));
+        assert!(sut.is_fulfilled_if_signed_by(&IndexSet::from_iter([
+            FactorSourceIDFromHash::fs2(),
+            FactorSourceIDFromHash::fs5(),
+        ])));
+    }
+
+    #[test]
+    fn weighted_threshold_factors() {
+        use PetitionFactorsStatus::*;
+        use PetitionFactorsStatusFinished::*;
+        let fi = |f: FactorSourceIDFromHash| {
+            HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                HDPathComponent::securified(0),
+                f,
+            )
+        };
+        let matrix = MatrixOfFactorInstances::new_weighted(
+            [
+                (fi(FactorSourceIDFromHash::fs1()), 2),
+                (fi(FactorSourceIDFromHash::fs0()), 1),
+                (fi(FactorSourceIDFromHash::fs5()), 1),
+            ],
+            2,
+            [],
+        )
+        .unwrap();
+        let mut sut = Sut::new_securified(
+            IntentHash::sample(),
+            AddressOfAccountOrPersona::sample(),
+            matrix,
+        );
+
+        // Ledger alone suffices...
+        assert!(sut.is_fulfilled_if_signed_by(&IndexSet::just(FactorSourceIDFromHash::fs1())));
+        // ... whereas Device alone does not
+        assert!(!sut.is_fulfilled_if_signed_by(&IndexSet::just(FactorSourceIDFromHash::fs0())));
+
+        // Device and Yubikey together can still succeed without Ledger
+        assert_eq!(
+            sut.status_if_skipped_factor_source(&FactorSourceIDFromHash::fs1()),
+            InProgress
+        );
+        sut.did_skip_if_relevant(&FactorSourceIDFromHash::fs0(), false)
+            .unwrap();
+        assert_eq!(
+            sut.status_if_skipped_factor_source(&FactorSourceIDFromHash::fs5()),
+            InProgress
+        );
+        assert_eq!(
+            sut.status_if_skipped_factor_source(&FactorSourceIDFromHash::fs1()),
+            Finished(Fail)
+        );
+    }
+
     #[test]
     fn inequality() {
         assert_ne!(Sut::sample(), Sut::sample_other())
@@ -2,14 +2,14 @@ use super::*;
 use crate::prelude::*;
 
 /// Petition of signatures from a factors list of an entity in a transaction.
-#[derive(Clone, PartialEq, Eq, derive_more::Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, derive_more::Debug)]
 #[debug("{}", self.debug_str())]
-pub struct PetitionFactors {
+pub struct PetitionFactors<ID: SignableID = IntentHash> {
     pub factor_list_kind: FactorListKind,
 
     /// Factors to sign with and the required number of them.
     pub(crate) input: PetitionFactorsInput,
-    state: RefCell<PetitionFactorsState>,
+    state: PetitionFactorsState<ID>,
 }
 
 impl HasSampleValues for PetitionFactors {
@@ -25,12 +25,12 @@ impl HasSampleValues for PetitionFactors {
     }
 }
 
-impl PetitionFactors {
+impl<ID: SignableID> PetitionFactors<ID> {
     pub fn new(factor_list_kind: FactorListKind, input: PetitionFactorsInput) -> Self {
         Self {
             factor_list_kind,
             input,
-            state: RefCell::new(PetitionFactorsState::new()),
+            state: PetitionFactorsState::new(),
         }
     }
 
@@ -39,11 +39,15 @@ impl PetitionFactors {
     }
 
     pub fn all_skipped(&self) -> IndexSet<HierarchicalDeterministicFactorInstance> {
-        self.state.borrow().all_skipped()
+        self.state.all_skipped()
     }
 
-    pub fn all_signatures(&self) -> IndexSet<HDSignature> {
-        self.state.borrow().all_signatures()
+    pub fn all_failed(&self) -> IndexSet<HierarchicalDeterministicFactorInstance> {
+        self.state.all_failed()
+    }
+
+    pub fn all_signatures(&self) -> IndexSet<HDSignature<ID>> {
+        self.state.all_signatures()
     }
 
     pub fn new_threshold(
@@ -59,6 +63,27 @@ impl PetitionFactors {
         ))
     }
 
+    /// Petition of threshold factors where each factor counts with its
+    /// weight towards `threshold`.
+    pub fn new_weighted_threshold(
+        weighted_factors: Vec<(HierarchicalDeterministicFactorInstance, u8)>,
+        threshold: u8,
+    ) -> Option<Self> {
+        if weighted_factors.is_empty() {
+            return None;
+        }
+        Some(Self::new(
+            FactorListKind::Threshold,
+            PetitionFactorsInput::new_weighted_threshold(weighted_factors, threshold),
+        ))
+    }
+
+    /// Petition for an arbitrary `rule`, which is petitioned as a single
+    /// threshold list which must be fulfilled.
+    pub fn new_rule(rule: AccessRule) -> Self {
+        Self::new(FactorListKind::Threshold, PetitionFactorsInput::new(rule))
+    }
+
     pub fn new_unsecurified(factor: HierarchicalDeterministicFactorInstance) -> Self {
         Self::new_threshold(vec![factor], 1).unwrap() // define as 1/1 threshold factor, which is a good definition.
     }
@@ -73,15 +98,26 @@ impl PetitionFactors {
         ))
     }
 
-    pub fn did_skip_if_relevant(&self, factor_source_id: &FactorSourceIDFromHash, simulated: bool) {
+    pub fn did_skip_if_relevant(
+        &mut self,
+        factor_source_id: &FactorSourceIDFromHash,
+        simulated: bool,
+    ) -> Result<()> {
         if let Some(_x_) = self.reference_to_factor_source_with_id(factor_source_id) {
-            self.did_skip(factor_source_id, simulated)
+            self.did_skip(factor_source_id, simulated)?;
         }
+        Ok(())
     }
 
-    fn did_skip(&self, factor_source_id: &FactorSourceIDFromHash, simulated: bool) {
-        let factor_instance = self.expect_reference_to_factor_source_with_id(factor_source_id);
-        self.state.borrow_mut().did_skip(factor_instance, simulated);
+    fn did_skip(
+        &mut self,
+        factor_source_id: &FactorSourceIDFromHash,
+        simulated: bool,
+    ) -> Result<()> {
+        let factor_instance = self
+            .known_reference_to_factor_source_with_id(factor_source_id)?
+            .clone();
+        self.state.did_skip(&factor_instance, simulated)
     }
 
     pub fn has_owned_instance_with_id(&self, owned_factor_instance: &OwnedFactorInstance) -> bool {
@@ -95,20 +131,21 @@ impl PetitionFactors {
         self.input.factors.iter().any(|f| f == factor_instance)
     }
 
-    pub fn add_signature_if_relevant(&self, signature: &HDSignature) -> bool {
+    /// Returns `Ok(true)` if the signature was added, `Ok(false)` if it was
+    /// not relevant for this list of factors.
+    pub fn add_signature_if_relevant(&mut self, signature: &HDSignature<ID>) -> Result<bool> {
         if self.has_owned_instance_with_id(signature.owned_factor_instance()) {
-            self.add_signature(signature);
-            true
+            self.add_signature(signature)?;
+            Ok(true)
         } else {
-            false
+            Ok(false)
         }
     }
 
-    /// # Panics
-    /// Panics if this factor source has already been skipped or signed with.
-    fn add_signature(&self, signature: &HDSignature) {
-        let state = self.state.borrow_mut();
-        state.add_signature(signature)
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this factor
+    /// source has already been skipped, failed or signed with.
+    fn add_signature(&mut self, signature: &HDSignature<ID>) -> Result<()> {
+        self.state.add_signature(signature)
     }
 
     pub fn references_factor_source_with_id(
@@ -119,18 +156,50 @@ impl PetitionFactors {
             .is_some()
     }
 
-    pub fn skip_if_references(&self, factor_source_id: &FactorSourceIDFromHash, simulated: bool) {
+    pub fn skip_if_references(
+        &mut self,
+        factor_source_id: &FactorSourceIDFromHash,
+        simulated: bool,
+    ) -> Result<()> {
         if self.references_factor_source_with_id(factor_source_id) {
-            self.did_skip(factor_source_id, simulated)
+            self.did_skip(factor_source_id, simulated)?;
+        }
+        Ok(())
+    }
+
+    pub fn fail_if_references(&mut self, factor_source_id: &FactorSourceIDFromHash) -> Result<()> {
+        if self.references_factor_source_with_id(factor_source_id) {
+            let factor_instance = self
+                .known_reference_to_factor_source_with_id(factor_source_id)?
+                .clone();
+            self.state.did_fail(&factor_instance)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this list
+    /// references the factor source and it has already been skipped, failed
+    /// or signed with, i.e. if using it again would fail.
+    pub fn validate_use_of_factor_source_if_relevant(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<()> {
+        if self.references_factor_source_with_id(factor_source_id)
+            && self.state.references_factor_source_by_id(*factor_source_id)
+        {
+            return Err(CommonError::FactorSourceAlreadyUsed);
         }
+        Ok(())
     }
 
-    fn expect_reference_to_factor_source_with_id(
+    /// Returns `Err(CommonError::UnknownFactorSource)` if this list does not
+    /// reference the factor source.
+    fn known_reference_to_factor_source_with_id(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
-    ) -> &HierarchicalDeterministicFactorInstance {
+    ) -> Result<&HierarchicalDeterministicFactorInstance> {
         self.reference_to_factor_source_with_id(factor_source_id)
-            .expect("Programmer error! Factor source not found in factors.")
+            .ok_or(CommonError::UnknownFactorSource)
     }
 
     fn reference_to_factor_source_with_id(
@@ -140,8 +209,24 @@ impl PetitionFactors {
         self.input.reference_factor_source_with_id(factor_source_id)
     }
 
-    fn state_snapshot(&self) -> PetitionFactorsStateSnapshot {
-        self.state.borrow().snapshot()
+    /// Whether this list of factors would be fulfilled if the factor sources
+    /// with `factor_source_ids`, which have not been used yet, signed.
+    pub fn is_fulfilled_if_signed_by(
+        &self,
+        factor_source_ids: &IndexSet<FactorSourceIDFromHash>,
+    ) -> bool {
+        let snapshot = self.state_snapshot();
+        self.input.rule.is_fulfilled_by(&|f| {
+            snapshot.has_signed(f)
+                || (factor_source_ids.contains(&f.factor_source_id)
+                    && !self
+                        .state
+                        .references_factor_source_by_id(f.factor_source_id))
+        })
+    }
+
+    fn state_snapshot(&self) -> PetitionFactorsStateSnapshot<ID> {
+        self.state.snapshot()
     }
 
     fn is_finished_successfully(&self) -> bool {
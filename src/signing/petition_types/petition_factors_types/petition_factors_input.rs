@@ -1,19 +1,19 @@
 use super::*;
 use crate::prelude::*;
 
-#[derive(Clone, PartialEq, Eq, derive_more::Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, derive_more::Debug)]
 #[debug("PetitionFactorsInput(factors: {:#?})", self.factors)]
 pub struct PetitionFactorsInput {
     /// Factors to sign with.
     pub(super) factors: IndexSet<HierarchicalDeterministicFactorInstance>,
 
-    /// Number of required factors to sign with.
-    pub(super) required: i8,
+    /// Rule of which factors must sign.
+    pub(super) rule: AccessRule,
 }
 
 impl HasSampleValues for PetitionFactorsInput {
     fn sample() -> Self {
-        Self::new(
+        Self::new_threshold(
             IndexSet::from_iter([
                 HierarchicalDeterministicFactorInstance::sample(),
                 HierarchicalDeterministicFactorInstance::sample_other(),
@@ -23,7 +23,7 @@ impl HasSampleValues for PetitionFactorsInput {
     }
 
     fn sample_other() -> Self {
-        Self::new(
+        Self::new_threshold(
             IndexSet::from_iter([HierarchicalDeterministicFactorInstance::sample_other()]),
             1,
         )
@@ -31,22 +31,29 @@ impl HasSampleValues for PetitionFactorsInput {
 }
 
 impl PetitionFactorsInput {
-    pub(super) fn new(
-        factors: IndexSet<HierarchicalDeterministicFactorInstance>,
-        required: i8,
-    ) -> Self {
-        Self { factors, required }
+    pub(super) fn new(rule: AccessRule) -> Self {
+        Self {
+            factors: rule.factors(),
+            rule,
+        }
     }
 
     pub(super) fn new_threshold(
         factors: IndexSet<HierarchicalDeterministicFactorInstance>,
         threshold: i8,
     ) -> Self {
-        Self::new(factors, threshold)
+        Self::new(AccessRule::count_of(threshold as u8, factors))
+    }
+
+    pub(super) fn new_weighted_threshold(
+        weighted_factors: Vec<(HierarchicalDeterministicFactorInstance, u8)>,
+        threshold: u8,
+    ) -> Self {
+        Self::new(AccessRule::weight_of(threshold, weighted_factors))
     }
 
     pub(super) fn new_override(factors: IndexSet<HierarchicalDeterministicFactorInstance>) -> Self {
-        Self::new(factors, 1) // we need just one, anyone, factor for threshold.
+        Self::new(AccessRule::any_of(factors)) // we need just one, anyone, factor for override.
     }
 
     pub fn reference_factor_source_with_id(
@@ -58,25 +65,19 @@ impl PetitionFactorsInput {
             .find(|f| f.factor_source_id == *factor_source_id)
     }
 
-    fn factors_count(&self) -> i8 {
-        self.factors.len() as i8
-    }
-
-    fn remaining_factors_until_success(&self, snapshot: PetitionFactorsStateSnapshot) -> i8 {
-        self.required - snapshot.signed_count()
-    }
-
-    pub(super) fn is_fulfilled_by(&self, snapshot: PetitionFactorsStateSnapshot) -> bool {
-        self.remaining_factors_until_success(snapshot) <= 0
-    }
-
-    fn factors_left_to_prompt(&self, snapshot: PetitionFactorsStateSnapshot) -> i8 {
-        self.factors_count() - snapshot.prompted_count()
+    pub(super) fn is_fulfilled_by<ID: SignableID>(
+        &self,
+        snapshot: PetitionFactorsStateSnapshot<ID>,
+    ) -> bool {
+        self.rule.is_fulfilled_by(&|f| snapshot.has_signed(f))
     }
 
-    pub(super) fn is_failure_with(&self, snapshot: PetitionFactorsStateSnapshot) -> bool {
-        let signed_or_pending =
-            self.factors_left_to_prompt(snapshot.clone()) + snapshot.signed_count();
-        signed_or_pending < self.required
+    /// Whether the rule can no longer be fulfilled, even if all factors
+    /// which have not been prompted yet sign.
+    pub(super) fn is_failure_with<ID: SignableID>(
+        &self,
+        snapshot: PetitionFactorsStateSnapshot<ID>,
+    ) -> bool {
+        !self.rule.is_fulfilled_by(&|f| !snapshot.has_skipped(f))
     }
 }
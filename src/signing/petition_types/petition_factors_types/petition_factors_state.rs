@@ -1,42 +1,52 @@
-use std::cell::Ref;
-
 use super::*;
 use crate::prelude::*;
 
 /// Mutable state of `PetitionFactors`, keeping track of which factors that
-/// have either signed or been skipped.
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub struct PetitionFactorsState {
+/// have either signed, been skipped or failed.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PetitionFactorsState<ID: SignableID = IntentHash> {
     /// Factors that have signed.
-    signed: RefCell<PetitionFactorsSubState<HDSignature>>,
+    signed: PetitionFactorsSubState<HDSignature<ID>>,
 
     /// Factors that user skipped.
-    skipped: RefCell<PetitionFactorsSubState<HierarchicalDeterministicFactorInstance>>,
+    skipped: PetitionFactorsSubState<HierarchicalDeterministicFactorInstance>,
+
+    /// Factors that failed, e.g. because interactor returned an error or
+    /// because the signature it produced was invalid.
+    failed: PetitionFactorsSubState<HierarchicalDeterministicFactorInstance>,
 }
 
-impl PetitionFactorsState {
+impl<ID: SignableID> PetitionFactorsState<ID> {
     /// Creates a new `PetitionFactorsState`.
     pub(super) fn new() -> Self {
         Self {
-            signed: RefCell::new(PetitionFactorsSubState::<_>::new()),
-            skipped: RefCell::new(PetitionFactorsSubState::<_>::new()),
+            signed: PetitionFactorsSubState::<_>::new(),
+            skipped: PetitionFactorsSubState::<_>::new(),
+            failed: PetitionFactorsSubState::<_>::new(),
         }
     }
 
     /// A reference to the skipped factors so far.
     pub(super) fn skipped(
         &self,
-    ) -> Ref<PetitionFactorsSubState<HierarchicalDeterministicFactorInstance>> {
-        self.skipped.borrow()
+    ) -> &PetitionFactorsSubState<HierarchicalDeterministicFactorInstance> {
+        &self.skipped
+    }
+
+    /// A reference to the failed factors so far.
+    pub(super) fn failed(
+        &self,
+    ) -> &PetitionFactorsSubState<HierarchicalDeterministicFactorInstance> {
+        &self.failed
     }
 
     /// A reference to the factors which have been signed with so far.
-    pub(super) fn signed(&self) -> Ref<PetitionFactorsSubState<HDSignature>> {
-        self.signed.borrow()
+    pub(super) fn signed(&self) -> &PetitionFactorsSubState<HDSignature<ID>> {
+        &self.signed
     }
 
     /// A set of signatures from factors that have been signed with so far.
-    pub fn all_signatures(&self) -> IndexSet<HDSignature> {
+    pub fn all_signatures(&self) -> IndexSet<HDSignature<ID>> {
         self.signed().snapshot()
     }
 
@@ -45,47 +55,78 @@ impl PetitionFactorsState {
         self.skipped().snapshot()
     }
 
-    /// # Panics
-    /// Panics if this factor source has already been skipped or signed with.
-    fn assert_not_referencing_factor_source(&self, factor_source_id: FactorSourceIDFromHash) {
-        assert!(
-            !self.references_factor_source_by_id(factor_source_id),
-            "Programmer error! Factor source {:#?} already used, should only be referenced once.",
-            factor_source_id,
-        );
+    /// A set factors have failed so far.
+    pub fn all_failed(&self) -> IndexSet<HierarchicalDeterministicFactorInstance> {
+        self.failed().snapshot()
     }
 
-    /// # Panics
-    /// Panics if this factor source has already been skipped or signed and
-    /// this is not a simulation.
-    pub(crate) fn did_skip(
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this factor
+    /// source has already been skipped, failed or signed with.
+    fn ensure_not_referencing_factor_source(
         &self,
+        factor_source_id: FactorSourceIDFromHash,
+    ) -> Result<()> {
+        if self.references_factor_source_by_id(factor_source_id) {
+            return Err(CommonError::FactorSourceAlreadyUsed);
+        }
+        Ok(())
+    }
+
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this factor
+    /// source has already been skipped, failed or signed with and this is
+    /// not a simulation.
+    pub(crate) fn did_skip(
+        &mut self,
         factor_instance: &HierarchicalDeterministicFactorInstance,
         simulated: bool,
-    ) {
+    ) -> Result<()> {
         if !simulated {
-            self.assert_not_referencing_factor_source(factor_instance.factor_source_id);
+            self.ensure_not_referencing_factor_source(factor_instance.factor_source_id)?;
         }
-        self.skipped.borrow_mut().insert(factor_instance);
+        self.skipped.insert(factor_instance);
+        Ok(())
     }
 
-    /// # Panics
-    /// Panics if this factor source has already been skipped or signed with.
-    pub(crate) fn add_signature(&self, signature: &HDSignature) {
-        self.assert_not_referencing_factor_source(signature.factor_source_id());
-        self.signed.borrow_mut().insert(signature)
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this factor
+    /// source has already been skipped, failed or signed with.
+    pub(crate) fn did_fail(
+        &mut self,
+        factor_instance: &HierarchicalDeterministicFactorInstance,
+    ) -> Result<()> {
+        self.ensure_not_referencing_factor_source(factor_instance.factor_source_id)?;
+        self.failed.insert(factor_instance);
+        Ok(())
     }
 
-    pub(super) fn snapshot(&self) -> PetitionFactorsStateSnapshot {
-        PetitionFactorsStateSnapshot::new(self.signed().snapshot(), self.skipped().snapshot())
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if this factor
+    /// source has already been skipped, failed or signed with.
+    pub(crate) fn add_signature(&mut self, signature: &HDSignature<ID>) -> Result<()> {
+        self.ensure_not_referencing_factor_source(signature.factor_source_id())?;
+        self.signed.insert(signature);
+        Ok(())
     }
 
-    fn references_factor_source_by_id(&self, factor_source_id: FactorSourceIDFromHash) -> bool {
+    /// Failed factors are counted as skipped in the snapshot, since for
+    /// the status of the petition it does not matter why a factor could not
+    /// be used.
+    pub(super) fn snapshot(&self) -> PetitionFactorsStateSnapshot<ID> {
+        let mut unusable = self.skipped().snapshot();
+        unusable.extend(self.failed().snapshot());
+        PetitionFactorsStateSnapshot::new(self.signed().snapshot(), unusable)
+    }
+
+    pub(super) fn references_factor_source_by_id(
+        &self,
+        factor_source_id: FactorSourceIDFromHash,
+    ) -> bool {
         self.signed()
             .references_factor_source_by_id(factor_source_id)
             || self
                 .skipped()
                 .references_factor_source_by_id(factor_source_id)
+            || self
+                .failed()
+                .references_factor_source_by_id(factor_source_id)
     }
 }
 
@@ -97,64 +138,89 @@ mod tests {
     type Sut = PetitionFactorsState;
 
     #[test]
-    #[should_panic]
-    fn skipping_twice_panics() {
-        let sut = Sut::new();
+    fn skipping_twice_is_err() {
+        let mut sut = Sut::new();
+        let fi = HierarchicalDeterministicFactorInstance::sample();
+        sut.did_skip(&fi, false).unwrap();
+        assert_eq!(
+            sut.did_skip(&fi, false),
+            Err(CommonError::FactorSourceAlreadyUsed)
+        );
+    }
+
+    #[test]
+    fn failing_already_skipped_is_err() {
+        let mut sut = Sut::new();
         let fi = HierarchicalDeterministicFactorInstance::sample();
-        sut.did_skip(&fi, false);
-        sut.did_skip(&fi, false);
+        sut.did_skip(&fi, false).unwrap();
+        assert_eq!(sut.did_fail(&fi), Err(CommonError::FactorSourceAlreadyUsed));
     }
 
     #[test]
-    #[should_panic]
-    fn signing_twice_panics() {
-        let sut = Sut::new();
+    fn failed_is_not_skipped() {
+        let mut sut = Sut::new();
+        let fi = HierarchicalDeterministicFactorInstance::sample();
+        sut.did_fail(&fi).unwrap();
+        assert!(sut.all_skipped().is_empty());
+        assert_eq!(sut.all_failed(), IndexSet::just(fi));
+    }
+
+    #[test]
+    fn signing_twice_is_err() {
+        let mut sut = Sut::new();
         let sig = HDSignature::sample();
-        sut.add_signature(&sig);
-        sut.add_signature(&sig);
+        sut.add_signature(&sig).unwrap();
+        assert_eq!(
+            sut.add_signature(&sig),
+            Err(CommonError::FactorSourceAlreadyUsed)
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn skipping_already_signed_panics() {
-        let sut = Sut::new();
+    fn skipping_already_signed_is_err() {
+        let mut sut = Sut::new();
 
-        let intent_hash = IntentHash::sample();
+        let payload_id = IntentHash::sample();
 
         let factor_instance = HierarchicalDeterministicFactorInstance::mainnet_tx_account(
             HDPathComponent::non_hardened(0),
             FactorSourceIDFromHash::fs0(),
         );
         let sign_input = HDSignatureInput::new(
-            intent_hash,
+            payload_id,
             OwnedFactorInstance::new(AddressOfAccountOrPersona::sample(), factor_instance.clone()),
         );
         let signature = HDSignature::produced_signing_with_input(sign_input);
 
-        sut.add_signature(&signature);
+        sut.add_signature(&signature).unwrap();
 
-        sut.did_skip(&factor_instance, false);
+        assert_eq!(
+            sut.did_skip(&factor_instance, false),
+            Err(CommonError::FactorSourceAlreadyUsed)
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn signing_already_skipped_panics() {
-        let sut = Sut::new();
+    fn signing_already_skipped_is_err() {
+        let mut sut = Sut::new();
 
-        let intent_hash = IntentHash::sample();
+        let payload_id = IntentHash::sample();
         let factor_instance = HierarchicalDeterministicFactorInstance::mainnet_tx_account(
             HDPathComponent::non_hardened(0),
             FactorSourceIDFromHash::fs0(),
         );
 
-        sut.did_skip(&factor_instance, false);
+        sut.did_skip(&factor_instance, false).unwrap();
 
         let sign_input = HDSignatureInput::new(
-            intent_hash,
+            payload_id,
             OwnedFactorInstance::new(AddressOfAccountOrPersona::sample(), factor_instance.clone()),
         );
 
         let signature = HDSignature::produced_signing_with_input(sign_input);
-        sut.add_signature(&signature);
+        assert_eq!(
+            sut.add_signature(&signature),
+            Err(CommonError::FactorSourceAlreadyUsed)
+        );
     }
 }
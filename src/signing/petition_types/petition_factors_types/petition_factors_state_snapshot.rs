@@ -3,32 +3,36 @@ use crate::prelude::*;
 /// An immutable "snapshot" of `PetitionFactorsState`
 #[derive(Clone, PartialEq, Eq, derive_more::Debug)]
 #[debug("{}", self.debug_str())]
-pub(super) struct PetitionFactorsStateSnapshot {
+pub(super) struct PetitionFactorsStateSnapshot<ID: SignableID = IntentHash> {
     /// Factors that have signed.
-    signed: IndexSet<HDSignature>,
+    signed: IndexSet<HDSignature<ID>>,
 
     /// Factors that user skipped.
     skipped: IndexSet<HierarchicalDeterministicFactorInstance>,
 }
 
-impl PetitionFactorsStateSnapshot {
+impl<ID: SignableID> PetitionFactorsStateSnapshot<ID> {
     pub(super) fn new(
-        signed: IndexSet<HDSignature>,
+        signed: IndexSet<HDSignature<ID>>,
         skipped: IndexSet<HierarchicalDeterministicFactorInstance>,
     ) -> Self {
         Self { signed, skipped }
     }
 
-    pub(super) fn prompted_count(&self) -> i8 {
-        self.signed_count() + self.skipped_count()
+    pub(super) fn has_signed(
+        &self,
+        factor_instance: &HierarchicalDeterministicFactorInstance,
+    ) -> bool {
+        self.signed
+            .iter()
+            .any(|s| s.owned_factor_instance().factor_instance() == factor_instance)
     }
 
-    pub(super) fn signed_count(&self) -> i8 {
-        self.signed.len() as i8
-    }
-
-    fn skipped_count(&self) -> i8 {
-        self.skipped.len() as i8
+    pub(super) fn has_skipped(
+        &self,
+        factor_instance: &HierarchicalDeterministicFactorInstance,
+    ) -> bool {
+        self.skipped.contains(factor_instance)
     }
 
     #[allow(unused)]
@@ -88,6 +92,6 @@ mod tests {
 
     #[test]
     fn debug() {
-        assert_eq!(format!("{:?}", Sut::sample()), "signatures: \"HDSignature { input: HDSignatureInput { intent_hash: TXID(\\\"dedede\\\"), owned_factor_instance: acco_Alice: factor_source_id: Device:dededede-dede-dede-dede-dededededede, derivation_path: 0/A/tx/0 } }, HDSignature { input: HDSignatureInput { intent_hash: TXID(\\\"ababab\\\"), owned_factor_instance: ident_Alice: factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1 } }\", skipped: \"factor_source_id: Device:dededede-dede-dede-dede-dededededede, derivation_path: 0/A/tx/0, factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1\"");
+        assert_eq!(format!("{:?}", Sut::sample()), "signatures: \"HDSignature { input: HDSignatureInput { payload_id: TXID(\\\"dedede\\\"), owned_factor_instance: acco_Alice: factor_source_id: Device:dededede-dede-dede-dede-dededededede, derivation_path: 0/A/tx/0 } }, HDSignature { input: HDSignatureInput { payload_id: TXID(\\\"ababab\\\"), owned_factor_instance: ident_Alice: factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1 } }\", skipped: \"factor_source_id: Device:dededede-dede-dede-dede-dededededede, derivation_path: 0/A/tx/0, factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1\"");
     }
 }
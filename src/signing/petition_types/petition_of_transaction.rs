@@ -2,23 +2,23 @@ use crate::prelude::*;
 
 /// Petition of signatures for a transaction.
 /// Essentially a wrapper around `Iterator<Item = PetitionEntity>`.
-#[derive(derive_more::Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, derive_more::Debug, PartialEq, Eq)]
 #[debug("{}", self.debug_str())]
-pub(crate) struct PetitionTransaction {
+pub(crate) struct PetitionTransaction<ID: SignableID = IntentHash> {
     /// Hash of transaction to sign
-    pub intent_hash: IntentHash,
+    pub payload_id: ID,
 
-    pub for_entities: RefCell<HashMap<AddressOfAccountOrPersona, PetitionEntity>>,
+    pub for_entities: HashMap<AddressOfAccountOrPersona, PetitionEntity<ID>>,
 }
 
-impl PetitionTransaction {
+impl<ID: SignableID> PetitionTransaction<ID> {
     pub(crate) fn new(
-        intent_hash: IntentHash,
-        for_entities: HashMap<AddressOfAccountOrPersona, PetitionEntity>,
+        payload_id: ID,
+        for_entities: HashMap<AddressOfAccountOrPersona, PetitionEntity<ID>>,
     ) -> Self {
         Self {
-            intent_hash,
-            for_entities: RefCell::new(for_entities),
+            payload_id,
+            for_entities,
         }
     }
 
@@ -37,15 +37,10 @@ impl PetitionTransaction {
         self,
     ) -> (
         bool,
-        IndexSet<HDSignature>,
+        IndexSet<HDSignature<ID>>,
         IndexSet<FactorSourceIDFromHash>,
     ) {
-        let for_entities = self
-            .for_entities
-            .into_inner()
-            .values()
-            .map(|x| x.to_owned())
-            .collect_vec();
+        let for_entities = self.for_entities.into_values().collect_vec();
 
         let successful = for_entities
             .iter()
@@ -64,9 +59,23 @@ impl PetitionTransaction {
         (successful, signatures, skipped)
     }
 
+    /// `Finished(Fail)` if any entity has failed, `Finished(Success)` if all
+    /// entities have been successfully signed, else `InProgress`.
+    pub fn status(&self) -> PetitionFactorsStatus {
+        use PetitionFactorsStatus::*;
+        use PetitionFactorsStatusFinished::*;
+        let statuses = self.for_entities.values().map(|p| p.status()).collect_vec();
+        if statuses.contains(&Finished(Fail)) {
+            Finished(Fail)
+        } else if statuses.iter().all(|s| *s == Finished(Success)) {
+            Finished(Success)
+        } else {
+            InProgress
+        }
+    }
+
     fn _all_factor_instances(&self) -> IndexSet<OwnedFactorInstance> {
         self.for_entities
-            .borrow()
             .iter()
             .flat_map(|(_, petition)| petition.all_factor_instances())
             .collect()
@@ -82,38 +91,92 @@ impl PetitionTransaction {
             .collect()
     }
 
-    pub fn add_signature(&self, signature: HDSignature) {
-        let for_entities = self.for_entities.borrow_mut();
-        let for_entity = for_entities
-            .get(&signature.owned_factor_instance().owner)
-            .unwrap();
-        for_entity.add_signature(signature.clone());
+    /// Returns `Err(CommonError::UnexpectedSignature)` if the signer is not
+    /// an entity of this transaction.
+    pub fn add_signature(&mut self, signature: HDSignature<ID>) -> Result<()> {
+        let for_entity = self
+            .for_entities
+            .get_mut(&signature.owned_factor_instance().owner)
+            .ok_or(CommonError::UnexpectedSignature)?;
+        for_entity.add_signature(signature.clone())
+    }
+
+    /// Returns `Err(CommonError::FactorSourceAlreadyUsed)` if any entity has
+    /// already skipped, failed or signed with this factor source.
+    pub fn validate_use_of_factor_source(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<()> {
+        self.for_entities
+            .values()
+            .try_for_each(|petition| petition.validate_use_of_factor_source(factor_source_id))
+    }
+
+    pub fn skipped_factor_source(
+        &mut self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<()> {
+        self.for_entities
+            .values_mut()
+            .try_for_each(|petition| petition.skipped_factor_source_if_relevant(factor_source_id))
+    }
+
+    pub fn failed_factor_source(
+        &mut self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<()> {
+        self.for_entities
+            .values_mut()
+            .try_for_each(|petition| petition.failed_factor_source_if_relevant(factor_source_id))
     }
 
-    pub fn skipped_factor_source(&self, factor_source_id: &FactorSourceIDFromHash) {
-        let mut for_entities = self.for_entities.borrow_mut();
-        for petition in for_entities.values_mut() {
-            petition.skipped_factor_source_if_relevant(factor_source_id)
+    /// The factor instances of `factor_source_id` of the entities which have
+    /// not finished yet. Empty if any entity has failed, since then the
+    /// transaction will fail no matter what we sign.
+    fn unfinished_factor_instances_of_source(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> IndexSet<OwnedFactorInstance> {
+        let statuses = self
+            .for_entities
+            .values()
+            .map(|p| (p, p.status()))
+            .collect_vec();
+        if statuses.iter().any(|(_, s)| {
+            *s == PetitionFactorsStatus::Finished(PetitionFactorsStatusFinished::Fail)
+        }) {
+            return IndexSet::new();
         }
+        statuses
+            .into_iter()
+            .filter(|(_, s)| *s == PetitionFactorsStatus::InProgress)
+            .flat_map(|(p, _)| p.all_factor_instances())
+            .filter(|f| f.factor_instance().factor_source_id == *factor_source_id)
+            .collect()
     }
 
+    /// `None` if no entity which has not finished yet needs a signature by
+    /// `factor_source_id`.
     pub(crate) fn input_for_interactor(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
-    ) -> BatchKeySigningRequest {
-        BatchKeySigningRequest::new(
-            self.intent_hash.clone(),
+    ) -> Option<BatchKeySigningRequest<ID>> {
+        let owned_factor_instances = self.unfinished_factor_instances_of_source(factor_source_id);
+        if owned_factor_instances.is_empty() {
+            return None;
+        }
+        Some(BatchKeySigningRequest::new(
+            self.payload_id.clone(),
             *factor_source_id,
-            self.all_factor_instances_of_source(factor_source_id),
-        )
+            owned_factor_instances,
+        ))
     }
 
     pub fn invalid_transactions_if_skipped(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
-    ) -> IndexSet<InvalidTransactionIfSkipped> {
+    ) -> IndexSet<InvalidTransactionIfSkipped<ID>> {
         self.for_entities
-            .borrow()
             .iter()
             .flat_map(|(_, petition)| petition.invalid_transactions_if_skipped(factor_source_id))
             .collect()
@@ -123,7 +186,6 @@ impl PetitionTransaction {
     fn debug_str(&self) -> String {
         let entities = self
             .for_entities
-            .borrow()
             .iter()
             .map(|p| format!("PetitionEntity({:#?})", p.1))
             .join(", ");
@@ -134,14 +196,14 @@ impl PetitionTransaction {
 
 impl HasSampleValues for PetitionTransaction {
     fn sample() -> Self {
-        let intent_hash = IntentHash::sample();
+        let payload_id = IntentHash::sample();
         let entity = Account::sample_securified();
         Self::new(
-            intent_hash.clone(),
+            payload_id.clone(),
             HashMap::from_iter([(
                 entity.address(),
                 PetitionEntity::new(
-                    intent_hash.clone(),
+                    payload_id.clone(),
                     entity.address(),
                     PetitionFactors::sample(),
                     PetitionFactors::sample_other(),
@@ -151,14 +213,14 @@ impl HasSampleValues for PetitionTransaction {
     }
 
     fn sample_other() -> Self {
-        let intent_hash = IntentHash::sample_other();
+        let payload_id = IntentHash::sample_other();
         let entity = Persona::sample_unsecurified();
         Self::new(
-            intent_hash.clone(),
+            payload_id.clone(),
             HashMap::from_iter([(
                 entity.address(),
                 PetitionEntity::new(
-                    intent_hash.clone(),
+                    payload_id.clone(),
                     entity.address(),
                     PetitionFactors::sample_other(),
                     None,
@@ -188,6 +250,6 @@ mod tests {
 
     #[test]
     fn debug() {
-        assert_eq!(format!("{:?}", Sut::sample()), "PetitionTransaction(for_entities: [PetitionEntity(intent_hash: TXID(\"dedede\"), entity: acco_Grace, \"threshold_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Device:dededede-dede-dede-dede-dededededede, derivation_path: 0/A/tx/0,\\n    factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\"\"override_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\")])");
+        assert_eq!(format!("{:?}", Sut::sample()), "PetitionTransaction(for_entities: [PetitionEntity(payload_id: TXID(\"dedede\"), entity: acco_Grace, \"threshold_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Device:dededede-dede-dede-dede-dededededede, derivation_path: 0/A/tx/0,\\n    factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\"\"override_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\")])");
     }
 }
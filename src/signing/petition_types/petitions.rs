@@ -2,9 +2,9 @@
 
 use crate::prelude::*;
 
-#[derive(derive_more::Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, derive_more::Debug, PartialEq, Eq)]
 #[debug("{}", self.debug_str())]
-pub(crate) struct Petitions {
+pub(crate) struct Petitions<ID: SignableID = IntentHash> {
     /// Lookup from factor to TXID.
     ///
     ///
@@ -16,143 +16,359 @@ pub(crate) struct Petitions {
     ///
     /// Where A, B, C and D, all use the factor source, e.g. some arculus
     /// card which the user has setup as a factor (source) for all these accounts.
-    pub factor_to_txid: HashMap<FactorSourceIDFromHash, IndexSet<IntentHash>>,
+    pub factor_to_txid: HashMap<FactorSourceIDFromHash, IndexSet<ID>>,
 
     /// Lookup from TXID to signatures builders, sorted according to the order of
     /// transactions passed to the SignaturesBuilder.
-    pub txid_to_petition: RefCell<IndexMap<IntentHash, PetitionTransaction>>,
+    pub txid_to_petition: IndexMap<ID, PetitionTransaction<ID>>,
+
+    /// Factor sources which could not be used, because the interactor failed
+    /// or because they produced invalid signatures, and the error why.
+    pub failed_factor_sources: IndexMap<FactorSourceIDFromHash, CommonError>,
 }
 
-impl Petitions {
+impl<ID: SignableID> Petitions<ID> {
     pub(crate) fn new(
-        factor_to_txid: HashMap<FactorSourceIDFromHash, IndexSet<IntentHash>>,
-        txid_to_petition: IndexMap<IntentHash, PetitionTransaction>,
+        factor_to_txid: HashMap<FactorSourceIDFromHash, IndexSet<ID>>,
+        txid_to_petition: IndexMap<ID, PetitionTransaction<ID>>,
     ) -> Self {
         Self {
             factor_to_txid,
-            txid_to_petition: RefCell::new(txid_to_petition),
+            txid_to_petition,
+            failed_factor_sources: IndexMap::new(),
         }
     }
 
-    pub fn outcome(self) -> SignaturesOutcome {
-        let txid_to_petition = self.txid_to_petition.into_inner();
+    /// With `BatchSigningPolicy::AllOrNothing` all transactions are reported
+    /// as failed unless every transaction was successfully signed.
+    pub fn outcome(self, aborted: bool, policy: BatchSigningPolicy) -> SignaturesOutcome<ID> {
+        let all_successful = self.txid_to_petition.values().all(|p| {
+            p.status() == PetitionFactorsStatus::Finished(PetitionFactorsStatusFinished::Success)
+        });
+        let fail_all = policy == BatchSigningPolicy::AllOrNothing && !all_successful;
         let mut failed_transactions = MaybeSignedTransactions::empty();
         let mut successful_transactions = MaybeSignedTransactions::empty();
         let mut skipped_factor_sources = IndexSet::<_>::new();
-        for (txid, petition_of_transaction) in txid_to_petition.into_iter() {
+        for (txid, petition_of_transaction) in self.txid_to_petition.into_iter() {
             let (successful, signatures, skipped) = petition_of_transaction.outcome();
-            if successful {
-                successful_transactions.add_signatures(txid, signatures);
-            } else {
-                failed_transactions.add_signatures(txid, signatures);
+            skipped_factor_sources.extend(skipped);
+            if successful
+                && !fail_all
+                && successful_transactions
+                    .add_signatures(txid.clone(), signatures.clone())
+                    .is_ok()
+            {
+                continue;
+            }
+            // `process_batch_response` ensures that each factor source is used
+            // at most once per entity, so signatures are unique, but should
+            // they not be we report the transaction as failed without them.
+            if failed_transactions
+                .add_signatures(txid.clone(), signatures)
+                .is_err()
+            {
+                _ = failed_transactions.add_signatures(txid, IndexSet::new());
             }
-            skipped_factor_sources.extend(skipped)
         }
 
         SignaturesOutcome::new(
             successful_transactions,
             failed_transactions,
             skipped_factor_sources,
+            self.failed_factor_sources,
+            aborted,
         )
     }
 
     /// `Ok(true)` means "continue", `Ok(false)` means "stop, we are done". `Err(_)` means "stop, we have failed".
-    pub fn continue_if_necessary(&self) -> Result<bool> {
-        let should_continue_signals = self
+    ///
+    /// With `BatchSigningPolicy::BestEffort` we fail only if all transactions
+    /// have failed, with `BatchSigningPolicy::AllOrNothing` as soon as any
+    /// transaction has failed.
+    pub fn continue_if_necessary(&self, policy: BatchSigningPolicy) -> Result<bool> {
+        use PetitionFactorsStatus::*;
+        use PetitionFactorsStatusFinished::*;
+        let statuses = self
             .txid_to_petition
-            .borrow()
-            .iter()
-            .flat_map(|(_, petition)| {
-                petition
-                    .for_entities
-                    .borrow()
-                    .iter()
-                    .map(|(_, petition)| petition.continue_if_necessary())
-                    .collect_vec()
-            })
-            .collect::<Result<Vec<bool>>>()?;
+            .values()
+            .map(|p| p.status())
+            .collect_vec();
 
-        // If **any** petition says we should continue, we must continue.
-        let should_continue_signal = should_continue_signals.into_iter().any(|b| b);
+        let failed = match policy {
+            BatchSigningPolicy::BestEffort => {
+                !statuses.is_empty() && statuses.iter().all(|s| *s == Finished(Fail))
+            }
+            BatchSigningPolicy::AllOrNothing => statuses.contains(&Finished(Fail)),
+        };
+        if failed {
+            return Err(CommonError::Failure);
+        }
 
-        Ok(should_continue_signal)
+        // If **any** transaction is in progress, we must continue.
+        Ok(statuses.contains(&InProgress))
     }
 
     pub fn invalid_transactions_if_skipped(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
-    ) -> IndexSet<InvalidTransactionIfSkipped> {
+    ) -> IndexSet<InvalidTransactionIfSkipped<ID>> {
         let txids = self.factor_to_txid.get(factor_source_id).unwrap();
         txids
             .into_iter()
             .flat_map(|txid| {
-                let binding = self.txid_to_petition.borrow();
-                let value = binding.get(txid).unwrap();
+                let value = self.txid_to_petition.get(txid).unwrap();
                 value.invalid_transactions_if_skipped(factor_source_id)
             })
             .collect::<IndexSet<_>>()
     }
 
+    /// The entities needing a signature from the factor source with id
+    /// `factor_source_id`, per transaction.
+    pub(crate) fn entities_requiring_factor_source(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> IndexMap<ID, IndexSet<AddressOfAccountOrPersona>> {
+        let txids = self.factor_to_txid.get(factor_source_id).unwrap();
+        txids
+            .into_iter()
+            .map(|txid| {
+                let petition = self.txid_to_petition.get(txid).unwrap();
+                let entities = petition
+                    .all_factor_instances_of_source(factor_source_id)
+                    .into_iter()
+                    .map(|f| f.owner)
+                    .collect();
+                (txid.clone(), entities)
+            })
+            .collect()
+    }
+
+    /// The transactions and factor instances to sign with `factor_source_id`,
+    /// excluding entities and transactions which have already finished.
+    ///
+    /// `None` if nothing is left to sign with `factor_source_id`.
     pub(crate) fn input_for_interactor(
         &self,
         factor_source_id: &FactorSourceIDFromHash,
-    ) -> BatchTXBatchKeySigningRequest {
+    ) -> Option<BatchTXBatchKeySigningRequest<ID>> {
         let txids = self.factor_to_txid.get(factor_source_id).unwrap();
         let per_transaction = txids
             .into_iter()
-            .map(|txid| {
-                let binding = self.txid_to_petition.borrow();
-                let petition = binding.get(txid).unwrap();
+            .filter_map(|txid| {
+                let petition = self.txid_to_petition.get(txid).unwrap();
                 petition.input_for_interactor(factor_source_id)
             })
-            .collect::<IndexSet<BatchKeySigningRequest>>();
+            .collect::<IndexSet<BatchKeySigningRequest<ID>>>();
+
+        if per_transaction.is_empty() {
+            return None;
+        }
+        Some(BatchTXBatchKeySigningRequest::new(
+            *factor_source_id,
+            per_transaction,
+        ))
+    }
+
+    /// Validates that `signature` was produced by the factor source with id
+    /// `factor_source_id`, for a factor instance we requested a signature
+    /// from, and that it is a valid signature of the payload by the
+    /// public key of that factor instance.
+    fn validate_signature(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+        signature: &HDSignature<ID>,
+    ) -> Result<()> {
+        if signature.factor_source_id() != *factor_source_id {
+            return Err(CommonError::UnexpectedSignature);
+        }
+        let Some(petition) = self.txid_to_petition.get(signature.payload_id()) else {
+            return Err(CommonError::UnexpectedSignature);
+        };
+        if !petition
+            .all_factor_instances_of_source(factor_source_id)
+            .contains(signature.owned_factor_instance())
+        {
+            return Err(CommonError::UnexpectedSignature);
+        }
+        if !signature.is_valid() {
+            return Err(CommonError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Validates `signatures` produced by the factor source with id
+    /// `factor_source_id`, and that they can be added, i.e. that no entity
+    /// has already used the factor source and that the factor source
+    /// produced at most one signature per entity and transaction.
+    fn validate_signatures(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+        signatures: &IndexSet<HDSignature<ID>>,
+    ) -> Result<()> {
+        signatures
+            .iter()
+            .try_for_each(|s| self.validate_signature(factor_source_id, s))?;
+        if signatures
+            .iter()
+            .map(|s| (s.payload_id(), &s.owned_factor_instance().owner))
+            .duplicates()
+            .next()
+            .is_some()
+        {
+            return Err(CommonError::DuplicateSignature);
+        }
+        signatures.iter().try_for_each(|s| {
+            self.txid_to_petition
+                .get(s.payload_id())
+                .and_then(|p| p.for_entities.get(&s.owned_factor_instance().owner))
+                .ok_or(CommonError::UnexpectedSignature)?
+                .validate_use_of_factor_source(factor_source_id)
+        })
+    }
+
+    /// Validates that the factor source with id `factor_source_id` is known
+    /// and has not been used by any entity yet, i.e. that it can be skipped
+    /// or failed.
+    fn validate_use_of_factor_source(
+        &self,
+        factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<()> {
+        let txids = self
+            .factor_to_txid
+            .get(factor_source_id)
+            .ok_or(CommonError::UnknownFactorSource)?;
+        txids.iter().try_for_each(|txid| {
+            self.txid_to_petition
+                .get(txid)
+                .ok_or(CommonError::UnknownFactorSource)?
+                .validate_use_of_factor_source(factor_source_id)
+        })
+    }
 
-        BatchTXBatchKeySigningRequest::new(*factor_source_id, per_transaction)
+    /// Validates that all factor sources with ids `factor_source_ids` can
+    /// be skipped or failed, which they cannot be if any is listed twice.
+    fn validate_use_of_factor_sources(
+        &self,
+        factor_source_ids: &[FactorSourceIDFromHash],
+    ) -> Result<()> {
+        if factor_source_ids.iter().duplicates().next().is_some() {
+            return Err(CommonError::FactorSourceAlreadyUsed);
+        }
+        factor_source_ids
+            .iter()
+            .try_for_each(|id| self.validate_use_of_factor_source(id))
     }
 
-    fn add_signature(&self, signature: &HDSignature) {
-        let binding = self.txid_to_petition.borrow();
-        let petition = binding.get(signature.intent_hash()).unwrap();
+    fn add_signature(&mut self, signature: &HDSignature<ID>) -> Result<()> {
+        let petition = self
+            .txid_to_petition
+            .get_mut(signature.payload_id())
+            .ok_or(CommonError::UnexpectedSignature)?;
         petition.add_signature(signature.clone())
     }
 
-    fn skip_factor_source_with_id(&self, skipped_factor_source_id: &FactorSourceIDFromHash) {
-        let binding = self.txid_to_petition.borrow();
-        let txids = self.factor_to_txid.get(skipped_factor_source_id).unwrap();
-        txids.into_iter().for_each(|txid| {
-            let petition = binding.get(txid).unwrap();
-            petition.skipped_factor_source(skipped_factor_source_id)
-        });
+    fn skip_factor_source_with_id(
+        &mut self,
+        skipped_factor_source_id: &FactorSourceIDFromHash,
+    ) -> Result<()> {
+        let txids = self
+            .factor_to_txid
+            .get(skipped_factor_source_id)
+            .ok_or(CommonError::UnknownFactorSource)?;
+        txids.into_iter().try_for_each(|txid| {
+            self.txid_to_petition
+                .get_mut(txid)
+                .ok_or(CommonError::UnknownFactorSource)?
+                .skipped_factor_source(skipped_factor_source_id)
+        })
     }
 
+    fn fail_factor_source_with_id(
+        &mut self,
+        failed_factor_source_id: &FactorSourceIDFromHash,
+        error: CommonError,
+    ) -> Result<()> {
+        let txids = self
+            .factor_to_txid
+            .get(failed_factor_source_id)
+            .ok_or(CommonError::UnknownFactorSource)?;
+        txids.into_iter().try_for_each(|txid| {
+            self.txid_to_petition
+                .get_mut(txid)
+                .ok_or(CommonError::UnknownFactorSource)?
+                .failed_factor_source(failed_factor_source_id)
+        })?;
+        self.failed_factor_sources
+            .insert(*failed_factor_source_id, error);
+        Ok(())
+    }
+
+    /// Returns `Err` if the response cannot be applied, e.g. if it skips a
+    /// factor source which has already been used. The response is validated
+    /// before it is applied, so the petitions are left unchanged for the
+    /// factor source(s) of an erroneous response.
     pub(crate) fn process_batch_response(
-        &self,
-        response: SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>,
-    ) {
+        &mut self,
+        response: SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse<ID>>,
+    ) -> Result<()> {
         match response {
             SignWithFactorSourceOrSourcesOutcome::Signed {
                 produced_signatures,
             } => {
-                produced_signatures
-                    .signatures
-                    .values()
-                    .flatten()
-                    .for_each(|s| self.add_signature(s));
+                let signatures = &produced_signatures.signatures;
+                self.validate_use_of_factor_sources(&signatures.keys().cloned().collect_vec())?;
+                // We reject all signatures from a factor source if any of
+                // them is invalid or cannot be added, and treat it as
+                // failed. Every factor source is classified before any is
+                // applied.
+                let validated = signatures
+                    .iter()
+                    .map(|(id, signatures)| {
+                        (id, signatures, self.validate_signatures(id, signatures))
+                    })
+                    .collect_vec();
+                validated.into_iter().try_for_each(
+                    |(id, signatures, validation)| match validation {
+                        Ok(()) => signatures.iter().try_for_each(|s| self.add_signature(s)),
+                        Err(error) => self.fail_factor_source_with_id(id, error),
+                    },
+                )
             }
             SignWithFactorSourceOrSourcesOutcome::Skipped {
                 ids_of_skipped_factors_sources,
             } => {
-                for skipped_factor_source_id in ids_of_skipped_factors_sources.iter() {
-                    self.skip_factor_source_with_id(skipped_factor_source_id)
-                }
+                self.validate_use_of_factor_sources(&ids_of_skipped_factors_sources)?;
+                ids_of_skipped_factors_sources
+                    .iter()
+                    .try_for_each(|id| self.skip_factor_source_with_id(id))
+            }
+            SignWithFactorSourceOrSourcesOutcome::Failed {
+                ids_of_failed_factor_sources,
+                error,
+            } => {
+                self.validate_use_of_factor_sources(&ids_of_failed_factor_sources)?;
+                ids_of_failed_factor_sources
+                    .iter()
+                    .try_for_each(|id| self.fail_factor_source_with_id(id, error.clone()))
             }
         }
     }
 
+    /// Applies the outcome of each factor source of `response`, e.g. some
+    /// might have signed while others were skipped or failed.
+    pub(crate) fn process_parallel_batch_response(
+        &mut self,
+        response: ParallelBatchSigningResponse<ID>,
+    ) -> Result<()> {
+        response
+            .outcomes()
+            .into_iter()
+            .try_for_each(|outcome| self.process_batch_response(outcome))
+    }
+
     #[allow(unused)]
     fn debug_str(&self) -> String {
         self.txid_to_petition
-            .borrow()
             .iter()
             .map(|p| format!("Petitions({:#?}: {:#?})", p.0, p.1))
             .join(" + ")
@@ -165,9 +381,9 @@ impl HasSampleValues for Petitions {
         Self::new(
             HashMap::from_iter([(
                 FactorSourceIDFromHash::fs0(),
-                IndexSet::from_iter([p0.intent_hash.clone()]),
+                IndexSet::from_iter([p0.payload_id.clone()]),
             )]),
-            IndexMap::from_iter([(p0.intent_hash.clone(), p0)]),
+            IndexMap::from_iter([(p0.payload_id.clone(), p0)]),
         )
     }
 
@@ -176,9 +392,9 @@ impl HasSampleValues for Petitions {
         Self::new(
             HashMap::from_iter([(
                 FactorSourceIDFromHash::fs1(),
-                IndexSet::from_iter([p1.intent_hash.clone()]),
+                IndexSet::from_iter([p1.payload_id.clone()]),
             )]),
-            IndexMap::from_iter([(p1.intent_hash.clone(), p1)]),
+            IndexMap::from_iter([(p1.payload_id.clone(), p1)]),
         )
     }
 }
@@ -200,8 +416,316 @@ mod tests {
         assert_ne!(Sut::sample(), Sut::sample_other());
     }
 
+    fn signature_input() -> HDSignatureInput {
+        HDSignatureInput::new(
+            IntentHash::sample(),
+            OwnedFactorInstance::new(
+                Account::sample_securified().address(),
+                HierarchicalDeterministicFactorInstance::sample(),
+            ),
+        )
+    }
+
+    fn sut_with_known_factor_source() -> Sut {
+        let p0 = PetitionTransaction::sample();
+        Sut::new(
+            HashMap::from_iter([(
+                FactorSourceIDFromHash::sample(),
+                IndexSet::from_iter([p0.payload_id.clone()]),
+            )]),
+            IndexMap::from_iter([(p0.payload_id.clone(), p0)]),
+        )
+    }
+
+    fn response(
+        signature: HDSignature,
+    ) -> SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse> {
+        SignWithFactorSourceOrSourcesOutcome::signed(BatchSigningResponse::new(
+            IndexMap::from_iter([(signature.factor_source_id(), IndexSet::just(signature))]),
+        ))
+    }
+
+    #[test]
+    fn validate_signature_valid() {
+        let sut = Sut::sample();
+        let signature = HDSignature::produced_signing_with_input(signature_input());
+        assert_eq!(
+            sut.validate_signature(&FactorSourceIDFromHash::sample(), &signature),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn validate_signature_invalid_signature() {
+        let sut = Sut::sample();
+        let signature =
+            HDSignature::produced_signing_with_input_by(signature_input(), &EmulatedHDSigner);
+        assert_eq!(
+            sut.validate_signature(&FactorSourceIDFromHash::sample(), &signature),
+            Err(CommonError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn validate_signature_wrong_factor_source() {
+        let sut = Sut::sample();
+        let signature = HDSignature::produced_signing_with_input(signature_input());
+        assert_eq!(
+            sut.validate_signature(&FactorSourceIDFromHash::sample_other(), &signature),
+            Err(CommonError::UnexpectedSignature)
+        );
+    }
+
+    #[test]
+    fn validate_signature_unknown_transaction() {
+        let sut = Sut::sample();
+        let signature = HDSignature::produced_signing_with_input(HDSignatureInput::new(
+            IntentHash::sample_other(),
+            signature_input().owned_factor_instance,
+        ));
+        assert_eq!(
+            sut.validate_signature(&FactorSourceIDFromHash::sample(), &signature),
+            Err(CommonError::UnexpectedSignature)
+        );
+    }
+
+    #[test]
+    fn validate_signature_unknown_factor_instance() {
+        let sut = Sut::sample();
+        let signature = HDSignature::produced_signing_with_input(HDSignatureInput::new(
+            IntentHash::sample(),
+            OwnedFactorInstance::new(
+                Account::sample_securified().address(),
+                HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                    HDPathComponent::non_hardened(237),
+                    FactorSourceIDFromHash::sample(),
+                ),
+            ),
+        ));
+        assert_eq!(
+            sut.validate_signature(&FactorSourceIDFromHash::sample(), &signature),
+            Err(CommonError::UnexpectedSignature)
+        );
+    }
+
+    #[test]
+    fn process_batch_response_valid_signature_is_added() {
+        let mut sut = sut_with_known_factor_source();
+        let signature = HDSignature::produced_signing_with_input(signature_input());
+        sut.process_batch_response(response(signature.clone()))
+            .unwrap();
+        let outcome = sut.outcome(false, BatchSigningPolicy::BestEffort);
+        assert_eq!(outcome.all_signatures(), IndexSet::just(signature));
+        assert!(outcome.skipped_factor_sources().is_empty());
+    }
+
+    #[test]
+    fn process_batch_response_invalid_signature_is_rejected() {
+        let mut sut = sut_with_known_factor_source();
+        let signature =
+            HDSignature::produced_signing_with_input_by(signature_input(), &EmulatedHDSigner);
+        sut.process_batch_response(response(signature)).unwrap();
+        let outcome = sut.outcome(false, BatchSigningPolicy::BestEffort);
+        assert!(outcome.all_signatures().is_empty());
+        assert!(outcome.skipped_factor_sources().is_empty());
+        assert_eq!(
+            outcome.failed_factor_sources(),
+            IndexMap::<_, _>::from_iter([(
+                FactorSourceIDFromHash::sample(),
+                CommonError::InvalidSignature
+            )])
+        );
+    }
+
+    #[test]
+    fn process_batch_response_signing_twice_is_err_and_leaves_petitions_unchanged() {
+        let mut sut = sut_with_known_factor_source();
+        let signature = HDSignature::produced_signing_with_input(signature_input());
+        sut.process_batch_response(response(signature.clone()))
+            .unwrap();
+        let before = sut.clone();
+        assert_eq!(
+            sut.process_batch_response(response(signature)),
+            Err(CommonError::FactorSourceAlreadyUsed)
+        );
+        assert_eq!(sut, before);
+    }
+
+    #[test]
+    fn process_batch_response_skipping_signed_is_err() {
+        let mut sut = sut_with_known_factor_source();
+        let signature = HDSignature::produced_signing_with_input(signature_input());
+        sut.process_batch_response(response(signature.clone()))
+            .unwrap();
+        assert_eq!(
+            sut.process_batch_response(SignWithFactorSourceOrSourcesOutcome::skipped(
+                IndexSet::just(FactorSourceIDFromHash::sample())
+            )),
+            Err(CommonError::FactorSourceAlreadyUsed)
+        );
+        assert_eq!(
+            sut.outcome(false, BatchSigningPolicy::BestEffort)
+                .all_signatures(),
+            IndexSet::just(signature)
+        );
+    }
+
+    #[test]
+    fn process_batch_response_skipping_unknown_is_err() {
+        let mut sut = sut_with_known_factor_source();
+        assert_eq!(
+            sut.process_batch_response(SignWithFactorSourceOrSourcesOutcome::skipped(
+                IndexSet::just(FactorSourceIDFromHash::fs9())
+            )),
+            Err(CommonError::UnknownFactorSource)
+        );
+    }
+
+    #[test]
+    fn process_batch_response_failing_unknown_is_err_and_leaves_petitions_unchanged() {
+        let mut sut = sut_with_known_factor_source();
+        let before = sut.clone();
+        assert_eq!(
+            sut.process_batch_response(SignWithFactorSourceOrSourcesOutcome::failed(
+                IndexSet::just(FactorSourceIDFromHash::fs9()),
+                CommonError::Failure
+            )),
+            Err(CommonError::UnknownFactorSource)
+        );
+        assert_eq!(sut, before);
+    }
+
+    fn sut_with_both_factor_sources() -> Sut {
+        let p0 = PetitionTransaction::sample();
+        let txids = IndexSet::just(p0.payload_id.clone());
+        Sut::new(
+            HashMap::from_iter([
+                (FactorSourceIDFromHash::sample(), txids.clone()),
+                (FactorSourceIDFromHash::sample_other(), txids),
+            ]),
+            IndexMap::from_iter([(p0.payload_id.clone(), p0)]),
+        )
+    }
+
+    #[test]
+    fn input_for_interactor_excludes_finished_entities() {
+        let mut sut = sut_with_both_factor_sources();
+        assert!(sut
+            .input_for_interactor(&FactorSourceIDFromHash::sample_other())
+            .is_some());
+
+        // Signing with the Device finishes the 1/2 threshold of the only entity.
+        let signature = HDSignature::produced_signing_with_input(signature_input());
+        sut.process_batch_response(response(signature)).unwrap();
+
+        assert_eq!(
+            sut.input_for_interactor(&FactorSourceIDFromHash::sample_other()),
+            None
+        );
+    }
+
+    #[test]
+    fn process_batch_response_skipping_unused_and_signed_is_err_and_leaves_petitions_unchanged() {
+        let mut sut = sut_with_both_factor_sources();
+        let signature = HDSignature::produced_signing_with_input(signature_input());
+        sut.process_batch_response(response(signature)).unwrap();
+        let before = sut.clone();
+        assert_eq!(
+            sut.process_batch_response(SignWithFactorSourceOrSourcesOutcome::skipped(
+                IndexSet::from_iter([
+                    FactorSourceIDFromHash::sample_other(),
+                    FactorSourceIDFromHash::sample()
+                ])
+            )),
+            Err(CommonError::FactorSourceAlreadyUsed)
+        );
+        assert_eq!(sut, before);
+    }
+
+    #[test]
+    fn process_parallel_batch_response_signed_and_skipped() {
+        let mut sut = sut_with_both_factor_sources();
+        let signature = HDSignature::produced_signing_with_input(signature_input());
+        sut.process_parallel_batch_response(ParallelBatchSigningResponse::new(
+            IndexMap::from_iter([
+                (
+                    FactorSourceIDFromHash::sample(),
+                    FactorSourceOutcome::Used(IndexSet::just(signature.clone())),
+                ),
+                (
+                    FactorSourceIDFromHash::sample_other(),
+                    FactorSourceOutcome::Skipped,
+                ),
+            ]),
+        ))
+        .unwrap();
+        let outcome = sut.outcome(false, BatchSigningPolicy::BestEffort);
+        assert_eq!(outcome.all_signatures(), IndexSet::just(signature));
+        assert_eq!(
+            outcome.skipped_factor_sources(),
+            IndexSet::just(FactorSourceIDFromHash::sample_other())
+        );
+        assert!(outcome.failed_factor_sources().is_empty());
+    }
+
+    #[test]
+    fn process_parallel_batch_response_skipped_and_failed() {
+        let mut sut = sut_with_both_factor_sources();
+        sut.process_parallel_batch_response(ParallelBatchSigningResponse::new(
+            IndexMap::from_iter([
+                (
+                    FactorSourceIDFromHash::sample(),
+                    FactorSourceOutcome::Failed(CommonError::Failure),
+                ),
+                (
+                    FactorSourceIDFromHash::sample_other(),
+                    FactorSourceOutcome::Skipped,
+                ),
+            ]),
+        ))
+        .unwrap();
+        let outcome = sut.outcome(false, BatchSigningPolicy::BestEffort);
+        assert!(outcome.all_signatures().is_empty());
+        assert_eq!(
+            outcome.skipped_factor_sources(),
+            IndexSet::just(FactorSourceIDFromHash::sample_other())
+        );
+        assert_eq!(
+            outcome.failed_factor_sources(),
+            IndexMap::<_, _>::from_iter([(FactorSourceIDFromHash::sample(), CommonError::Failure)])
+        );
+    }
+
+    #[test]
+    fn process_batch_response_with_unknown_second_factor_source_is_err_and_leaves_petitions_unchanged(
+    ) {
+        let mut sut = sut_with_known_factor_source();
+        let signature = HDSignature::produced_signing_with_input(signature_input());
+        let bogus = HDSignature::produced_signing_with_input(HDSignatureInput::new(
+            IntentHash::sample(),
+            OwnedFactorInstance::new(
+                Account::sample_securified().address(),
+                HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+                    HDPathComponent::non_hardened(0),
+                    FactorSourceIDFromHash::fs9(),
+                ),
+            ),
+        ));
+        let before = sut.clone();
+        assert_eq!(
+            sut.process_batch_response(SignWithFactorSourceOrSourcesOutcome::signed(
+                BatchSigningResponse::new(IndexMap::from_iter([
+                    (FactorSourceIDFromHash::sample(), IndexSet::just(signature)),
+                    (FactorSourceIDFromHash::fs9(), IndexSet::just(bogus)),
+                ]))
+            )),
+            Err(CommonError::UnknownFactorSource)
+        );
+        assert_eq!(sut, before);
+    }
+
     #[test]
     fn debug() {
-        assert_eq!(format!("{:?}", Sut::sample()), "Petitions(TXID(\"dedede\"): PetitionTransaction(for_entities: [PetitionEntity(intent_hash: TXID(\"dedede\"), entity: acco_Grace, \"threshold_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Device:dededede-dede-dede-dede-dededededede, derivation_path: 0/A/tx/0,\\n    factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\"\"override_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\")]))");
+        assert_eq!(format!("{:?}", Sut::sample()), "Petitions(TXID(\"dedede\"): PetitionTransaction(for_entities: [PetitionEntity(payload_id: TXID(\"dedede\"), entity: acco_Grace, \"threshold_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Device:dededede-dede-dede-dede-dededededede, derivation_path: 0/A/tx/0,\\n    factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\"\"override_factors PetitionFactors(input: PetitionFactorsInput(factors: {\\n    factor_source_id: Ledger:1e1e1e1e-1e1e-1e1e-1e1e-1e1e1e1e1e1e, derivation_path: 0/A/tx/1,\\n}), state_snapshot: signatures: \\\"\\\", skipped: \\\"\\\")\")]))");
     }
 }
@@ -0,0 +1,79 @@
+use crate::prelude::*;
+
+/// The progress of all petitions at some point in time, comparing two of
+/// them tells us what changed when a response from an interactor was
+/// processed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PetitionsProgress<ID: SignableID = IntentHash> {
+    /// Ids of factor sources which have signed.
+    pub(crate) signed_factor_sources: IndexSet<FactorSourceIDFromHash>,
+
+    /// Ids of factor sources which have been skipped.
+    pub(crate) skipped_factor_sources: IndexSet<FactorSourceIDFromHash>,
+
+    /// Ids of factor sources which have failed, and the error why.
+    pub(crate) failed_factor_sources: IndexMap<FactorSourceIDFromHash, CommonError>,
+
+    /// Status of every entity in every transaction.
+    pub(crate) entities: IndexMap<(ID, AddressOfAccountOrPersona), PetitionFactorsStatus>,
+
+    /// Status of every transaction, `Finished(Success)` if all entities
+    /// have finished successfully, `Finished(Fail)` if any entity failed.
+    pub(crate) transactions: IndexMap<ID, PetitionFactorsStatus>,
+}
+
+impl<ID: SignableID> Petitions<ID> {
+    pub(crate) fn progress(&self) -> PetitionsProgress<ID> {
+        let mut signed_factor_sources = IndexSet::new();
+        let mut skipped_factor_sources = IndexSet::new();
+        let mut entities = IndexMap::new();
+        let mut transactions = IndexMap::new();
+
+        for (payload_id, petition) in self.txid_to_petition.iter() {
+            for (address, petition) in petition.for_entities.iter() {
+                signed_factor_sources.extend(
+                    petition
+                        .all_signatures()
+                        .into_iter()
+                        .map(|s| s.factor_source_id()),
+                );
+                skipped_factor_sources.extend(petition.all_skipped_factor_sources());
+                entities.insert((payload_id.clone(), address.clone()), petition.status());
+            }
+            transactions.insert(payload_id.clone(), petition.status());
+        }
+
+        PetitionsProgress {
+            signed_factor_sources,
+            skipped_factor_sources,
+            failed_factor_sources: self.failed_factor_sources.clone(),
+            entities,
+            transactions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_of_new_petitions_is_in_progress() {
+        let sut = Petitions::sample();
+        let progress = sut.progress();
+        assert!(progress.signed_factor_sources.is_empty());
+        assert!(progress.skipped_factor_sources.is_empty());
+        assert!(progress.failed_factor_sources.is_empty());
+        assert!(progress
+            .entities
+            .values()
+            .all(|s| *s == PetitionFactorsStatus::InProgress));
+        assert_eq!(
+            progress.transactions,
+            IndexMap::<_, _>::from_iter([(
+                IntentHash::sample(),
+                PetitionFactorsStatus::InProgress
+            )])
+        );
+    }
+}
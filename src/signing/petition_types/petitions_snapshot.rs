@@ -0,0 +1,170 @@
+use crate::prelude::*;
+
+/// The version of the byte format produced by `Petitions::snapshot`, stored
+/// as the first byte of every snapshot. Version 2 added failed factors,
+/// version 3 generic payload ids and factor lists with an `AccessRule`,
+/// including weighted ones.
+const PETITIONS_SNAPSHOT_VERSION: u8 = 3;
+
+impl<ID: SignableID> Petitions<ID> {
+    /// Serializes the full state of these petitions, including signed,
+    /// skipped and failed factors of every `PetitionFactorsState`, into a versioned
+    /// byte format which can be restored with `Petitions::from_snapshot`.
+    pub(crate) fn snapshot(&self) -> Vec<u8> {
+        let payload = bincode::serialize(self).expect("Petitions should always be serializable");
+        [vec![PETITIONS_SNAPSHOT_VERSION], payload].concat()
+    }
+
+    /// Ids of all factor sources which have either signed, been skipped or
+    /// failed.
+    pub(crate) fn used_factor_sources(&self) -> IndexSet<FactorSourceIDFromHash> {
+        let progress = self.progress();
+        progress
+            .signed_factor_sources
+            .union(&progress.skipped_factor_sources)
+            .cloned()
+            .chain(progress.failed_factor_sources.keys().cloned())
+            .collect()
+    }
+}
+
+impl<ID: SignableID + serde::de::DeserializeOwned> Petitions<ID> {
+    /// Restores petitions from bytes produced by `Petitions::snapshot`.
+    pub(crate) fn from_snapshot(bytes: impl AsRef<[u8]>) -> Result<Self> {
+        let Some((version, payload)) = bytes.as_ref().split_first() else {
+            return Err(CommonError::InvalidSnapshot);
+        };
+        match *version {
+            PETITIONS_SNAPSHOT_VERSION => {
+                bincode::deserialize(payload).map_err(|_| CommonError::InvalidSnapshot)
+            }
+            _ => Err(CommonError::UnsupportedSnapshotVersion),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = Petitions;
+
+    #[test]
+    fn snapshot_roundtrip() {
+        let sut = Sut::sample();
+        assert_eq!(Sut::from_snapshot(sut.snapshot()), Ok(sut));
+    }
+
+    #[test]
+    fn snapshot_roundtrip_keeps_skipped() {
+        let p0 = PetitionTransaction::sample();
+        let mut sut = Sut::new(
+            HashMap::from_iter([(
+                FactorSourceIDFromHash::sample(),
+                IndexSet::just(p0.payload_id.clone()),
+            )]),
+            IndexMap::from_iter([(p0.payload_id.clone(), p0)]),
+        );
+        sut.process_batch_response(SignWithFactorSourceOrSourcesOutcome::skipped(
+            IndexSet::just(FactorSourceIDFromHash::sample()),
+        ))
+        .unwrap();
+        let restored = Sut::from_snapshot(sut.snapshot()).unwrap();
+        assert_eq!(
+            restored.used_factor_sources(),
+            IndexSet::just(FactorSourceIDFromHash::sample())
+        );
+        assert_eq!(restored, sut);
+    }
+
+    /// A transaction signed by one securified entity with a weighted rule,
+    /// one of whose factor sources has been skipped and the other failed.
+    fn fixture() -> Sut {
+        let payload_id = IntentHash::sample();
+        let entity = Account::sample_securified().address();
+        let f0 = HierarchicalDeterministicFactorInstance::sample();
+        let f1 = HierarchicalDeterministicFactorInstance::sample_other();
+        let petition = PetitionEntity::new_securified_with_rule(
+            payload_id.clone(),
+            entity.clone(),
+            AccessRule::weight_of(2, [(f0.clone(), 2), (f1.clone(), 1)]),
+        );
+        let mut sut = Sut::new(
+            HashMap::from_iter([
+                (f0.factor_source_id, IndexSet::just(payload_id.clone())),
+                (f1.factor_source_id, IndexSet::just(payload_id.clone())),
+            ]),
+            IndexMap::from_iter([(
+                payload_id.clone(),
+                PetitionTransaction::new(payload_id, HashMap::from_iter([(entity, petition)])),
+            )]),
+        );
+        sut.process_batch_response(SignWithFactorSourceOrSourcesOutcome::skipped(
+            IndexSet::just(f1.factor_source_id),
+        ))
+        .unwrap();
+        sut.process_batch_response(SignWithFactorSourceOrSourcesOutcome::failed(
+            IndexSet::just(f0.factor_source_id),
+            CommonError::Failure,
+        ))
+        .unwrap();
+        sut
+    }
+
+    /// `fixture()` as a snapshot of the current version, which must keep
+    /// decoding as long as `PETITIONS_SNAPSHOT_VERSION` is not bumped.
+    const FIXTURE_HEX: &str = "\
+        030200000000000000050000001000000000000000dededededededededededededededede010000\
+        00000000001000000000000000dededededededededededededededede0000000010000000000000\
+        001e1e1e1e1e1e1e1e1e1e1e1e1e1e1e1e01000000000000001000000000000000dedededededede\
+        dedededededededede01000000000000001000000000000000dedededededededededededededede\
+        de1000000000000000dededededededededededededededede010000000000000000000000050000\
+        0000000000477261636500000000050000000000000047726163651000000000000000dedededede\
+        dedededededededededede01000000000200000000000000050000001000000000000000dededede\
+        dededededededededededede283e1694c5be5c53ceb3a50657768863b3ca4dacb4f9c1ff27c77e3c\
+        537626e3000000000000000000000000000000000000000010000000000000001e1e1e1e1e1e1e1e\
+        1e1e1e1e1e1e1e1e08196d4c67a977b7c28496cc27730c287d8cedea49cac1482cc1ffd95f60a5c2\
+        00000000000000000000000001000000040000000202000000000000000200000000050000001000\
+        000000000000dededededededededededededededede283e1694c5be5c53ceb3a50657768863b3ca\
+        4dacb4f9c1ff27c77e3c537626e30000000000000000000000000000000001000000000000000010\
+        000000000000001e1e1e1e1e1e1e1e1e1e1e1e1e1e1e1e08196d4c67a977b7c28496cc27730c287d\
+        8cedea49cac1482cc1ffd95f60a5c200000000000000000000000001000000000000000000000001\
+        000000000000000000000010000000000000001e1e1e1e1e1e1e1e1e1e1e1e1e1e1e1e08196d4c67\
+        a977b7c28496cc27730c287d8cedea49cac1482cc1ffd95f60a5c200000000000000000000000001\
+        0000000100000000000000050000001000000000000000dededededededededededededededede28\
+        3e1694c5be5c53ceb3a50657768863b3ca4dacb4f9c1ff27c77e3c537626e3000000000000000000\
+        00000000000000000100000000000000050000001000000000000000dededededededededededede\
+        dededede01000000";
+
+    #[test]
+    fn decodes_fixture() {
+        assert_eq!(
+            Sut::from_snapshot(hex::decode(FIXTURE_HEX).unwrap()),
+            Ok(fixture())
+        );
+    }
+
+    #[test]
+    fn snapshot_starts_with_version() {
+        assert_eq!(Sut::sample().snapshot()[0], PETITIONS_SNAPSHOT_VERSION);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut bytes = Sut::sample().snapshot();
+        bytes[0] = PETITIONS_SNAPSHOT_VERSION + 1;
+        assert_eq!(
+            Sut::from_snapshot(bytes),
+            Err(CommonError::UnsupportedSnapshotVersion)
+        );
+    }
+
+    #[test]
+    fn invalid_snapshot() {
+        assert_eq!(Sut::from_snapshot([]), Err(CommonError::InvalidSnapshot));
+        assert_eq!(
+            Sut::from_snapshot([PETITIONS_SNAPSHOT_VERSION, 0xff]),
+            Err(CommonError::InvalidSnapshot)
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use crate::prelude::*;
+
+/// The identifier of a `Signable`, e.g. the `IntentHash` of a transaction
+/// intent, whose hash is what the factor instances sign.
+pub trait SignableID:
+    Clone + PartialEq + Eq + std::hash::Hash + std::fmt::Debug + Serialize + Send + Sync + 'static
+{
+    /// The hash which is signed.
+    fn hash_to_sign(&self) -> &Hash;
+}
+
+/// A payload which must be signed by the entities requiring auth, e.g.
+/// a transaction intent, a subintent, a ROLA authentication challenge or an
+/// off-ledger message, using the same threshold and override logic.
+pub trait Signable {
+    /// The type identifying this payload, which is what gets signed.
+    type ID: SignableID;
+
+    /// The identifier of this payload.
+    fn get_id(&self) -> Self::ID;
+
+    /// The addresses of the accounts which must sign this payload.
+    fn addresses_of_accounts_requiring_auth(&self) -> Vec<AccountAddress>;
+
+    /// The addresses of the personas which must sign this payload.
+    fn addresses_of_personas_requiring_auth(&self) -> Vec<IdentityAddress>;
+
+    /// The role of the securified entities which must sign this payload.
+    fn role(&self) -> RoleKind {
+        RoleKind::Primary
+    }
+}
+
+impl SignableID for IntentHash {
+    fn hash_to_sign(&self) -> &Hash {
+        self.hash()
+    }
+}
+
+impl Signable for TransactionIntent {
+    type ID = IntentHash;
+
+    fn get_id(&self) -> IntentHash {
+        self.intent_hash.clone()
+    }
+
+    fn addresses_of_accounts_requiring_auth(&self) -> Vec<AccountAddress> {
+        self.manifest_summary().addresses_of_accounts_requiring_auth
+    }
+
+    fn addresses_of_personas_requiring_auth(&self) -> Vec<IdentityAddress> {
+        self.manifest_summary().addresses_of_personas_requiring_auth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = TransactionIntent;
+
+    #[test]
+    fn id_is_intent_hash() {
+        let sut = Sut::new([], []);
+        assert_eq!(sut.get_id(), sut.intent_hash);
+    }
+
+    #[test]
+    fn entities_requiring_auth() {
+        let account = Account::a0().entity_address();
+        let persona = Persona::p0().entity_address();
+        let sut = Sut::new([account.clone()], [persona.clone()]);
+        assert_eq!(sut.addresses_of_accounts_requiring_auth(), vec![account]);
+        assert_eq!(sut.addresses_of_personas_requiring_auth(), vec![persona]);
+    }
+
+    #[derive(Clone, PartialEq, Eq, std::hash::Hash, Debug, Serialize, Deserialize)]
+    struct MessageHash(Hash);
+
+    impl SignableID for MessageHash {
+        fn hash_to_sign(&self) -> &Hash {
+            &self.0
+        }
+    }
+
+    /// An off-ledger message, signed by accounts only.
+    #[derive(Clone, PartialEq, Eq, std::hash::Hash)]
+    struct Message {
+        hash: MessageHash,
+        signers: Vec<AccountAddress>,
+    }
+
+    impl Signable for Message {
+        type ID = MessageHash;
+
+        fn get_id(&self) -> MessageHash {
+            self.hash.clone()
+        }
+
+        fn addresses_of_accounts_requiring_auth(&self) -> Vec<AccountAddress> {
+            self.signers.clone()
+        }
+
+        fn addresses_of_personas_requiring_auth(&self) -> Vec<IdentityAddress> {
+            Vec::new()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn collector_signs_other_signables() {
+        let account = Account::a6();
+        let message = Message {
+            hash: MessageHash(Hash::generate()),
+            signers: vec![account.entity_address()],
+        };
+        let collector = SignaturesCollector::new(
+            IndexSet::just(message.clone()),
+            Arc::new(TestSignatureCollectingInteractors::new(
+                SimulatedUser::prudent_no_fail(),
+            )),
+            &Profile::new(HDFactorSource::all(), [&account], []),
+        )
+        .unwrap();
+
+        let outcome = collector.collect_signatures().await;
+        assert!(outcome.successful());
+        let signatures = outcome.all_signatures();
+        assert!(!signatures.is_empty());
+        assert!(signatures
+            .iter()
+            .all(|s| *s.payload_id() == message.hash && s.is_valid()));
+    }
+
+    #[test]
+    fn transaction_intent_requires_primary_role() {
+        assert_eq!(Sut::new([], []).role(), RoleKind::Primary);
+    }
+
+    #[test]
+    fn intent_hash_signs_its_hash() {
+        let id = IntentHash::sample();
+        assert_eq!(id.hash_to_sign(), id.hash());
+    }
+}
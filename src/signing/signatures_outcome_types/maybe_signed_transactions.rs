@@ -1,29 +1,29 @@
 use crate::prelude::*;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct MaybeSignedTransactions {
+pub struct MaybeSignedTransactions<ID: SignableID = IntentHash> {
     /// Collection of transactions which might be signed or not.
-    pub(super) transactions: IndexMap<IntentHash, IndexSet<HDSignature>>,
+    pub(super) transactions: IndexMap<ID, IndexSet<HDSignature<ID>>>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct SignedTransaction {
-    /// The transaction intent hash.
-    pub intent_hash: IntentHash,
+pub struct SignedTransaction<ID: SignableID = IntentHash> {
+    /// The identifier of the signed payload.
+    pub payload_id: ID,
     /// The signatures for this transaction.
-    pub signatures: IndexSet<HDSignature>,
+    pub signatures: IndexSet<HDSignature<ID>>,
 }
-impl SignedTransaction {
-    pub fn new(intent_hash: IntentHash, signatures: IndexSet<HDSignature>) -> Self {
+impl<ID: SignableID> SignedTransaction<ID> {
+    pub fn new(payload_id: ID, signatures: IndexSet<HDSignature<ID>>) -> Self {
         Self {
-            intent_hash,
+            payload_id,
             signatures,
         }
     }
 }
 
-impl MaybeSignedTransactions {
-    fn new(transactions: IndexMap<IntentHash, IndexSet<HDSignature>>) -> Self {
+impl<ID: SignableID> MaybeSignedTransactions<ID> {
+    fn new(transactions: IndexMap<ID, IndexSet<HDSignature<ID>>>) -> Self {
         Self { transactions }
     }
 
@@ -39,7 +39,7 @@ impl MaybeSignedTransactions {
         self.transactions.is_empty()
     }
 
-    pub fn transactions(&self) -> Vec<SignedTransaction> {
+    pub fn transactions(&self) -> Vec<SignedTransaction<ID>> {
         self.transactions
             .clone()
             .into_iter()
@@ -47,63 +47,66 @@ impl MaybeSignedTransactions {
             .collect_vec()
     }
 
-    /// Validates that all values, all signatures, have the same `intent_hash`
+    /// Validates that all values, all signatures, have the same `payload_id`
     /// as its key.
     ///
     /// Also validates that the input of every signature is unique - to identify
-    /// if the same signer has been used twice, would be a programmer error.
+    /// if the same signer has been used twice.
     ///
-    /// # Panics
-    /// Panics if any signature has a different `intent_hash` than its key.
-    fn validate(&self) {
-        for (intent_hash, signatures) in self.transactions.iter() {
-            assert!(
-                signatures.iter().all(|s| s.intent_hash() == intent_hash),
-                "Discrepancy between intent hash and signature intent hash."
-            );
+    /// Returns `Err(CommonError::UnexpectedSignature)` if any signature has a
+    /// different `payload_id` than its key, and
+    /// `Err(CommonError::DuplicateSignature)` if the same signer has been
+    /// used twice.
+    fn validate(&self) -> Result<()> {
+        if self
+            .transactions
+            .iter()
+            .any(|(payload_id, signatures)| signatures.iter().any(|s| s.payload_id() != payload_id))
+        {
+            return Err(CommonError::UnexpectedSignature);
         }
         let all_signatures = self.all_signatures();
-        let all_signatures_count = all_signatures.len();
-        let inputs = self
-            .all_signatures()
+        let inputs = all_signatures
             .iter()
             .map(|s| s.input.clone())
             .collect::<IndexSet<_>>();
-        assert_eq!(
-            all_signatures_count,
-            inputs.len(),
-            "Discrepancy, the same signer has been used twice."
-        );
+        if all_signatures.len() != inputs.len() {
+            return Err(CommonError::DuplicateSignature);
+        }
+        Ok(())
     }
 
-    /// Inserts a set of signatures for transaction with `intent_hash`, if
+    /// Inserts a set of signatures for transaction with `payload_id`, if
     /// the transaction was already present, the signatures are added to the
     /// existing set, if the transaction was not already present a new set is
     /// created.
     ///
-    /// # Panics
-    /// Panics if any signature has a different `intent_hash` than its key.
-    ///
-    /// Panics if any signatures in `signature` is not new, that is, already present
-    /// in `transactions`.
-    pub fn add_signatures(&mut self, intent_hash: IntentHash, signatures: IndexSet<HDSignature>) {
-        if let Some(ref mut sigs) = self.transactions.get_mut(&intent_hash) {
-            let old_count = sigs.len();
-            let delta_count = signatures.len();
-            sigs.extend(signatures);
-            assert_eq!(
-                sigs.len(),
-                old_count + delta_count,
-                "Discrepancy, some signature in signatures to add found in existing set."
-            );
-        } else {
-            self.transactions.insert(intent_hash, signatures);
+    /// Returns `Err(CommonError::UnexpectedSignature)` if any signature has a
+    /// different `payload_id` than its key, and
+    /// `Err(CommonError::DuplicateSignature)` if any signature in
+    /// `signatures` is not new, that is, already present in `transactions`,
+    /// or if the same signer has been used twice. `self` is left unchanged
+    /// on error.
+    pub fn add_signatures(
+        &mut self,
+        payload_id: ID,
+        signatures: IndexSet<HDSignature<ID>>,
+    ) -> Result<()> {
+        let mut updated = self.clone();
+        let existing = updated.transactions.entry(payload_id).or_default();
+        let old_count = existing.len();
+        let delta_count = signatures.len();
+        existing.extend(signatures);
+        if existing.len() != old_count + delta_count {
+            return Err(CommonError::DuplicateSignature);
         }
-        self.validate();
+        updated.validate()?;
+        *self = updated;
+        Ok(())
     }
 
     /// Returns all the signatures for all the transactions.
-    pub fn all_signatures(&self) -> IndexSet<HDSignature> {
+    pub fn all_signatures(&self) -> IndexSet<HDSignature<ID>> {
         self.transactions
             .values()
             .flat_map(|v| v.iter())
@@ -238,10 +241,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "Discrepancy, some signature in signatures to add found in existing set."
-    )]
-    fn panics_when_adding_same_signature() {
+    fn err_when_adding_same_signature() {
         let mut sut = Sut::sample();
         let tx = IntentHash::sample();
         let input = HDSignatureInput::new(
@@ -256,12 +256,15 @@ mod tests {
         );
         let signature = HDSignature::produced_signing_with_input(input);
 
-        sut.add_signatures(tx, IndexSet::from_iter([signature]));
+        assert_eq!(
+            sut.add_signatures(tx, IndexSet::from_iter([signature])),
+            Err(CommonError::DuplicateSignature)
+        );
+        assert_eq!(sut, Sut::sample());
     }
 
     #[test]
-    #[should_panic(expected = "Discrepancy between intent hash and signature intent hash.")]
-    fn panics_when_intent_hash_key_does_not_match_signature() {
+    fn err_when_intent_hash_key_does_not_match_signature() {
         let mut sut = Sut::sample();
         let tx = IntentHash::sample();
         let input = HDSignatureInput::new(
@@ -276,12 +279,15 @@ mod tests {
         );
         let signature = HDSignature::produced_signing_with_input(input);
 
-        sut.add_signatures(IntentHash::sample_other(), IndexSet::from_iter([signature]));
+        assert_eq!(
+            sut.add_signatures(IntentHash::sample_other(), IndexSet::from_iter([signature])),
+            Err(CommonError::UnexpectedSignature)
+        );
+        assert_eq!(sut, Sut::sample());
     }
 
     #[test]
-    #[should_panic(expected = "Discrepancy, the same signer has been used twice.")]
-    fn panics_when_same_signer_used_twice() {
+    fn err_when_same_signer_used_twice() {
         let mut sut = Sut::empty();
         let factor_instance = OwnedFactorInstance::sample();
         let tx = IntentHash::sample();
@@ -294,6 +300,10 @@ mod tests {
             input: input.clone(),
             signature: Signature::sample_other(),
         };
-        sut.add_signatures(tx, IndexSet::from_iter([sig_a, sig_b]));
+        assert_eq!(
+            sut.add_signatures(tx, IndexSet::from_iter([sig_a, sig_b])),
+            Err(CommonError::DuplicateSignature)
+        );
+        assert!(sut.is_empty());
     }
 }
@@ -4,41 +4,55 @@ use crate::prelude::*;
 /// which would be successful if submitted to the network (from a signatures point of view)
 /// and a collection of transactions which would fail if submitted to the network,
 /// since not enough signatures have been gathered. And a collection of factor sources
-/// which were skipped.
+/// which were skipped, and those which failed together with the error. Also tells if the session was aborted, i.e. cancelled
+/// before all factor sources were used.
 #[derive(Debug, PartialEq, Eq, Clone)]
-pub struct SignaturesOutcome {
+pub struct SignaturesOutcome<ID: SignableID = IntentHash> {
     /// A potentially empty collection of transactions which which would be
     /// successful if submitted to the network (from a signatures point of view).
     ///
     /// Potentially empty
-    successful_transactions: MaybeSignedTransactions,
+    successful_transactions: MaybeSignedTransactions<ID>,
 
     /// A collection of transactions which would fail if submitted to the network,
     /// since not enough signatures have been gathered.
     ///
     /// Potentially empty
-    failed_transactions: MaybeSignedTransactions,
+    failed_transactions: MaybeSignedTransactions<ID>,
 
-    /// List of ids of all factor sources which failed.
+    /// List of ids of all factor sources which the user skipped.
     skipped_factor_sources: IndexSet<FactorSourceIDFromHash>,
+
+    /// Ids of all factor sources which failed, e.g. because the interactor
+    /// returned an error or the signatures were invalid, with the error.
+    failed_factor_sources: IndexMap<FactorSourceIDFromHash, CommonError>,
+
+    /// If the signing session was cancelled before it had finished, the
+    /// signatures collected up until cancellation are still kept.
+    aborted: bool,
 }
 
-impl SignaturesOutcome {
+impl<ID: SignableID> SignaturesOutcome<ID> {
     /// # Panics
     /// Panics if the `successful_transactions` or `failed_transactions` shared
-    /// either any transaction intent hash, or any signature.
+    /// either any payload id, or any signature.
     pub fn new(
-        successful_transactions: MaybeSignedTransactions,
-        failed_transactions: MaybeSignedTransactions,
+        successful_transactions: MaybeSignedTransactions<ID>,
+        failed_transactions: MaybeSignedTransactions<ID>,
         skipped_factor_sources: impl IntoIterator<Item = FactorSourceIDFromHash>,
+        failed_factor_sources: impl IntoIterator<Item = (FactorSourceIDFromHash, CommonError)>,
+        aborted: bool,
     ) -> Self {
         let skipped_factor_sources = skipped_factor_sources.into_iter().collect::<IndexSet<_>>();
-        let successful_hashes: IndexSet<IntentHash> = successful_transactions
+        let failed_factor_sources = failed_factor_sources
+            .into_iter()
+            .collect::<IndexMap<_, _>>();
+        let successful_hashes: IndexSet<ID> = successful_transactions
             .transactions
             .keys()
             .cloned()
             .collect();
-        let failure_hashes: IndexSet<IntentHash> =
+        let failure_hashes: IndexSet<ID> =
             failed_transactions.transactions.keys().cloned().collect();
 
         let valid = successful_hashes
@@ -48,13 +62,15 @@ impl SignaturesOutcome {
 
         assert!(
             valid,
-            "Discrepancy, found intent hash in both successful and failed transactions, this is a programmer error."
+            "Discrepancy, found payload id in both successful and failed transactions, this is a programmer error."
         );
 
         Self {
             successful_transactions,
             failed_transactions,
             skipped_factor_sources,
+            failed_factor_sources,
+            aborted,
         }
     }
 
@@ -62,15 +78,21 @@ impl SignaturesOutcome {
         self.failed_transactions.is_empty()
     }
 
-    pub fn signatures_of_successful_transactions(&self) -> IndexSet<HDSignature> {
+    /// Returns `true` if the signing session was cancelled, using a
+    /// `CancellationHandle`, before it had finished.
+    pub fn aborted(&self) -> bool {
+        self.aborted
+    }
+
+    pub fn signatures_of_successful_transactions(&self) -> IndexSet<HDSignature<ID>> {
         self.successful_transactions.all_signatures()
     }
 
-    pub fn successful_transactions(&self) -> Vec<SignedTransaction> {
+    pub fn successful_transactions(&self) -> Vec<SignedTransaction<ID>> {
         self.successful_transactions.clone().transactions()
     }
 
-    pub fn failed_transactions(&self) -> Vec<SignedTransaction> {
+    pub fn failed_transactions(&self) -> Vec<SignedTransaction<ID>> {
         self.failed_transactions.clone().transactions()
     }
 
@@ -78,12 +100,17 @@ impl SignaturesOutcome {
         self.skipped_factor_sources.clone()
     }
 
-    pub fn signatures_of_failed_transactions(&self) -> IndexSet<HDSignature> {
+    /// Ids of the factor sources which failed, and the error why.
+    pub fn failed_factor_sources(&self) -> IndexMap<FactorSourceIDFromHash, CommonError> {
+        self.failed_factor_sources.clone()
+    }
+
+    pub fn signatures_of_failed_transactions(&self) -> IndexSet<HDSignature<ID>> {
         self.failed_transactions.all_signatures()
     }
 
     /// All signatures from both successful transactions and failed transactions.
-    pub fn all_signatures(&self) -> IndexSet<HDSignature> {
+    pub fn all_signatures(&self) -> IndexSet<HDSignature<ID>> {
         self.signatures_of_successful_transactions()
             .union(&self.signatures_of_failed_transactions())
             .cloned()
@@ -99,13 +126,15 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Discrepancy, found intent hash in both successful and failed transactions, this is a programmer error."
+        expected = "Discrepancy, found payload id in both successful and failed transactions, this is a programmer error."
     )]
     fn new_panics_if_intent_hash_is_in_both_failed_and_success_collection() {
         Sut::new(
             MaybeSignedTransactions::sample(),
             MaybeSignedTransactions::sample(),
             [],
+            [],
+            false,
         );
     }
 }
@@ -1,31 +1,45 @@
 use crate::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
-pub struct TXToSign {
-    pub intent_hash: IntentHash,
+pub struct TXToSign<ID: SignableID = IntentHash> {
+    pub payload_id: ID,
+    /// The role of the securified entities which must sign, the
+    /// unsecurified entities sign with their single factor instance.
+    pub role: RoleKind,
     entities_requiring_auth: Vec<AccountOrPersona>, // should be a set but Sets are not `Hash`.
 }
 
-impl TXToSign {
+impl<ID: SignableID> TXToSign<ID> {
     pub fn with(
-        intent_hash: IntentHash,
+        payload_id: ID,
         entities_requiring_auth: impl IntoIterator<Item = impl Into<AccountOrPersona>>,
     ) -> Self {
         Self {
-            intent_hash,
+            payload_id,
+            role: RoleKind::Primary,
             entities_requiring_auth: entities_requiring_auth
                 .into_iter()
                 .map(|i| i.into())
                 .collect_vec(),
         }
     }
-    pub fn new(
-        entities_requiring_auth: impl IntoIterator<Item = impl Into<AccountOrPersona>>,
-    ) -> Self {
-        Self::with(IntentHash::generate(), entities_requiring_auth)
+
+    /// Requires the securified entities to sign with `role`, instead of
+    /// `RoleKind::Primary`.
+    pub fn with_role(mut self, role: RoleKind) -> Self {
+        self.role = role;
+        self
     }
 
     pub fn entities_requiring_auth(&self) -> IndexSet<AccountOrPersona> {
         self.entities_requiring_auth.clone().into_iter().collect()
     }
 }
+
+impl TXToSign {
+    pub fn new(
+        entities_requiring_auth: impl IntoIterator<Item = impl Into<AccountOrPersona>>,
+    ) -> Self {
+        Self::with(IntentHash::generate(), entities_requiring_auth)
+    }
+}
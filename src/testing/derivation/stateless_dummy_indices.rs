@@ -4,13 +4,13 @@ use crate::prelude::*;
 pub struct StatelessDummyIndices;
 
 impl UsedDerivationIndices for StatelessDummyIndices {
-    fn next_derivation_index_with_request(
+    fn reserve_derivation_indices_with_request(
         &self,
         request: CreateNextDerivationPathRequest,
-    ) -> HDPathComponent {
-        match request.key_space {
-            KeySpace::Securified => HDPathComponent::non_hardened(BIP32_SECURIFIED_HALF),
-            KeySpace::Unsecurified => HDPathComponent::non_hardened(0),
-        }
+        count: usize,
+    ) -> Result<std::ops::Range<HDPathValue>> {
+        request
+            .key_space
+            .range(request.key_space.first_index(), count)
     }
 }
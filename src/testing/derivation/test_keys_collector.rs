@@ -1,13 +1,13 @@
 use crate::prelude::*;
 
 pub struct TestDerivationInteractors {
-    pub parallel: Arc<dyn DeriveKeyWithFactorParallelInteractor + Send + Sync>,
-    pub serial: Arc<dyn DeriveKeyWithFactorSerialInteractor + Send + Sync>,
+    pub parallel: Arc<dyn DeriveKeyWithFactorParallelInteractor>,
+    pub serial: Arc<dyn DeriveKeyWithFactorSerialInteractor>,
 }
 impl TestDerivationInteractors {
     pub fn new(
-        parallel: impl DeriveKeyWithFactorParallelInteractor + Send + Sync + 'static,
-        serial: impl DeriveKeyWithFactorSerialInteractor + Send + Sync + 'static,
+        parallel: impl DeriveKeyWithFactorParallelInteractor + 'static,
+        serial: impl DeriveKeyWithFactorSerialInteractor + 'static,
     ) -> Self {
         Self {
             parallel: Arc::new(parallel),
@@ -78,7 +78,7 @@ fn do_derive_serially(
     let instances = request
         .derivation_paths
         .into_iter()
-        .map(|p| HierarchicalDeterministicFactorInstance::mocked_with(p, factor_source_id))
+        .map(|p| HierarchicalDeterministicFactorInstance::derive(p, factor_source_id))
         .collect::<IndexSet<_>>();
 
     Ok(instances)
@@ -89,19 +89,19 @@ impl DeriveKeyWithFactorParallelInteractor for TestDerivationParallelInteractor
     async fn derive(
         &self,
         request: ParallelBatchKeyDerivationRequest,
-    ) -> Result<BatchDerivationResponse> {
-        let pairs_result: Result<
-            IndexMap<FactorSourceIDFromHash, IndexSet<HierarchicalDeterministicFactorInstance>>,
-        > = request
+    ) -> Result<ParallelBatchDerivationResponse> {
+        let per_factor_source = request
             .per_factor_source
             .into_iter()
             .map(|(k, r)| {
-                let instances = self.derive(r);
-                instances.map(|i| (k, i))
+                let outcome = match self.derive(r) {
+                    Ok(instances) => FactorSourceOutcome::Used(instances),
+                    Err(error) => FactorSourceOutcome::Failed(error),
+                };
+                (k, outcome)
             })
             .collect();
-        let pairs = pairs_result?;
-        Ok(BatchDerivationResponse::new(pairs))
+        Ok(ParallelBatchDerivationResponse::new(per_factor_source))
     }
 }
 
@@ -174,13 +174,15 @@ impl KeysCollector {
         key_space: KeySpace,
     ) -> Self {
         let indices = StatelessDummyIndices;
-        let path = indices.next_derivation_path(
-            factor_source.clone().factor_source_id(),
-            network_id,
-            key_kind,
-            entity_kind,
-            key_space,
-        );
+        let path = indices
+            .next_derivation_path(
+                factor_source.clone().factor_source_id(),
+                network_id,
+                key_kind,
+                entity_kind,
+                key_space,
+            )
+            .expect("Dummy indices are never exhausted");
         Self::new_test_with_factor_sources(
             [factor_source.clone()],
             [(
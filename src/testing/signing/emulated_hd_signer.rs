@@ -0,0 +1,52 @@
+use crate::prelude::*;
+
+/// A test only `HDSigner` which emulates signing of the hash of the payload with the
+/// factor instance - in a deterministic manner - by hashing them together.
+///
+/// The produced signatures are NOT valid Ed25519 signatures.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EmulatedHDSigner;
+
+impl HDSigner for EmulatedHDSigner {
+    fn sign<ID: SignableID>(&self, input: &HDSignatureInput<ID>) -> Signature {
+        let hash_bytes = input.payload_id.hash_to_sign().to_bytes();
+        let factor_instance_bytes = input.owned_factor_instance.factor_instance().to_bytes();
+        let input_bytes = [hash_bytes, factor_instance_bytes].concat();
+        let hash = sha256::digest(input_bytes);
+        Signature::from_bytes(
+            hash.as_bytes()
+                .try_into()
+                .expect("Hex of SHA256 digest is 64 bytes"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = EmulatedHDSigner;
+
+    #[test]
+    fn deterministic() {
+        let input = HDSignatureInput::sample();
+        assert_eq!(Sut::default().sign(&input), Sut::default().sign(&input));
+    }
+
+    #[test]
+    fn different_inputs_different_signatures() {
+        assert_ne!(
+            Sut::default().sign(&HDSignatureInput::sample()),
+            Sut::default().sign(&HDSignatureInput::sample_other())
+        );
+    }
+
+    #[test]
+    fn emulated_signatures_are_not_valid() {
+        let signature = HDSignature::produced_signing_with_input_by(
+            HDSignatureInput::sample(),
+            &Sut::default(),
+        );
+        assert!(!signature.is_valid());
+    }
+}
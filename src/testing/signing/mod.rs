@@ -1,11 +1,13 @@
 #![allow(unused_imports)]
 
+mod emulated_hd_signer;
 mod simulated_user;
 mod test_data;
 mod test_interactors;
 mod test_signature_collecting_interactors;
 mod test_signatures_collector;
 
+pub use emulated_hd_signer::*;
 pub use simulated_user::*;
 pub use test_data::*;
 pub use test_interactors::*;
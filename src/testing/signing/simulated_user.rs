@@ -105,8 +105,6 @@ impl SimulatedUser {
     }
 }
 
-unsafe impl Sync for SimulatedUser {}
-
 /// A very lazy user that defers all boring work such as signing stuff for as long
 /// as possible. Ironically, this sometimes leads to user signing more than she
 /// actually needs. For example, if the user has a Securified Account with threshold
@@ -120,9 +118,9 @@ pub enum Laziness {
 }
 
 impl SimulatedUser {
-    pub fn sign_or_skip(
+    pub fn sign_or_skip<ID: crate::prelude::SignableID>(
         &self,
-        invalid_tx_if_skipped: impl IntoIterator<Item = crate::prelude::InvalidTransactionIfSkipped>,
+        invalid_tx_if_skipped: impl IntoIterator<Item = crate::prelude::InvalidTransactionIfSkipped<ID>>,
     ) -> SigningUserInput {
         let invalid_tx_if_skipped = invalid_tx_if_skipped
             .into_iter()
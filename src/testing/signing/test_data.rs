@@ -155,7 +155,7 @@ impl MatrixOfFactorInstances {
     where
         F: Fn(FactorSourceIDFromHash) -> HierarchicalDeterministicFactorInstance,
     {
-        Self::single_threshold(fi(FactorSourceIDFromHash::fs0()))
+        Self::single_threshold(fi(FactorSourceIDFromHash::fs0())).expect("Valid matrix")
     }
 
     /// Securified { Single Override only }
@@ -163,7 +163,7 @@ impl MatrixOfFactorInstances {
     where
         F: Fn(FactorSourceIDFromHash) -> HierarchicalDeterministicFactorInstance,
     {
-        Self::single_override(fi(FactorSourceIDFromHash::fs1()))
+        Self::single_override(fi(FactorSourceIDFromHash::fs1())).expect("Valid matrix")
     }
 
     /// Securified { Threshold factors only #3 }
@@ -172,7 +172,7 @@ impl MatrixOfFactorInstances {
         F: Fn(FactorSourceIDFromHash) -> HierarchicalDeterministicFactorInstance,
     {
         type F = FactorSourceIDFromHash;
-        Self::threshold_only([F::fs0(), F::fs3(), F::fs5()].map(fi), 2)
+        Self::threshold_only([F::fs0(), F::fs3(), F::fs5()].map(fi), 2).expect("Valid matrix")
     }
 
     /// Securified { Override factors only #2 }
@@ -181,7 +181,7 @@ impl MatrixOfFactorInstances {
         F: Fn(FactorSourceIDFromHash) -> HierarchicalDeterministicFactorInstance,
     {
         type F = FactorSourceIDFromHash;
-        Self::override_only([F::fs1(), F::fs4()].map(&fi))
+        Self::override_only([F::fs1(), F::fs4()].map(&fi)).expect("Valid matrix")
     }
 
     /// Securified { Threshold #3 and Override factors #2  }
@@ -195,6 +195,7 @@ impl MatrixOfFactorInstances {
             2,
             [F::fs1(), F::fs4()].map(&fi),
         )
+        .expect("Valid matrix")
     }
 
     /// Securified { Threshold only # 5/5 }
@@ -207,6 +208,7 @@ impl MatrixOfFactorInstances {
             [F::fs2(), F::fs6(), F::fs7(), F::fs8(), F::fs9()].map(&fi),
             5,
         )
+        .expect("Valid matrix")
     }
 }
 
@@ -18,21 +18,21 @@ impl IsTestInteractor for TestSigningParallelInteractor {
 }
 
 #[async_trait::async_trait]
-impl SignWithFactorParallelInteractor for TestSigningParallelInteractor {
+impl<ID: SignableID> SignWithFactorParallelInteractor<ID> for TestSigningParallelInteractor {
     async fn sign(
         &self,
-        request: ParallelBatchSigningRequest,
-    ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>> {
-        if self.should_simulate_failure(request.per_factor_source.keys().cloned().collect()) {
-            return Err(CommonError::Failure);
-        }
-        match self
+        request: ParallelBatchSigningRequest<ID>,
+    ) -> Result<ParallelBatchSigningResponse<ID>> {
+        let (failing, per_factor_source): (IndexMap<_, _>, IndexMap<_, _>) = request
+            .per_factor_source
+            .into_iter()
+            .partition(|(id, _)| self.should_simulate_failure(IndexSet::just(*id)));
+        let mut response = match self
             .simulated_user
             .sign_or_skip(request.invalid_transactions_if_skipped)
         {
             SigningUserInput::Sign => {
-                let signatures = request
-                    .per_factor_source
+                let signatures = per_factor_source
                     .iter()
                     .flat_map(|(_, v)| {
                         v.per_transaction
@@ -43,30 +43,22 @@ impl SignWithFactorParallelInteractor for TestSigningParallelInteractor {
                                     .map(|y| HDSignature::produced_signing_with_input(y.clone()))
                                     .collect_vec()
                             })
-                            .collect::<IndexSet<HDSignature>>()
+                            .collect::<IndexSet<HDSignature<ID>>>()
                     })
-                    .collect::<IndexSet<HDSignature>>();
+                    .collect::<IndexSet<HDSignature<ID>>>();
 
-                let signatures = signatures
-                    .into_iter()
-                    .into_group_map_by(|x| x.factor_source_id());
-                let response = BatchSigningResponse::new(
-                    signatures
-                        .into_iter()
-                        .map(|(k, v)| (k, IndexSet::from_iter(v)))
-                        .collect(),
-                );
-
-                Ok(SignWithFactorSourceOrSourcesOutcome::signed(response))
+                ParallelBatchSigningResponse::signed(signatures)
             }
 
-            SigningUserInput::Skip => Ok(SignWithFactorSourceOrSourcesOutcome::skipped(
-                request
-                    .per_factor_source
-                    .keys()
-                    .cloned()
-                    .collect::<IndexSet<_>>(),
-            )),
-        }
+            SigningUserInput::Skip => {
+                ParallelBatchSigningResponse::skipped(per_factor_source.into_keys())
+            }
+        };
+        response.per_factor_source.extend(
+            failing
+                .into_keys()
+                .map(|id| (id, FactorSourceOutcome::Failed(CommonError::Failure))),
+        );
+        Ok(response)
     }
 }
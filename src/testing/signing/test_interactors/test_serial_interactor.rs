@@ -18,11 +18,11 @@ impl IsTestInteractor for TestSigningSerialInteractor {
 }
 
 #[async_trait::async_trait]
-impl SignWithFactorSerialInteractor for TestSigningSerialInteractor {
+impl<ID: SignableID> SignWithFactorSerialInteractor<ID> for TestSigningSerialInteractor {
     async fn sign(
         &self,
-        request: SerialBatchSigningRequest,
-    ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse>> {
+        request: SerialBatchSigningRequest<ID>,
+    ) -> Result<SignWithFactorSourceOrSourcesOutcome<BatchSigningResponse<ID>>> {
         if self.should_simulate_failure(IndexSet::from_iter([request.input.factor_source_id])) {
             return Err(CommonError::Failure);
         }
@@ -42,7 +42,7 @@ impl SignWithFactorSerialInteractor for TestSigningSerialInteractor {
                             .map(|x| HDSignature::produced_signing_with_input(x.clone()))
                             .collect::<IndexSet<_>>()
                     })
-                    .collect::<IndexSet<HDSignature>>();
+                    .collect::<IndexSet<HDSignature<ID>>>();
                 let signatures = signatures
                     .into_iter()
                     .into_group_map_by(|x| x.factor_source_id());
@@ -10,8 +10,8 @@ impl TestSignatureCollectingInteractors {
     }
 }
 
-impl SignatureCollectingInteractors for TestSignatureCollectingInteractors {
-    fn interactor_for(&self, kind: FactorSourceKind) -> SigningInteractor {
+impl<ID: SignableID> SignatureCollectingInteractors<ID> for TestSignatureCollectingInteractors {
+    fn interactor_for(&self, kind: FactorSourceKind) -> SigningInteractor<ID> {
         match kind {
             FactorSourceKind::Device => SigningInteractor::parallel(Arc::new(
                 TestSigningParallelInteractor::new(self.simulated_user.clone()),
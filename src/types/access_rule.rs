@@ -0,0 +1,335 @@
+use crate::prelude::*;
+
+/// A rule of which factors must sign for an entity, built recursively from
+/// all-of, any-of and count-of nodes, e.g.
+/// "(Ledger AND Arculus) OR 2-of-3 Yubikeys".
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, std::hash::Hash)]
+pub enum AccessRule<F = HierarchicalDeterministicFactorInstance> {
+    /// Fulfilled if this factor signs.
+    Factor(F),
+
+    /// Fulfilled if all of the rules are fulfilled.
+    AllOf(Vec<AccessRule<F>>),
+
+    /// Fulfilled if any of the rules is fulfilled.
+    AnyOf(Vec<AccessRule<F>>),
+
+    /// Fulfilled if at least `count` of the rules are fulfilled.
+    CountOf(u8, Vec<AccessRule<F>>),
+
+    /// Fulfilled if the weights of the fulfilled rules sum up to at least
+    /// `threshold`.
+    WeightOf(u8, Vec<(u8, AccessRule<F>)>),
+}
+
+impl<F> AccessRule<F> {
+    fn factors_of(factors: impl IntoIterator<Item = F>) -> Vec<Self> {
+        factors.into_iter().map(Self::Factor).collect_vec()
+    }
+
+    pub fn all_of(factors: impl IntoIterator<Item = F>) -> Self {
+        Self::AllOf(Self::factors_of(factors))
+    }
+
+    pub fn any_of(factors: impl IntoIterator<Item = F>) -> Self {
+        Self::AnyOf(Self::factors_of(factors))
+    }
+
+    pub fn count_of(count: u8, factors: impl IntoIterator<Item = F>) -> Self {
+        Self::CountOf(count, Self::factors_of(factors))
+    }
+
+    pub fn weight_of(threshold: u8, weighted_factors: impl IntoIterator<Item = (F, u8)>) -> Self {
+        Self::WeightOf(
+            threshold,
+            weighted_factors
+                .into_iter()
+                .map(|(f, weight)| (weight, Self::Factor(f)))
+                .collect_vec(),
+        )
+    }
+
+    /// Whether this rule is fulfilled if exactly the factors for which
+    /// `is_signed` returns `true` sign.
+    pub fn is_fulfilled_by(&self, is_signed: &impl Fn(&F) -> bool) -> bool {
+        match self {
+            Self::Factor(factor) => is_signed(factor),
+            Self::AllOf(rules) => rules.iter().all(|r| r.is_fulfilled_by(is_signed)),
+            Self::AnyOf(rules) => rules.iter().any(|r| r.is_fulfilled_by(is_signed)),
+            Self::CountOf(count, rules) => {
+                rules
+                    .iter()
+                    .filter(|r| r.is_fulfilled_by(is_signed))
+                    .count()
+                    >= *count as usize
+            }
+            Self::WeightOf(threshold, rules) => {
+                rules
+                    .iter()
+                    .filter(|(_, r)| r.is_fulfilled_by(is_signed))
+                    .map(|(weight, _)| *weight as u32)
+                    .sum::<u32>()
+                    >= *threshold as u32
+            }
+        }
+    }
+
+    /// The same rule with every factor mapped by `map`.
+    pub fn map<G>(&self, map: &impl Fn(&F) -> G) -> AccessRule<G> {
+        let map_all = |rules: &Vec<Self>| rules.iter().map(|r| r.map(map)).collect_vec();
+        match self {
+            Self::Factor(factor) => AccessRule::Factor(map(factor)),
+            Self::AllOf(rules) => AccessRule::AllOf(map_all(rules)),
+            Self::AnyOf(rules) => AccessRule::AnyOf(map_all(rules)),
+            Self::CountOf(count, rules) => AccessRule::CountOf(*count, map_all(rules)),
+            Self::WeightOf(threshold, rules) => AccessRule::WeightOf(
+                *threshold,
+                rules.iter().map(|(w, r)| (*w, r.map(map))).collect_vec(),
+            ),
+        }
+    }
+
+    /// The same rule with every factor mapped by `map`, or the first error.
+    pub fn try_map<G>(&self, map: &impl Fn(&F) -> Result<G>) -> Result<AccessRule<G>> {
+        let map_all = |rules: &Vec<Self>| {
+            rules
+                .iter()
+                .map(|r| r.try_map(map))
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(match self {
+            Self::Factor(factor) => AccessRule::Factor(map(factor)?),
+            Self::AllOf(rules) => AccessRule::AllOf(map_all(rules)?),
+            Self::AnyOf(rules) => AccessRule::AnyOf(map_all(rules)?),
+            Self::CountOf(count, rules) => AccessRule::CountOf(*count, map_all(rules)?),
+            Self::WeightOf(threshold, rules) => AccessRule::WeightOf(
+                *threshold,
+                rules
+                    .iter()
+                    .map(|(w, r)| Ok((*w, r.try_map(map)?)))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+        })
+    }
+}
+
+impl<F: std::hash::Hash + Eq + Clone> AccessRule<F> {
+    /// All factors referenced anywhere in this rule.
+    pub fn factors(&self) -> IndexSet<F> {
+        match self {
+            Self::Factor(factor) => IndexSet::just(factor.clone()),
+            Self::AllOf(rules) | Self::AnyOf(rules) | Self::CountOf(_, rules) => {
+                rules.iter().flat_map(|r| r.factors()).collect()
+            }
+            Self::WeightOf(_, rules) => rules.iter().flat_map(|(_, r)| r.factors()).collect(),
+        }
+    }
+
+    /// The `MatrixOfFactors` this rule was converted from, if it has the
+    /// shape `AnyOf([CountOf(threshold, threshold_factors), AnyOf(override_factors)])`,
+    /// where the threshold list is omitted if it is empty with a threshold of
+    /// zero, the override list is omitted if empty, and `CountOf` is
+    /// `WeightOf` if the threshold factors are weighted.
+    pub fn as_matrix(&self) -> Option<MatrixOfFactors<F>> {
+        let leaves = |rules: &Vec<Self>| {
+            rules
+                .iter()
+                .map(|r| match r {
+                    Self::Factor(factor) => Some(factor.clone()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<F>>>()
+        };
+        let Self::AnyOf(lists) = self else {
+            return None;
+        };
+        let mut lists = lists.iter().peekable();
+        let (threshold_factors, threshold) = match lists.peek() {
+            Some(Self::CountOf(threshold, rules)) => {
+                let factors = leaves(rules)?;
+                lists.next();
+                let factors = factors
+                    .into_iter()
+                    .map(WeightedFactor::unweighted)
+                    .collect_vec();
+                (factors, *threshold)
+            }
+            Some(Self::WeightOf(threshold, rules)) => {
+                let (weights, rules): (Vec<u8>, Vec<Self>) = rules.iter().cloned().unzip();
+                let factors = leaves(&rules)?;
+                lists.next();
+                let factors = factors
+                    .into_iter()
+                    .zip(weights)
+                    .map(WeightedFactor::from)
+                    .collect_vec();
+                (factors, *threshold)
+            }
+            _ => (Vec::new(), 0),
+        };
+        let override_factors = match lists.next() {
+            Some(Self::AnyOf(rules)) => leaves(rules)?,
+            Some(_) => return None,
+            None => Vec::new(),
+        };
+        if lists.next().is_some() {
+            return None;
+        }
+        Some(MatrixOfFactors {
+            threshold_factors,
+            threshold,
+            override_factors,
+        })
+    }
+}
+
+/// Lossless, see `AccessRule::as_matrix`.
+impl<F> From<MatrixOfFactors<F>> for AccessRule<F> {
+    fn from(value: MatrixOfFactors<F>) -> Self {
+        let mut lists = Vec::new();
+        if value.threshold_factors.iter().any(|w| w.weight != 1) {
+            lists.push(Self::weight_of(
+                value.threshold,
+                value
+                    .threshold_factors
+                    .into_iter()
+                    .map(|w| (w.factor, w.weight)),
+            ));
+        } else if !value.threshold_factors.is_empty() || value.threshold != 0 {
+            lists.push(Self::count_of(
+                value.threshold,
+                value.threshold_factors.into_iter().map(|w| w.factor),
+            ));
+        }
+        if !value.override_factors.is_empty() {
+            lists.push(Self::any_of(value.override_factors));
+        }
+        Self::AnyOf(lists)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = AccessRule<u8>;
+
+    fn matrix(
+        threshold_factors: impl IntoIterator<Item = (u8, u8)>,
+        threshold: u8,
+        override_factors: impl IntoIterator<Item = u8>,
+    ) -> MatrixOfFactors<u8> {
+        MatrixOfFactors {
+            threshold_factors: threshold_factors
+                .into_iter()
+                .map(WeightedFactor::from)
+                .collect_vec(),
+            threshold,
+            override_factors: override_factors.into_iter().collect_vec(),
+        }
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        let matrices = [
+            matrix([(1, 1), (2, 1), (3, 1)], 2, [4, 5]),
+            matrix([(1, 1), (2, 1)], 2, []),
+            matrix([], 0, [1, 2]),
+            matrix([(1, 1)], 0, []),
+            matrix([], 0, []),
+            matrix([(1, 2), (2, 1), (3, 1)], 2, [4]),
+        ];
+        for matrix in matrices {
+            assert_eq!(Sut::from(matrix.clone()).as_matrix(), Some(matrix));
+        }
+    }
+
+    #[test]
+    fn matrix_round_trip_exhaustive() {
+        let subsets = |factors: &[u8]| {
+            (0..=factors.len())
+                .flat_map(|n| factors.iter().cloned().combinations(n))
+                .collect_vec()
+        };
+        for threshold_factors in subsets(&[1, 2, 3]) {
+            let n = threshold_factors.len();
+            for threshold_weights in itertools::repeat_n([1u8, 2], n).multi_cartesian_product() {
+                for threshold in 0..=3 {
+                    for override_factors in subsets(&[4, 5]) {
+                        let matrix = matrix(
+                            threshold_factors
+                                .iter()
+                                .cloned()
+                                .zip(threshold_weights.iter().cloned()),
+                            threshold,
+                            override_factors,
+                        );
+                        assert_eq!(Sut::from(matrix.clone()).as_matrix(), Some(matrix));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn non_matrix_rule_is_not_matrix() {
+        let sut = Sut::AnyOf(vec![Sut::all_of([1, 2]), Sut::count_of(2, [3, 4, 5])]);
+        assert_eq!(sut.as_matrix(), None);
+    }
+
+    #[test]
+    fn matrix_semantics_are_kept() {
+        let sut = Sut::from(matrix([(1, 1), (2, 1), (3, 1)], 2, [4]));
+        assert!(!sut.is_fulfilled_by(&|f| *f == 1));
+        assert!(sut.is_fulfilled_by(&|f| [1, 3].contains(f)));
+        assert!(sut.is_fulfilled_by(&|f| *f == 4));
+    }
+
+    #[test]
+    fn weighted_matrix_semantics() {
+        let sut = Sut::from(matrix([(1, 2), (2, 1), (3, 1)], 2, []));
+        assert!(sut.is_fulfilled_by(&|f| *f == 1));
+        assert!(!sut.is_fulfilled_by(&|f| *f == 2));
+        assert!(sut.is_fulfilled_by(&|f| [2, 3].contains(f)));
+    }
+
+    #[test]
+    fn nested_rule() {
+        let sut = Sut::AnyOf(vec![Sut::all_of([1, 2]), Sut::count_of(2, [3, 4, 5])]);
+        assert!(!sut.is_fulfilled_by(&|f| [1, 3].contains(f)));
+        assert!(sut.is_fulfilled_by(&|f| [1, 2].contains(f)));
+        assert!(sut.is_fulfilled_by(&|f| [3, 5].contains(f)));
+    }
+
+    #[test]
+    fn factors() {
+        let sut = Sut::AnyOf(vec![Sut::all_of([1, 2]), Sut::count_of(2, [3, 2])]);
+        assert_eq!(sut.factors(), IndexSet::<u8>::from_iter([1, 2, 3]));
+    }
+
+    #[test]
+    fn map() {
+        let sut = Sut::AnyOf(vec![Sut::all_of([1, 2]), Sut::count_of(1, [3])]);
+        assert_eq!(
+            sut.map(&|f| *f as u16 * 10),
+            AccessRule::AnyOf(vec![
+                AccessRule::all_of([10, 20]),
+                AccessRule::count_of(1, [30])
+            ])
+        );
+    }
+
+    #[test]
+    fn try_map_fails_on_first_error() {
+        let sut = Sut::AnyOf(vec![Sut::all_of([1, 2]), Sut::count_of(1, [3])]);
+        assert_eq!(
+            sut.try_map(&|f| if *f == 3 {
+                Err(CommonError::UnknownFactorInstance)
+            } else {
+                Ok(*f)
+            }),
+            Err(CommonError::UnknownFactorInstance)
+        );
+        assert_eq!(sut.try_map(&|f| Ok(*f)), Ok(sut));
+    }
+}
@@ -0,0 +1,14 @@
+/// Controls whether the `SignaturesCollector` keeps prompting for the other
+/// transactions of the batch once some transaction has failed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BatchSigningPolicy {
+    /// Keeps prompting as long as any transaction might still be signed,
+    /// transactions which have failed are reported as failed, the others
+    /// as successful.
+    #[default]
+    BestEffort,
+
+    /// Stops prompting as soon as any transaction has failed, and reports
+    /// the whole batch as failed.
+    AllOrNothing,
+}
@@ -0,0 +1,15 @@
+/// Controls which factor sources the `SignaturesCollector` prompts the user
+/// for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FactorSelectionPolicy {
+    /// Every factor source referenced by any entity is prompted for, in
+    /// friction order, until all transactions are signed or would fail.
+    #[default]
+    AllReferenced,
+
+    /// Only the set of factor sources with the lowest total friction which
+    /// fulfills every entity is prompted for. Other factor sources are used
+    /// as fallbacks, only when needed, e.g. after the user skipped a factor
+    /// source of that set.
+    MinimumFriction,
+}
@@ -0,0 +1,18 @@
+use crate::prelude::*;
+
+/// The outcome of using a single factor source as part of a batch with many
+/// factor sources, e.g. by a parallel interactor, the user might use some of
+/// the factor sources, skip others and some might fail.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FactorSourceOutcome<T> {
+    /// The factor source was used, the associated value contains what it
+    /// produced, e.g. signatures or derived factor instances.
+    Used(T),
+
+    /// The user skipped the factor source.
+    Skipped,
+
+    /// Using the factor source failed, e.g. because the mnemonic could not
+    /// be loaded from secure storage.
+    Failed(CommonError),
+}
@@ -0,0 +1,26 @@
+use crate::prelude::*;
+
+/// A value which references a factor source by its id.
+pub trait FactorSourceReferencing: std::hash::Hash + PartialEq + Eq + Clone {
+    fn factor_source_id(&self) -> FactorSourceIDFromHash;
+}
+
+impl FactorSourceReferencing for HierarchicalDeterministicFactorInstance {
+    fn factor_source_id(&self) -> FactorSourceIDFromHash {
+        self.factor_source_id
+    }
+}
+
+impl<ID: SignableID> FactorSourceReferencing for HDSignature<ID> {
+    fn factor_source_id(&self) -> FactorSourceIDFromHash {
+        self.owned_factor_instance()
+            .factor_instance()
+            .factor_source_id
+    }
+}
+
+impl FactorSourceReferencing for HDFactorSource {
+    fn factor_source_id(&self) -> FactorSourceIDFromHash {
+        HDFactorSource::factor_source_id(self)
+    }
+}
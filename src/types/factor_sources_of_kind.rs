@@ -27,7 +27,11 @@ impl FactorSourcesOfKind {
         })
     }
 
-    pub(crate) fn factor_sources(&self) -> IndexSet<HDFactorSource> {
+    pub fn kind(&self) -> FactorSourceKind {
+        self.kind
+    }
+
+    pub fn factor_sources(&self) -> IndexSet<HDFactorSource> {
         self.factor_sources.clone().into_iter().collect()
     }
 
@@ -0,0 +1,157 @@
+use crate::prelude::*;
+
+impl FactorSourceKind {
+    /// How tedious it is for the user to use a factor source of this kind,
+    /// Device being the least tedious and Ledger the most.
+    pub fn friction(&self) -> usize {
+        match self {
+            Self::Device => 1,
+            Self::OffDeviceMnemonic => 2,
+            Self::SecurityQuestions => 3,
+            Self::Yubikey => 4,
+            Self::Arculus => 5,
+            Self::Ledger => 6,
+        }
+    }
+}
+
+/// Controls in which order the collectors use factor sources of different
+/// kinds, i.e. which `FactorSourceKind` the user is prompted for first,
+/// second etc.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum FrictionOrderPolicy {
+    /// The most tedious kind first, and the quickest and easiest to use kind
+    /// last, i.e. the order of `FactorSourceKind`: Ledger, Arculus, Yubikey,
+    /// SecurityQuestions, OffDeviceMnemonic, Device. Good for "failing fast"
+    /// if user lacks access to e.g. a Ledger device.
+    #[default]
+    MostFrictionFirst,
+
+    /// The reverse of `MostFrictionFirst`, i.e. Device first, useful for a
+    /// "quick approve" flow.
+    LeastFrictionFirst,
+
+    /// An explicit order of kinds, kinds not present in the list are used
+    /// after all listed kinds, in the order of `MostFrictionFirst`.
+    Custom(Vec<FactorSourceKind>),
+}
+
+impl FrictionOrderPolicy {
+    /// An explicit order of kinds, kinds not present in `kinds` are used
+    /// last.
+    pub fn custom(kinds: impl IntoIterator<Item = FactorSourceKind>) -> Self {
+        Self::Custom(kinds.into_iter().collect())
+    }
+
+    fn sort_key(&self, kind: FactorSourceKind) -> (usize, std::cmp::Reverse<usize>) {
+        let most_friction_first = std::cmp::Reverse(kind.friction());
+        match self {
+            Self::MostFrictionFirst => (0, most_friction_first),
+            Self::LeastFrictionFirst => (kind.friction(), most_friction_first),
+            Self::Custom(kinds) => (
+                kinds.iter().position(|k| *k == kind).unwrap_or(kinds.len()),
+                most_friction_first,
+            ),
+        }
+    }
+
+    /// Sorts `kinds` according to this policy.
+    pub fn sorted(
+        &self,
+        kinds: impl IntoIterator<Item = FactorSourceKind>,
+    ) -> Vec<FactorSourceKind> {
+        kinds
+            .into_iter()
+            .sorted_by_key(|k| self.sort_key(*k))
+            .collect()
+    }
+
+    pub(crate) fn sort(
+        &self,
+        factors_of_kind: IndexSet<FactorSourcesOfKind>,
+    ) -> IndexSet<FactorSourcesOfKind> {
+        factors_of_kind
+            .into_iter()
+            .sorted_by_key(|f| self.sort_key(f.kind))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = FrictionOrderPolicy;
+
+    fn all_kinds() -> Vec<FactorSourceKind> {
+        use FactorSourceKind::*;
+        vec![
+            Device,
+            SecurityQuestions,
+            Ledger,
+            OffDeviceMnemonic,
+            Yubikey,
+            Arculus,
+        ]
+    }
+
+    #[test]
+    fn friction_is_unique_per_kind() {
+        assert_eq!(
+            all_kinds()
+                .into_iter()
+                .map(|k| k.friction())
+                .unique()
+                .count(),
+            all_kinds().len()
+        );
+    }
+
+    #[test]
+    fn default_is_most_friction_first() {
+        use FactorSourceKind::*;
+        assert_eq!(
+            Sut::default().sorted(all_kinds()),
+            vec![
+                Ledger,
+                Arculus,
+                Yubikey,
+                SecurityQuestions,
+                OffDeviceMnemonic,
+                Device
+            ]
+        );
+    }
+
+    #[test]
+    fn least_friction_first() {
+        use FactorSourceKind::*;
+        assert_eq!(
+            Sut::LeastFrictionFirst.sorted(all_kinds()),
+            vec![
+                Device,
+                OffDeviceMnemonic,
+                SecurityQuestions,
+                Yubikey,
+                Arculus,
+                Ledger
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_unlisted_kinds_last_in_default_order() {
+        use FactorSourceKind::*;
+        assert_eq!(
+            Sut::custom([Device, Yubikey]).sorted(all_kinds()),
+            vec![
+                Device,
+                Yubikey,
+                Ledger,
+                Arculus,
+                SecurityQuestions,
+                OffDeviceMnemonic
+            ]
+        );
+    }
+}
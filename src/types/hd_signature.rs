@@ -1,11 +1,11 @@
 use crate::prelude::*;
 
-/// A signature of `intent_hash` by `entity` using `factor_source_id` and `derivation_path`, with `public_key` used for verification.
-#[derive(Clone, PartialEq, Eq, Hash, derive_more::Debug)]
+/// A signature of the hash of `payload_id` by `entity` using `factor_source_id` and `derivation_path`, with `public_key` used for verification.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, derive_more::Debug)]
 #[debug("HDSignature {{ input: {:#?} }}", input)]
-pub struct HDSignature {
+pub struct HDSignature<ID: SignableID = IntentHash> {
     /// The input used to produce this `HDSignature`
-    pub input: HDSignatureInput,
+    pub input: HDSignatureInput<ID>,
 
     /// The ECDSA/EdDSA signature produced by the private key of the
     /// `owned_hd_factor_instance.public_key`,
@@ -15,19 +15,38 @@ pub struct HDSignature {
     pub signature: Signature,
 }
 
-impl HDSignature {
-    pub fn produced_signing_with_input(input: HDSignatureInput) -> Self {
-        let signature = Signature::produced_by_input(&input);
+impl<ID: SignableID> HDSignature<ID> {
+    /// Signs `input` using a `Ed25519HDSigner`.
+    pub fn produced_signing_with_input(input: HDSignatureInput<ID>) -> Self {
+        Self::produced_signing_with_input_by(input, &Ed25519HDSigner)
+    }
+
+    /// Signs `input` using `signer`.
+    pub fn produced_signing_with_input_by(
+        input: HDSignatureInput<ID>,
+        signer: &impl HDSigner,
+    ) -> Self {
+        let signature = signer.sign(&input);
         Self::with_details(input, signature)
     }
 
     /// Constructs a HDSignature from an already produced `Signature`.
-    fn with_details(input: HDSignatureInput, signature: Signature) -> Self {
+    pub fn with_details(input: HDSignatureInput<ID>, signature: Signature) -> Self {
         Self { input, signature }
     }
 
-    pub fn intent_hash(&self) -> &IntentHash {
-        &self.input.intent_hash
+    /// Returns `true` if `signature` is a valid signature of `payload_id`
+    /// by the public key of the owned factor instance.
+    pub fn is_valid(&self) -> bool {
+        self.owned_factor_instance()
+            .factor_instance()
+            .public_key
+            .public_key
+            .is_valid_signature_for_hash(&self.signature, self.payload_id().hash_to_sign())
+    }
+
+    pub fn payload_id(&self) -> &ID {
+        &self.input.payload_id
     }
 
     pub fn owned_factor_instance(&self) -> &OwnedFactorInstance {
@@ -77,6 +96,18 @@ mod tests {
         assert_ne!(Sut::sample(), Sut::sample_other());
     }
 
+    #[test]
+    fn samples_are_valid() {
+        assert!(Sut::sample().is_valid());
+        assert!(Sut::sample_other().is_valid());
+    }
+
+    #[test]
+    fn invalid_if_signature_of_other_input() {
+        let sut = Sut::with_details(HDSignatureInput::sample(), Sut::sample_other().signature);
+        assert!(!sut.is_valid());
+    }
+
     #[test]
     fn hash_of_samples() {
         assert_eq!(
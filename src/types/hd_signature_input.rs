@@ -2,26 +2,26 @@ use crate::prelude::*;
 
 /// The input used to produce a `HDSignature`. Can be used to see two signatures
 /// has the same signer, which would be a bug.
-#[derive(Clone, PartialEq, Eq, Hash, derive_more::Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, derive_more::Debug)]
 #[debug(
-    "HDSignatureInput {{ intent_hash: {:#?}, owned_factor_instance: {:#?} }}",
-    intent_hash,
+    "HDSignatureInput {{ payload_id: {:#?}, owned_factor_instance: {:#?} }}",
+    payload_id,
     owned_factor_instance
 )]
-pub struct HDSignatureInput {
-    /// Hash which was signed.
-    pub intent_hash: IntentHash,
+pub struct HDSignatureInput<ID: SignableID = IntentHash> {
+    /// Identifier of the payload whose hash was signed.
+    pub payload_id: ID,
 
     /// The account or identity address of the entity which signed the hash,
     /// with expected public key and with derivation path to derive PrivateKey
     /// with.
     pub owned_factor_instance: OwnedFactorInstance,
 }
-impl HDSignatureInput {
+impl<ID: SignableID> HDSignatureInput<ID> {
     /// Constructs a new `HDSignatureInput`.
-    pub fn new(intent_hash: IntentHash, owned_factor_instance: OwnedFactorInstance) -> Self {
+    pub fn new(payload_id: ID, owned_factor_instance: OwnedFactorInstance) -> Self {
         Self {
-            intent_hash,
+            payload_id,
             owned_factor_instance,
         }
     }
@@ -0,0 +1,60 @@
+use crate::prelude::*;
+
+/// Something which can produce a `Signature` for a `HDSignatureInput`, i.e.
+/// sign the hash of the payload with the private key of the owned factor instance.
+pub trait HDSigner {
+    fn sign<ID: SignableID>(&self, input: &HDSignatureInput<ID>) -> Signature;
+}
+
+/// A `HDSigner` producing Ed25519 signatures, using the private key derived
+/// at the derivation path of the factor instance, by its factor source.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ed25519HDSigner;
+
+impl HDSigner for Ed25519HDSigner {
+    fn sign<ID: SignableID>(&self, input: &HDSignatureInput<ID>) -> Signature {
+        let factor_instance = input.owned_factor_instance.factor_instance();
+        PrivateKey::derive(
+            &factor_instance.factor_source_id,
+            &factor_instance.derivation_path(),
+        )
+        .sign(input.payload_id.hash_to_sign())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = Ed25519HDSigner;
+
+    #[test]
+    fn signature_is_valid() {
+        let input = HDSignatureInput::sample();
+        let signature = Sut::default().sign(&input);
+        assert!(input
+            .owned_factor_instance
+            .factor_instance()
+            .public_key
+            .public_key
+            .is_valid_signature_for_hash(&signature, input.payload_id.hash()));
+    }
+
+    #[test]
+    fn signature_of_other_hash_is_invalid() {
+        let input = HDSignatureInput::sample();
+        let signature = Sut::default().sign(&input);
+        assert!(!input
+            .owned_factor_instance
+            .factor_instance()
+            .public_key
+            .public_key
+            .is_valid_signature_for_hash(&signature, IntentHash::sample_other().hash()));
+    }
+
+    #[test]
+    fn deterministic() {
+        let input = HDSignatureInput::sample();
+        assert_eq!(Sut::default().sign(&input), Sut::default().sign(&input));
+    }
+}
@@ -3,23 +3,23 @@ use crate::prelude::*;
 /// A list of entities which would fail in a transaction if we would
 /// skip signing with a certain factor source
 #[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
-pub struct InvalidTransactionIfSkipped {
-    /// The intent hash of the transaction which would be invalid if we skipped
+pub struct InvalidTransactionIfSkipped<ID: SignableID = IntentHash> {
+    /// The identifier of the payload which would be invalid if we skipped
     /// signing with a certain factor source
-    pub intent_hash: IntentHash,
+    pub payload_id: ID,
 
     /// The entities in the transaction which would fail auth.
     entities_which_would_fail_auth: Vec<AddressOfAccountOrPersona>,
 }
 
-impl InvalidTransactionIfSkipped {
+impl<ID: SignableID> InvalidTransactionIfSkipped<ID> {
     /// Constructs a new `InvalidTransactionIfSkipped` from an IndexSet of
     /// entities which would fail auth..
     ///
     /// # Panics
     /// Panics if `entities_which_would_fail_auth` is empty.
     pub fn new(
-        intent_hash: IntentHash,
+        payload_id: ID,
         entities_which_would_fail_auth: impl IntoIterator<Item = AddressOfAccountOrPersona>,
     ) -> Self {
         let entities_which_would_fail_auth =
@@ -37,7 +37,7 @@ impl InvalidTransactionIfSkipped {
         );
 
         Self {
-            intent_hash,
+            payload_id,
             entities_which_would_fail_auth: entities_which_would_fail_auth
                 .into_iter()
                 .collect_vec(),
@@ -0,0 +1,260 @@
+use crate::prelude::*;
+
+/// A rule broken by a `MatrixOfFactors`, explaining why it is rejected.
+#[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
+pub enum MatrixOfFactorsViolation {
+    /// The threshold is higher than the weights of all threshold factors
+    /// together, so it can never be met.
+    ThresholdExceedsThresholdFactors,
+
+    /// The threshold is 0 while there are threshold factors, which would
+    /// make them pointless since no signature is needed.
+    ZeroThresholdWithThresholdFactors,
+
+    /// There are neither threshold factors nor override factors, so nobody
+    /// could ever sign.
+    NoFactors,
+
+    /// A threshold factor has weight 0, so it never counts towards the
+    /// threshold.
+    ZeroWeightThresholdFactor(FactorSourceIDFromHash),
+
+    /// The same factor source is used more than once in the same list.
+    DuplicateFactor(FactorSourceIDFromHash),
+
+    /// The same factor source is used in both the threshold and the
+    /// override list.
+    FactorInBothLists(FactorSourceIDFromHash),
+
+    /// A SecurityQuestions factor source must not meet the threshold on
+    /// its own, i.e. its weight must be lower than the threshold.
+    SecurityQuestionsOnlyThresholdFactor,
+
+    /// A Device factor source cannot be the only override factor.
+    DeviceOnlyOverrideFactor,
+}
+
+impl<F: FactorSourceReferencing> MatrixOfFactors<F> {
+    /// All rules this matrix breaks, empty if it is valid.
+    pub fn violations(&self) -> Vec<MatrixOfFactorsViolation> {
+        use MatrixOfFactorsViolation::*;
+        let mut violations = Vec::new();
+
+        let total_weight = self
+            .threshold_factors
+            .iter()
+            .map(|w| w.weight as u32)
+            .sum::<u32>();
+        if total_weight < self.threshold as u32 {
+            violations.push(ThresholdExceedsThresholdFactors);
+        }
+        if self.threshold == 0 && !self.threshold_factors.is_empty() {
+            violations.push(ZeroThresholdWithThresholdFactors);
+        }
+        if self.threshold_factors.is_empty() && self.override_factors.is_empty() {
+            violations.push(NoFactors);
+        }
+
+        violations.extend(
+            self.weighted_threshold_factors()
+                .into_iter()
+                .filter(|(_, weight)| *weight == 0)
+                .map(|(f, _)| ZeroWeightThresholdFactor(f.factor_source_id())),
+        );
+
+        let threshold_ids = self
+            .threshold_factors
+            .iter()
+            .map(|w| w.factor.factor_source_id())
+            .collect_vec();
+        let override_ids = self
+            .override_factors
+            .iter()
+            .map(|f| f.factor_source_id())
+            .collect_vec();
+
+        for list in [&threshold_ids, &override_ids] {
+            violations.extend(list.iter().duplicates().map(|id| DuplicateFactor(*id)));
+        }
+        violations.extend(
+            threshold_ids
+                .iter()
+                .unique()
+                .filter(|id| override_ids.contains(id))
+                .map(|id| FactorInBothLists(*id)),
+        );
+
+        if self.threshold_factors.iter().any(|w| {
+            w.factor.factor_source_id().kind == FactorSourceKind::SecurityQuestions
+                && w.weight >= self.threshold
+        }) {
+            violations.push(SecurityQuestionsOnlyThresholdFactor);
+        }
+        if let [only] = override_ids.as_slice() {
+            if only.kind == FactorSourceKind::Device {
+                violations.push(DeviceOnlyOverrideFactor);
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use MatrixOfFactorsViolation::*;
+
+    type Sut = MatrixOfFactorSources;
+
+    fn violations(result: Result<Sut>) -> Vec<MatrixOfFactorsViolation> {
+        match result {
+            Err(CommonError::InvalidMatrixOfFactors(violations)) => violations,
+            _ => panic!("Expected invalid matrix"),
+        }
+    }
+
+    #[test]
+    fn valid() {
+        let sut = Sut::new(
+            [HDFactorSource::ledger(), HDFactorSource::arculus()],
+            1,
+            [HDFactorSource::yubikey()],
+        )
+        .unwrap();
+        assert!(sut.violations().is_empty());
+    }
+
+    #[test]
+    fn threshold_exceeds_factors() {
+        assert_eq!(
+            violations(Sut::new([HDFactorSource::ledger()], 2, [])),
+            vec![ThresholdExceedsThresholdFactors]
+        );
+    }
+
+    #[test]
+    fn threshold_exceeds_weights() {
+        assert!(Sut::new_weighted([(HDFactorSource::ledger(), 2)], 2, []).is_ok());
+        assert_eq!(
+            violations(Sut::new_weighted([(HDFactorSource::ledger(), 2)], 3, [])),
+            vec![ThresholdExceedsThresholdFactors]
+        );
+    }
+
+    #[test]
+    fn zero_threshold_without_override_factors() {
+        assert_eq!(violations(Sut::new([], 0, [])), vec![NoFactors]);
+    }
+
+    #[test]
+    fn zero_threshold_with_threshold_factors() {
+        assert_eq!(
+            violations(Sut::new(
+                [HDFactorSource::ledger()],
+                0,
+                [HDFactorSource::arculus()]
+            )),
+            vec![ZeroThresholdWithThresholdFactors]
+        );
+    }
+
+    #[test]
+    fn zero_weight() {
+        let ledger = HDFactorSource::ledger();
+        assert_eq!(
+            violations(Sut::new_weighted(
+                [(ledger.clone(), 0), (HDFactorSource::arculus(), 1)],
+                1,
+                []
+            )),
+            vec![ZeroWeightThresholdFactor(ledger.factor_source_id())]
+        );
+    }
+
+    #[test]
+    fn duplicate_and_in_both_lists() {
+        let ledger = HDFactorSource::ledger();
+        assert_eq!(
+            violations(Sut::new(
+                [ledger.clone(), ledger.clone()],
+                1,
+                [ledger.clone(), HDFactorSource::arculus()]
+            )),
+            vec![
+                DuplicateFactor(ledger.factor_source_id()),
+                FactorInBothLists(ledger.factor_source_id())
+            ]
+        );
+    }
+
+    #[test]
+    fn security_questions_only_threshold_factor() {
+        assert_eq!(
+            violations(Sut::new(
+                [HDFactorSource::security_question()],
+                1,
+                [HDFactorSource::ledger()]
+            )),
+            vec![SecurityQuestionsOnlyThresholdFactor]
+        );
+    }
+
+    #[test]
+    fn security_questions_meeting_weighted_threshold_alone() {
+        assert_eq!(
+            violations(Sut::new_weighted(
+                [
+                    (HDFactorSource::security_question(), 2),
+                    (HDFactorSource::ledger(), 1),
+                    (HDFactorSource::arculus(), 1)
+                ],
+                2,
+                []
+            )),
+            vec![SecurityQuestionsOnlyThresholdFactor]
+        );
+        assert!(Sut::new_weighted(
+            [
+                (HDFactorSource::security_question(), 1),
+                (HDFactorSource::ledger(), 1)
+            ],
+            2,
+            []
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn device_only_override_factor() {
+        assert_eq!(
+            violations(Sut::new(
+                [HDFactorSource::ledger()],
+                1,
+                [HDFactorSource::device()]
+            )),
+            vec![DeviceOnlyOverrideFactor]
+        );
+    }
+
+    #[test]
+    fn all_violations_are_reported() {
+        let ledger = HDFactorSource::ledger();
+        assert_eq!(
+            violations(Sut::new(
+                [
+                    HDFactorSource::security_question(),
+                    ledger.clone(),
+                    ledger.clone()
+                ],
+                1,
+                [HDFactorSource::device()]
+            )),
+            vec![
+                DuplicateFactor(ledger.factor_source_id()),
+                SecurityQuestionsOnlyThresholdFactor,
+                DeviceOnlyOverrideFactor
+            ]
+        );
+    }
+}
@@ -1,16 +1,38 @@
+mod access_rule;
+mod batch_signing_policy;
+mod factor_selection_policy;
+mod factor_source_outcome;
+mod factor_source_referencing;
 mod factor_sources_of_kind;
+mod friction_order_policy;
 mod hd_signature;
 mod hd_signature_input;
+mod hd_signer;
 mod invalid_transaction_if_skipped;
+mod matrix_of_factors_violation;
 mod new_methods_on_sargon_types;
 mod owned_types;
+mod retry_policy;
+mod role_kind;
 mod sargon_types;
 mod sign_with_factor_source_or_sources_outcome;
+mod weighted_factor;
 
-pub(crate) use factor_sources_of_kind::*;
+pub use access_rule::*;
+pub use batch_signing_policy::*;
+pub use factor_selection_policy::*;
+pub use factor_source_outcome::*;
+pub use factor_source_referencing::*;
+pub use factor_sources_of_kind::*;
+pub use friction_order_policy::*;
 pub use hd_signature::*;
 pub use hd_signature_input::*;
+pub use hd_signer::*;
 pub use invalid_transaction_if_skipped::*;
+pub use matrix_of_factors_violation::*;
 pub use owned_types::*;
+pub use retry_policy::*;
+pub use role_kind::*;
 pub use sargon_types::*;
 pub use sign_with_factor_source_or_sources_outcome::*;
+pub use weighted_factor::*;
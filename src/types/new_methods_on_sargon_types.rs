@@ -14,9 +14,64 @@ impl AccountOrPersona {
             Self::PersonaEntity(p) => p.security_state.clone(),
         }
     }
+
+    /// The same entity but using its registered factor instances for
+    /// `key_kind`.
+    ///
+    /// Returns `Err(CommonError::UnknownFactorInstance)` if the entity has no
+    /// factor instance for `key_kind` registered for any of its factor sources.
+    pub fn with_key_kind(&self, key_kind: CAP26KeyKind) -> Result<Self> {
+        match self {
+            Self::AccountEntity(a) => {
+                let mut a = a.clone();
+                a.security_state = a.security_state.with_key_kind(key_kind)?;
+                Ok(Self::AccountEntity(a))
+            }
+            Self::PersonaEntity(p) => {
+                let mut p = p.clone();
+                p.security_state = p.security_state.with_key_kind(key_kind)?;
+                Ok(Self::PersonaEntity(p))
+            }
+        }
+    }
 }
 
 impl Profile {
+    /// Replaces the entity in this profile with the same address as `entity`.
+    pub fn update_entity(&mut self, entity: impl Into<AccountOrPersona>) -> Result<()> {
+        match entity.into() {
+            AccountOrPersona::AccountEntity(account) => {
+                let existing = self
+                    .accounts
+                    .get_mut(&account.entity_address())
+                    .ok_or(CommonError::UnknownAccount)?;
+                *existing = account;
+            }
+            AccountOrPersona::PersonaEntity(persona) => {
+                let existing = self
+                    .personas
+                    .get_mut(&persona.entity_address())
+                    .ok_or(CommonError::UnknownPersona)?;
+                *existing = persona;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn entity_by_address(
+        &self,
+        address: &AddressOfAccountOrPersona,
+    ) -> Result<AccountOrPersona> {
+        match address {
+            AddressOfAccountOrPersona::Account(a) => {
+                self.account_by_address(a.clone()).map(Into::into)
+            }
+            AddressOfAccountOrPersona::Identity(i) => {
+                self.persona_by_address(i.clone()).map(Into::into)
+            }
+        }
+    }
+
     pub fn persona_by_address(&self, address: IdentityAddress) -> Result<Persona> {
         self.personas
             .get(&address)
@@ -46,4 +101,56 @@ mod tests {
         let persona = AccountOrPersona::from(Persona::sample());
         assert_eq!(persona.address().to_string(), "ident_Alice")
     }
+
+    #[test]
+    fn with_key_kind_rola() {
+        let account = AccountOrPersona::from(Account::a6());
+        let rola = account.with_key_kind(CAP26KeyKind::Rola).unwrap();
+        assert_eq!(rola.address(), account.address());
+        let instances = rola.security_state().all_factor_instances();
+        assert_eq!(
+            instances.len(),
+            account.security_state().all_factor_instances().len()
+        );
+        assert!(instances
+            .iter()
+            .all(|f| f.derivation_path().key_kind == CAP26KeyKind::Rola));
+    }
+
+    #[test]
+    fn with_key_kind_rola_uses_registered_instances() {
+        let account = Account::a0();
+        let registered = HierarchicalDeterministicFactorInstance::mainnet_tx_account(
+            HDPathComponent::non_hardened(42),
+            FactorSourceIDFromHash::fs0(),
+        )
+        .with_key_kind(CAP26KeyKind::Rola);
+        let account = AccountOrPersona::from(
+            account.with_security_state(
+                account
+                    .security_state()
+                    .with_rola_factor_instances([registered.clone()]),
+            ),
+        );
+        assert_eq!(
+            account
+                .with_key_kind(CAP26KeyKind::Rola)
+                .unwrap()
+                .security_state()
+                .all_factor_instances(),
+            IndexSet::just(registered)
+        );
+    }
+
+    #[test]
+    fn with_key_kind_rola_without_registered_instance_is_err() {
+        let account = Account::a6();
+        let account = AccountOrPersona::from(
+            account.with_security_state(account.security_state().with_rola_factor_instances([])),
+        );
+        assert_eq!(
+            account.with_key_kind(CAP26KeyKind::Rola),
+            Err(CommonError::UnknownFactorInstance)
+        );
+    }
 }
@@ -0,0 +1,155 @@
+use crate::prelude::*;
+
+/// The answer of the user when asked if a failing factor source should be
+/// retried or skipped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryOrSkip {
+    /// Try to use the factor source(s) again, e.g. after the user has
+    /// reconnected the Ledger device.
+    Retry,
+
+    /// Skip the factor source(s).
+    Skip,
+}
+
+/// A hook used to ask the user whether to retry or skip factor sources which
+/// failed, e.g. because a Ledger device timed out or a card read failed.
+#[async_trait::async_trait]
+pub trait RetryOrSkipHook: Send + Sync {
+    /// Called with the ids of the factor sources which failed with `error`
+    /// on attempt number `attempt`, starting at 1.
+    async fn retry_or_skip(
+        &self,
+        factor_source_ids: &IndexSet<FactorSourceIDFromHash>,
+        error: &CommonError,
+        attempt: usize,
+    ) -> RetryOrSkip;
+}
+
+/// What a client should do after using factor sources failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AfterFailure {
+    Retry,
+    Skip,
+    Fail,
+}
+
+/// Controls how many times the collectors try to use factor sources of a
+/// kind which failed, before giving up on them. By default no factor source
+/// is retried.
+///
+/// If a `RetryOrSkipHook` is set, the user is asked whether to retry or
+/// skip as long as attempts remain, otherwise failing factor sources are
+/// retried until the maximum number of attempts has been reached.
+#[derive(Clone, Default)]
+pub struct RetryPolicy {
+    max_attempts: IndexMap<FactorSourceKind, usize>,
+    hook: Option<Arc<dyn RetryOrSkipHook>>,
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use factor sources of `kind` at most `max_attempts` times, including
+    /// the first attempt.
+    pub fn with_max_attempts(mut self, kind: FactorSourceKind, max_attempts: usize) -> Self {
+        self.max_attempts.insert(kind, max_attempts);
+        self
+    }
+
+    /// Ask `hook` whether to retry or skip failing factor sources.
+    pub fn with_hook(mut self, hook: Arc<dyn RetryOrSkipHook>) -> Self {
+        self.hook = Some(hook);
+        self
+    }
+
+    /// The maximum number of times factor sources of `kind` are used,
+    /// including the first attempt, which is `1` unless set.
+    pub fn max_attempts(&self, kind: FactorSourceKind) -> usize {
+        self.max_attempts.get(&kind).copied().unwrap_or(1)
+    }
+
+    pub(crate) async fn after_failure(
+        &self,
+        kind: FactorSourceKind,
+        factor_source_ids: &IndexSet<FactorSourceIDFromHash>,
+        error: &CommonError,
+        attempt: usize,
+    ) -> AfterFailure {
+        if attempt >= self.max_attempts(kind) {
+            return AfterFailure::Fail;
+        }
+        let Some(hook) = &self.hook else {
+            return AfterFailure::Retry;
+        };
+        match hook.retry_or_skip(factor_source_ids, error, attempt).await {
+            RetryOrSkip::Retry => AfterFailure::Retry,
+            RetryOrSkip::Skip => AfterFailure::Skip,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = RetryPolicy;
+
+    struct AlwaysSkip;
+
+    #[async_trait::async_trait]
+    impl RetryOrSkipHook for AlwaysSkip {
+        async fn retry_or_skip(
+            &self,
+            _factor_source_ids: &IndexSet<FactorSourceIDFromHash>,
+            _error: &CommonError,
+            _attempt: usize,
+        ) -> RetryOrSkip {
+            RetryOrSkip::Skip
+        }
+    }
+
+    async fn after_first_failure(sut: &Sut, kind: FactorSourceKind) -> AfterFailure {
+        sut.after_failure(
+            kind,
+            &IndexSet::just(FactorSourceIDFromHash::fs1()),
+            &CommonError::Failure,
+            1,
+        )
+        .await
+    }
+
+    #[actix_rt::test]
+    async fn default_does_not_retry() {
+        assert_eq!(
+            after_first_failure(&Sut::default(), FactorSourceKind::Ledger).await,
+            AfterFailure::Fail
+        );
+    }
+
+    #[actix_rt::test]
+    async fn retries_without_hook() {
+        let sut = Sut::new().with_max_attempts(FactorSourceKind::Ledger, 2);
+        assert_eq!(
+            after_first_failure(&sut, FactorSourceKind::Ledger).await,
+            AfterFailure::Retry
+        );
+        assert_eq!(
+            after_first_failure(&sut, FactorSourceKind::Arculus).await,
+            AfterFailure::Fail
+        );
+    }
+
+    #[actix_rt::test]
+    async fn hook_can_skip() {
+        let sut = Sut::new()
+            .with_max_attempts(FactorSourceKind::Ledger, 2)
+            .with_hook(Arc::new(AlwaysSkip));
+        assert_eq!(
+            after_first_failure(&sut, FactorSourceKind::Ledger).await,
+            AfterFailure::Skip
+        );
+    }
+}
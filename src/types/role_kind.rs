@@ -0,0 +1,16 @@
+/// The role of a security structure a transaction must be signed with,
+/// e.g. changing the security structure of an entity requires its recovery
+/// role, whereas most transactions require its primary role.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, std::hash::Hash)]
+pub enum RoleKind {
+    /// The role used for everyday transactions, such as withdrawing funds.
+    #[default]
+    Primary,
+
+    /// The role used to initiate a recovery, e.g. if the factors of the
+    /// primary role have been lost.
+    Recovery,
+
+    /// The role used to confirm a recovery.
+    Confirmation,
+}
@@ -40,7 +40,17 @@ impl<T: From<Uuid>> IDStepper<T> {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, std::hash::Hash, derive_more::Display, derive_more::Debug)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    std::hash::Hash,
+    derive_more::Display,
+    derive_more::Debug,
+)]
 #[display("{kind}:{id}")]
 #[debug("{}", self.to_string())]
 pub struct FactorSourceIDFromHash {
@@ -149,7 +159,19 @@ impl<T: std::hash::Hash + Eq> Just<T> for IndexSet<T> {
 }
 
 #[repr(u32)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, std::hash::Hash, PartialOrd, Ord, strum::Display)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    std::hash::Hash,
+    PartialOrd,
+    Ord,
+    strum::Display,
+)]
 pub enum FactorSourceKind {
     Ledger,
     Arculus,
@@ -171,7 +193,17 @@ impl HasSampleValues for FactorSourceKind {
 pub type HDPathValue = u32;
 
 #[derive(
-    Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, derive_more::Display, derive_more::Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    derive_more::Display,
+    derive_more::Debug,
 )]
 #[display("{value}")]
 #[debug("{value}")]
@@ -206,7 +238,17 @@ impl HasSampleValues for HDPathComponent {
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Eq, Hash, derive_more::Display, derive_more::Debug)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    derive_more::Display,
+    derive_more::Debug,
+)]
 pub enum CAP26KeyKind {
     #[display("tx")]
     #[debug("tx")]
@@ -223,7 +265,17 @@ impl CAP26KeyKind {
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Eq, Hash, derive_more::Display, derive_more::Debug)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    derive_more::Display,
+    derive_more::Debug,
+)]
 pub enum NetworkID {
     #[display("Mainnet")]
     #[debug("0")]
@@ -241,7 +293,17 @@ impl NetworkID {
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Eq, Hash, derive_more::Display, derive_more::Debug)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    derive_more::Display,
+    derive_more::Debug,
+)]
 pub enum CAP26EntityKind {
     #[display("Account")]
     #[debug("A")]
@@ -258,7 +320,9 @@ impl CAP26EntityKind {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, derive_more::Display, derive_more::Debug)]
+#[derive(
+    Serialize, Deserialize, Clone, PartialEq, Eq, Hash, derive_more::Display, derive_more::Debug,
+)]
 #[display("{}/{}/{}/{}", network_id, entity_kind, key_kind, index)]
 #[debug("{:?}/{:?}/{:?}/{:?}", network_id, entity_kind, key_kind, index)]
 pub struct DerivationPath {
@@ -314,21 +378,72 @@ impl DerivationPath {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// An Ed25519 private key, derived at some derivation path using the mnemonic
+/// of a factor source.
+#[derive(Clone)]
+pub struct PrivateKey(ed25519_dalek::SigningKey);
+
+impl PrivateKey {
+    /// Derives the private key at `derivation_path` using the factor source
+    /// with id `factor_source_id`, the id of the factor source emulates the
+    /// mnemonic, i.e. the same factor source and derivation path always
+    /// results in the same key pair.
+    pub fn derive(
+        factor_source_id: &FactorSourceIDFromHash,
+        derivation_path: &DerivationPath,
+    ) -> Self {
+        use sha2::{Digest, Sha256};
+        let seed: [u8; 32] =
+            Sha256::digest([factor_source_id.to_bytes(), derivation_path.to_bytes()].concat())
+                .into();
+        Self(ed25519_dalek::SigningKey::from_bytes(&seed))
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_bytes(self.0.verifying_key().to_bytes())
+    }
+
+    /// Signs `hash` with this private key.
+    pub fn sign(&self, hash: &Hash) -> Signature {
+        use ed25519_dalek::Signer;
+        Signature::from_bytes(self.0.sign(&hash.to_bytes()).to_bytes())
+    }
+}
+
+/// An Ed25519 public key.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, derive_more::Debug)]
+#[debug("{}", self.to_hex())]
 pub struct PublicKey {
-    /// this emulates the mnemonic
-    factor_source_id: FactorSourceIDFromHash,
+    bytes: [u8; 32],
 }
+
 impl PublicKey {
-    pub fn new(factor_source_id: FactorSourceIDFromHash) -> Self {
-        Self { factor_source_id }
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self { bytes }
     }
+
     pub fn to_bytes(&self) -> Vec<u8> {
-        self.factor_source_id.to_bytes()
+        self.bytes.to_vec()
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.bytes)
+    }
+
+    /// Returns `true` if `signature` is a valid Ed25519 signature of `hash`
+    /// produced by the private key of this public key.
+    pub fn is_valid_signature_for_hash(&self, signature: &Signature, hash: &Hash) -> bool {
+        let Ok(verifying_key) = ed25519_dalek::VerifyingKey::from_bytes(&self.bytes) else {
+            return false;
+        };
+        let signature = ed25519_dalek::Signature::from_bytes(&signature.bytes);
+        verifying_key
+            .verify_strict(&hash.to_bytes(), &signature)
+            .is_ok()
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct HierarchicalDeterministicPublicKey {
     /// The expected public key of the private key derived at `derivationPath`
     pub public_key: PublicKey,
@@ -344,11 +459,14 @@ impl HierarchicalDeterministicPublicKey {
         }
     }
 
-    pub fn mocked_with(
+    /// Derives the public key at `derivation_path` using the factor source
+    /// with id `factor_source_id`.
+    pub fn derive(
         derivation_path: DerivationPath,
         factor_source_id: &FactorSourceIDFromHash,
     ) -> Self {
-        Self::new(derivation_path, PublicKey::new(*factor_source_id))
+        let public_key = PrivateKey::derive(factor_source_id, &derivation_path).public_key();
+        Self::new(derivation_path, public_key)
     }
 
     pub fn to_bytes(&self) -> Vec<u8> {
@@ -356,7 +474,7 @@ impl HierarchicalDeterministicPublicKey {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, std::hash::Hash, derive_more::Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, std::hash::Hash, derive_more::Debug)]
 #[debug("{}", self.debug_str())]
 pub struct HierarchicalDeterministicFactorInstance {
     pub factor_source_id: FactorSourceIDFromHash,
@@ -386,16 +504,26 @@ impl HierarchicalDeterministicFactorInstance {
         self.public_key.derivation_path.clone()
     }
 
-    pub fn mocked_with(
+    /// Derives the factor instance at `derivation_path` using the factor source
+    /// with id `factor_source_id`.
+    pub fn derive(
         derivation_path: DerivationPath,
         factor_source_id: &FactorSourceIDFromHash,
     ) -> Self {
         Self::new(
-            HierarchicalDeterministicPublicKey::mocked_with(derivation_path, factor_source_id),
+            HierarchicalDeterministicPublicKey::derive(derivation_path, factor_source_id),
             *factor_source_id,
         )
     }
 
+    /// The factor instance of the same factor source at the same index, but
+    /// for `key_kind`, e.g. the ROLA key of a transaction signing instance.
+    pub fn with_key_kind(&self, key_kind: CAP26KeyKind) -> Self {
+        let mut derivation_path = self.derivation_path();
+        derivation_path.key_kind = key_kind;
+        Self::derive(derivation_path, &self.factor_source_id)
+    }
+
     pub fn tx_on_network(
         entity_kind: CAP26EntityKind,
         network_id: NetworkID,
@@ -404,9 +532,7 @@ impl HierarchicalDeterministicFactorInstance {
     ) -> Self {
         let derivation_path =
             DerivationPath::new(network_id, entity_kind, CAP26KeyKind::T9n, index);
-        let public_key = PublicKey::new(factor_source_id);
-        let hd_public_key = HierarchicalDeterministicPublicKey::new(derivation_path, public_key);
-        Self::new(hd_public_key, factor_source_id)
+        Self::derive(derivation_path, &factor_source_id)
     }
 
     pub fn mainnet_tx(
@@ -441,7 +567,7 @@ impl HasSampleValues for HierarchicalDeterministicFactorInstance {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, std::hash::Hash)]
 pub struct Hash {
     id: Uuid,
 }
@@ -455,6 +581,15 @@ impl Hash {
     pub fn generate() -> Self {
         Self::new(Uuid::new_v4())
     }
+    /// Deterministically hashes `bytes`, i.e. the same bytes always result
+    /// in the same hash.
+    pub fn digest(bytes: impl AsRef<[u8]>) -> Self {
+        use sha2::{Digest, Sha256};
+        let digest: [u8; 32] = Sha256::digest(bytes).into();
+        let mut id = [0u8; 16];
+        id.copy_from_slice(&digest[..16]);
+        Self::new(Uuid::from_bytes(id))
+    }
     pub fn sample_third() -> Self {
         Self::new(Uuid::from_bytes([0x11; 16]))
     }
@@ -470,30 +605,164 @@ impl HasSampleValues for Hash {
 
 #[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
 pub enum EntitySecurityState {
-    Unsecured(HierarchicalDeterministicFactorInstance),
-    Securified(MatrixOfFactorInstances),
+    Unsecured {
+        transaction_signing: HierarchicalDeterministicFactorInstance,
+        /// The registered ROLA factor instance, if any.
+        rola_factor_instances: Vec<HierarchicalDeterministicFactorInstance>,
+    },
+    Securified {
+        structure: SecurityStructureOfFactorInstances,
+        /// The registered ROLA factor instances, at most one per factor source.
+        rola_factor_instances: Vec<HierarchicalDeterministicFactorInstance>,
+    },
 }
 impl EntitySecurityState {
+    /// Unsecurified, controlled by `transaction_signing`, without ROLA
+    /// factor instances.
+    pub fn unsecured(transaction_signing: HierarchicalDeterministicFactorInstance) -> Self {
+        Self::Unsecured {
+            transaction_signing,
+            rola_factor_instances: Vec::new(),
+        }
+    }
+
+    /// Securified with `structure`, without ROLA factor instances.
+    pub fn securified(structure: SecurityStructureOfFactorInstances) -> Self {
+        Self::Securified {
+            structure,
+            rola_factor_instances: Vec::new(),
+        }
+    }
+
     pub fn all_factor_instances(&self) -> IndexSet<HierarchicalDeterministicFactorInstance> {
         match self {
-            Self::Securified(matrix) => {
+            Self::Securified { structure, .. } => {
                 let mut set = IndexSet::new();
-                set.extend(matrix.threshold_factors.clone());
-                set.extend(matrix.override_factors.clone());
+                for role in [
+                    &structure.primary_role,
+                    &structure.recovery_role,
+                    &structure.confirmation_role,
+                ] {
+                    set.extend(role.factors());
+                }
                 set
             }
-            Self::Unsecured(fi) => IndexSet::from_iter([fi.clone()]),
+            Self::Unsecured {
+                transaction_signing,
+                ..
+            } => IndexSet::from_iter([transaction_signing.clone()]),
         }
     }
+
+    pub fn rola_factor_instances(&self) -> &[HierarchicalDeterministicFactorInstance] {
+        match self {
+            Self::Unsecured {
+                rola_factor_instances,
+                ..
+            }
+            | Self::Securified {
+                rola_factor_instances,
+                ..
+            } => rola_factor_instances,
+        }
+    }
+
+    /// The same security state with `rola_factor_instances` registered.
+    pub fn with_rola_factor_instances(
+        mut self,
+        instances: impl IntoIterator<Item = HierarchicalDeterministicFactorInstance>,
+    ) -> Self {
+        match &mut self {
+            Self::Unsecured {
+                rola_factor_instances,
+                ..
+            }
+            | Self::Securified {
+                rola_factor_instances,
+                ..
+            } => *rola_factor_instances = instances.into_iter().unique().collect(),
+        }
+        self
+    }
+
+    /// The same security state with ROLA factor instances derived at the
+    /// same index as its transaction signing factor instances registered.
+    pub(crate) fn deriving_rola_factor_instances(self) -> Self {
+        let rola = self
+            .all_factor_instances()
+            .into_iter()
+            .map(|f| f.with_key_kind(CAP26KeyKind::Rola))
+            .collect_vec();
+        self.with_rola_factor_instances(rola)
+    }
+
+    /// The same security state but with every factor instance replaced by
+    /// the registered one of the same factor source for `key_kind`, e.g. the
+    /// ROLA keys of an entity.
+    ///
+    /// Returns `Err(CommonError::UnknownFactorInstance)` if no factor
+    /// instance for `key_kind` is registered for any of the factor sources.
+    pub fn with_key_kind(&self, key_kind: CAP26KeyKind) -> Result<Self> {
+        if key_kind == CAP26KeyKind::T9n {
+            return Ok(self.clone());
+        }
+        let registered = |f: &HierarchicalDeterministicFactorInstance| {
+            self.rola_factor_instances()
+                .iter()
+                .find(|r| {
+                    r.factor_source_id == f.factor_source_id
+                        && r.derivation_path().key_kind == key_kind
+                })
+                .cloned()
+                .ok_or(CommonError::UnknownFactorInstance)
+        };
+        let rola_factor_instances = self.rola_factor_instances().to_vec();
+        match self {
+            Self::Securified { structure, .. } => {
+                let structure = SecurityStructure {
+                    primary_role: structure.primary_role.try_map(&registered)?,
+                    recovery_role: structure.recovery_role.try_map(&registered)?,
+                    confirmation_role: structure.confirmation_role.try_map(&registered)?,
+                };
+                Ok(Self::Securified {
+                    structure,
+                    rola_factor_instances,
+                })
+            }
+            Self::Unsecured {
+                transaction_signing,
+                ..
+            } => Ok(Self::Unsecured {
+                transaction_signing: registered(transaction_signing)?,
+                rola_factor_instances,
+            }),
+        }
+    }
+}
+
+impl From<SecurityStructureOfFactorInstances> for EntitySecurityState {
+    fn from(value: SecurityStructureOfFactorInstances) -> Self {
+        Self::securified(value)
+    }
 }
 
+/// Uses `value` for all roles.
+impl From<AccessRule> for EntitySecurityState {
+    fn from(value: AccessRule) -> Self {
+        Self::securified(SecurityStructure::with_same_rule_for_all_roles(value))
+    }
+}
+
+/// Uses `value` for all roles.
 impl From<MatrixOfFactorInstances> for EntitySecurityState {
     fn from(value: MatrixOfFactorInstances) -> Self {
-        Self::Securified(value)
+        Self::securified(SecurityStructure::with_same_rule_for_all_roles(value))
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash, derive_more::Display)]
+#[derive(
+    Serialize, Deserialize, Clone, Debug, PartialEq, Eq, std::hash::Hash, derive_more::Display,
+)]
 #[display("{name}")]
 pub struct AbstractAddress<T: EntityKindSpecifier> {
     phantom: PhantomData<T>,
@@ -525,10 +794,10 @@ impl<T: EntityKindSpecifier> HasSampleValues for AbstractAddress<T> {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, std::hash::Hash)]
 pub struct AccountAddressTag;
 
-#[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, std::hash::Hash)]
 pub struct IdentityAddressTag;
 
 pub trait EntityKindSpecifier {
@@ -554,7 +823,7 @@ impl<T: EntityKindSpecifier> EntityKindSpecifier for AbstractAddress<T> {
 pub type AccountAddress = AbstractAddress<AccountAddressTag>;
 pub type IdentityAddress = AbstractAddress<IdentityAddressTag>;
 
-#[derive(Clone, PartialEq, Eq, std::hash::Hash, derive_more::Display)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, std::hash::Hash, derive_more::Display)]
 pub enum AddressOfAccountOrPersona {
     #[display("acco_{_0}")]
     Account(AccountAddress),
@@ -587,6 +856,8 @@ pub trait IsEntity: Into<AccountOrPersona> + Clone {
     fn new(name: impl AsRef<str>, security_state: impl Into<EntitySecurityState>) -> Self;
 
     fn entity_address(&self) -> Self::Address;
+    /// This entity with its security state replaced by `security_state`.
+    fn with_security_state(&self, security_state: impl Into<EntitySecurityState>) -> Self;
     fn kind() -> CAP26EntityKind {
         Self::Address::entity_kind()
     }
@@ -608,7 +879,10 @@ pub trait IsEntity: Into<AccountOrPersona> + Clone {
         name: impl AsRef<str>,
         make_matrix: fn(HDPathComponent) -> MatrixOfFactorInstances,
     ) -> Self {
-        Self::new(name, make_matrix(index))
+        Self::new(
+            name,
+            EntitySecurityState::from(make_matrix(index)).deriving_rola_factor_instances(),
+        )
     }
 
     fn unsecurified_mainnet(
@@ -618,11 +892,12 @@ pub trait IsEntity: Into<AccountOrPersona> + Clone {
     ) -> Self {
         Self::new(
             name,
-            EntitySecurityState::Unsecured(HierarchicalDeterministicFactorInstance::mainnet_tx(
+            EntitySecurityState::unsecured(HierarchicalDeterministicFactorInstance::mainnet_tx(
                 Self::kind(),
                 HDPathComponent::non_hardened(index),
                 factor_source_id,
-            )),
+            ))
+            .deriving_rola_factor_instances(),
         )
     }
 }
@@ -635,6 +910,12 @@ pub struct AbstractEntity<A: Clone + Into<AddressOfAccountOrPersona> + EntityKin
 }
 pub type Account = AbstractEntity<AccountAddress>;
 impl IsEntity for Account {
+    fn with_security_state(&self, security_state: impl Into<EntitySecurityState>) -> Self {
+        Self {
+            address: self.address.clone(),
+            security_state: security_state.into(),
+        }
+    }
     fn new(name: impl AsRef<str>, security_state: impl Into<EntitySecurityState>) -> Self {
         Self {
             address: AccountAddress::from(name.as_ref().to_owned()),
@@ -676,6 +957,12 @@ impl IsEntity for Account {
 
 pub type Persona = AbstractEntity<IdentityAddress>;
 impl IsEntity for Persona {
+    fn with_security_state(&self, security_state: impl Into<EntitySecurityState>) -> Self {
+        Self {
+            address: self.address.clone(),
+            security_state: security_state.into(),
+        }
+    }
     fn new(name: impl AsRef<str>, security_state: impl Into<EntitySecurityState>) -> Self {
         Self {
             address: IdentityAddress::from(name.as_ref().to_owned()),
@@ -801,7 +1088,10 @@ impl<T: Clone + Into<AddressOfAccountOrPersona> + EntityKindSpecifier + From<Str
         name: impl AsRef<str>,
         make_matrix: fn(u32) -> MatrixOfFactorInstances,
     ) -> Self {
-        Self::new(name, make_matrix(index))
+        Self::new(
+            name,
+            EntitySecurityState::from(make_matrix(index)).deriving_rola_factor_instances(),
+        )
     }
 
     pub fn unsecurified_mainnet(
@@ -811,18 +1101,19 @@ impl<T: Clone + Into<AddressOfAccountOrPersona> + EntityKindSpecifier + From<Str
     ) -> Self {
         Self::new(
             name,
-            EntitySecurityState::Unsecured(HierarchicalDeterministicFactorInstance::mainnet_tx(
+            EntitySecurityState::unsecured(HierarchicalDeterministicFactorInstance::mainnet_tx(
                 Self::entity_kind(),
                 HDPathComponent::non_hardened(index),
                 factor_source_id,
-            )),
+            ))
+            .deriving_rola_factor_instances(),
         )
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
 pub struct MatrixOfFactors<F> {
-    pub threshold_factors: Vec<F>,
+    pub threshold_factors: Vec<WeightedFactor<F>>,
     pub threshold: u8,
     pub override_factors: Vec<F>,
 }
@@ -831,49 +1122,90 @@ impl<F> MatrixOfFactors<F>
 where
     F: std::hash::Hash + std::cmp::Eq + Clone,
 {
-    /// # Panics
-    /// Panics if threshold > threshold_factor.len()
+    /// The same matrix with every factor mapped by `map`.
+    pub fn map<G>(&self, map: impl Fn(&F) -> G) -> MatrixOfFactors<G> {
+        MatrixOfFactors {
+            threshold_factors: self
+                .threshold_factors
+                .iter()
+                .map(|w| w.map(&map))
+                .collect_vec(),
+            threshold: self.threshold,
+            override_factors: self.override_factors.iter().map(&map).collect_vec(),
+        }
+    }
+
+    /// The threshold factors together with their weights.
+    pub fn weighted_threshold_factors(&self) -> Vec<(F, u8)> {
+        self.threshold_factors
+            .iter()
+            .map(|w| (w.factor.clone(), w.weight))
+            .collect_vec()
+    }
+
+    /// The threshold factors followed by the override factors.
+    pub fn all_factors(&self) -> impl Iterator<Item = &F> {
+        self.threshold_factors
+            .iter()
+            .map(|w| &w.factor)
+            .chain(self.override_factors.iter())
+    }
+}
+
+impl<F: FactorSourceReferencing> MatrixOfFactors<F> {
+    /// A matrix where each threshold factor has weight 1, i.e. `threshold`
+    /// many of them must sign.
     ///
-    /// Panics if the same factor is present in both lists
+    /// Returns `CommonError::InvalidMatrixOfFactors` with all rules the
+    /// matrix breaks, see `MatrixOfFactors::violations`.
     pub fn new(
         threshold_factors: impl IntoIterator<Item = F>,
         threshold: u8,
         override_factors: impl IntoIterator<Item = F>,
-    ) -> Self {
-        let threshold_factors = threshold_factors.into_iter().collect_vec();
-
-        assert!(threshold_factors.len() >= threshold as usize);
-
-        let override_factors = override_factors.into_iter().collect_vec();
-
-        assert!(
-            HashSet::<F>::from_iter(threshold_factors.clone())
-                .intersection(&HashSet::<F>::from_iter(override_factors.clone()))
-                .collect_vec()
-                .is_empty(),
-            "A factor MUST NOT be present in both threshold AND override list."
-        );
+    ) -> Result<Self> {
+        Self::new_weighted(
+            threshold_factors.into_iter().map(|f| (f, 1)),
+            threshold,
+            override_factors,
+        )
+    }
 
-        Self {
-            threshold_factors,
+    /// Returns `CommonError::InvalidMatrixOfFactors` with all rules the
+    /// matrix breaks, see `MatrixOfFactors::violations`.
+    pub fn new_weighted(
+        weighted_threshold_factors: impl IntoIterator<Item = (F, u8)>,
+        threshold: u8,
+        override_factors: impl IntoIterator<Item = F>,
+    ) -> Result<Self> {
+        let matrix = Self {
+            threshold_factors: weighted_threshold_factors
+                .into_iter()
+                .map(WeightedFactor::from)
+                .collect_vec(),
             threshold,
             override_factors: override_factors.into_iter().collect_vec(),
+        };
+        let violations = matrix.violations();
+        if violations.is_empty() {
+            Ok(matrix)
+        } else {
+            Err(CommonError::InvalidMatrixOfFactors(violations))
         }
     }
 
-    pub fn override_only(factors: impl IntoIterator<Item = F>) -> Self {
+    pub fn override_only(factors: impl IntoIterator<Item = F>) -> Result<Self> {
         Self::new([], 0, factors)
     }
 
-    pub fn single_override(factor: F) -> Self {
+    pub fn single_override(factor: F) -> Result<Self> {
         Self::override_only([factor])
     }
 
-    pub fn threshold_only(factors: impl IntoIterator<Item = F>, threshold: u8) -> Self {
+    pub fn threshold_only(factors: impl IntoIterator<Item = F>, threshold: u8) -> Result<Self> {
         Self::new(factors, threshold, [])
     }
 
-    pub fn single_threshold(factor: F) -> Self {
+    pub fn single_threshold(factor: F) -> Result<Self> {
         Self::threshold_only([factor], 1)
     }
 }
@@ -881,13 +1213,54 @@ where
 pub type MatrixOfFactorInstances = MatrixOfFactors<HierarchicalDeterministicFactorInstance>;
 pub type MatrixOfFactorSources = MatrixOfFactors<HDFactorSource>;
 
+/// The roles of a securified entity, each with its own access rule, e.g.
+/// converted from a `MatrixOfFactors` of threshold and override factors.
+#[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
+pub struct SecurityStructure<F> {
+    pub primary_role: AccessRule<F>,
+    pub recovery_role: AccessRule<F>,
+    pub confirmation_role: AccessRule<F>,
+}
+
+impl<F: Clone> SecurityStructure<F> {
+    pub fn new(
+        primary_role: impl Into<AccessRule<F>>,
+        recovery_role: impl Into<AccessRule<F>>,
+        confirmation_role: impl Into<AccessRule<F>>,
+    ) -> Self {
+        Self {
+            primary_role: primary_role.into(),
+            recovery_role: recovery_role.into(),
+            confirmation_role: confirmation_role.into(),
+        }
+    }
+
+    pub fn with_same_rule_for_all_roles(rule: impl Into<AccessRule<F>>) -> Self {
+        let rule = rule.into();
+        Self::new(rule.clone(), rule.clone(), rule)
+    }
+
+    /// The access rule of `role`.
+    pub fn role(&self, role: RoleKind) -> &AccessRule<F> {
+        match role {
+            RoleKind::Primary => &self.primary_role,
+            RoleKind::Recovery => &self.recovery_role,
+            RoleKind::Confirmation => &self.confirmation_role,
+        }
+    }
+}
+
+pub type SecurityStructureOfFactorInstances =
+    SecurityStructure<HierarchicalDeterministicFactorInstance>;
+pub type SecurityStructureOfFactorSources = SecurityStructure<HDFactorSource>;
+
 /// For unsecurified entities we map single factor -> single threshold factor.
 /// Which is used by ROLA.
 impl From<HierarchicalDeterministicFactorInstance> for MatrixOfFactorInstances {
     fn from(value: HierarchicalDeterministicFactorInstance) -> Self {
         Self {
             threshold: 1,
-            threshold_factors: vec![value],
+            threshold_factors: vec![WeightedFactor::unweighted(value)],
             override_factors: Vec::new(),
         }
     }
@@ -898,7 +1271,9 @@ pub trait HasSampleValues {
     fn sample_other() -> Self;
 }
 
-#[derive(Clone, PartialEq, Eq, std::hash::Hash, Getters, derive_more::Debug)]
+#[derive(
+    Serialize, Deserialize, Clone, PartialEq, Eq, std::hash::Hash, Getters, derive_more::Debug,
+)]
 #[debug("TXID({:#?})", hash.id.to_string()[..6].to_owned())]
 pub struct IntentHash {
     hash: Hash,
@@ -1045,44 +1420,67 @@ impl Profile {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
-pub struct Signature(String);
+/// An Ed25519 signature.
+#[derive(Clone, Copy, PartialEq, Eq, std::hash::Hash, derive_more::Debug)]
+#[debug("{}", self.to_hex())]
+pub struct Signature {
+    bytes: [u8; 64],
+}
+
 impl HasSampleValues for Signature {
     fn sample() -> Self {
-        Self("deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef".to_owned())
+        Self::from_hex("deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef")
     }
     fn sample_other() -> Self {
-        Self("fadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafe".to_owned())
+        Self::from_hex("fadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafefadecafe")
     }
 }
+
 impl Signature {
-    /// Emulates the signing of `intent_hash` with `factor_instance` - in a
-    /// deterministic manner.
-    pub fn produced_by(
-        intent_hash: IntentHash,
-        factor_instance: impl Into<HierarchicalDeterministicFactorInstance>,
-    ) -> Self {
-        let factor_instance = factor_instance.into();
+    pub fn from_bytes(bytes: [u8; 64]) -> Self {
+        Self { bytes }
+    }
 
-        let intent_hash_bytes = intent_hash.hash().to_bytes();
-        let factor_instance_bytes = factor_instance.to_bytes();
-        let input_bytes = [intent_hash_bytes, factor_instance_bytes].concat();
-        let hash = sha256::digest(input_bytes);
-        Self(hash)
+    /// # Panics
+    /// Panics if `hex` is not the hex encoding of exactly 64 bytes.
+    pub fn from_hex(hex: impl AsRef<str>) -> Self {
+        let bytes: [u8; 64] = hex::decode(hex.as_ref())
+            .expect("Valid hex")
+            .try_into()
+            .expect("64 bytes");
+        Self::from_bytes(bytes)
     }
 
-    /// Emulates signing using `input`.
-    pub fn produced_by_input(input: &HDSignatureInput) -> Self {
-        Self::produced_by(
-            input.intent_hash.clone(),
-            input.owned_factor_instance.clone(),
-        )
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.to_vec()
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.bytes)
+    }
+}
+
+impl Serialize for Signature {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        let bytes: [u8; 64] = bytes
+            .try_into()
+            .map_err(|_| serde::de::Error::custom("Expected 64 bytes"))?;
+        Ok(Self::from_bytes(bytes))
     }
 }
 
 pub type Result<T, E = CommonError> = std::result::Result<T, E>;
 
-#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+#[derive(
+    thiserror::Error, Clone, Debug, PartialEq, Eq, std::hash::Hash, Serialize, Deserialize,
+)]
 pub enum CommonError {
     #[error("Unknown factor source")]
     UnknownFactorSource,
@@ -1101,4 +1499,56 @@ pub enum CommonError {
 
     #[error("Unknown persona")]
     UnknownPersona,
+
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    #[error("Signature was not requested")]
+    UnexpectedSignature,
+
+    #[error("Invalid snapshot")]
+    InvalidSnapshot,
+
+    #[error("Unsupported snapshot version")]
+    UnsupportedSnapshotVersion,
+
+    #[error("Factor source already used")]
+    FactorSourceAlreadyUsed,
+
+    #[error("Factor instance was not requested")]
+    UnexpectedFactorInstance,
+
+    #[error("Factor instance already derived")]
+    DuplicateFactorInstance,
+
+    #[error("Duplicate signature")]
+    DuplicateSignature,
+
+    #[error("Failed to authenticate")]
+    FailedToAuthenticate,
+
+    #[error("Failed to derive factor instances")]
+    FailedToDeriveFactorInstances,
+
+    #[error("Entity is already securified")]
+    EntityAlreadySecurified,
+
+    #[error("Securify transaction was not signed")]
+    SecurifyTransactionNotSigned,
+
+    /// The violations are not serialized, e.g. into petitions snapshots.
+    #[error("Invalid matrix of factors: {0:?}")]
+    InvalidMatrixOfFactors(#[serde(skip)] Vec<MatrixOfFactorsViolation>),
+
+    #[error("Unknown factor instance")]
+    UnknownFactorInstance,
+
+    #[error("Entity has no factor instances")]
+    EntityWithoutFactorInstances,
+
+    #[error("Entity has changed")]
+    EntityChanged,
+
+    #[error("No derivation index left in key space")]
+    KeySpaceExhausted,
 }
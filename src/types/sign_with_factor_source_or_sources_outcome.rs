@@ -12,6 +12,14 @@ pub enum SignWithFactorSourceOrSourcesOutcome<T> {
     Skipped {
         ids_of_skipped_factors_sources: Vec<FactorSourceIDFromHash>,
     },
+
+    /// Using the factor sources with ids failed, e.g. because a Ledger device
+    /// got disconnected, the associated `error` tells why.
+    #[debug("Failed: {:#?}", error)]
+    Failed {
+        ids_of_failed_factor_sources: Vec<FactorSourceIDFromHash>,
+        error: CommonError,
+    },
 }
 
 impl<T> SignWithFactorSourceOrSourcesOutcome<T> {
@@ -31,4 +39,14 @@ impl<T> SignWithFactorSourceOrSourcesOutcome<T> {
     pub fn skipped_factor_source(factor_source_id: FactorSourceIDFromHash) -> Self {
         Self::skipped(IndexSet::from_iter([factor_source_id]))
     }
+
+    pub fn failed(
+        ids_of_failed_factor_sources: IndexSet<FactorSourceIDFromHash>,
+        error: CommonError,
+    ) -> Self {
+        Self::Failed {
+            ids_of_failed_factor_sources: ids_of_failed_factor_sources.into_iter().collect_vec(),
+            error,
+        }
+    }
 }
@@ -0,0 +1,30 @@
+/// A threshold factor of a `MatrixOfFactors` together with its weight, the
+/// threshold is met when the weights of the signed threshold factors sum up
+/// to the threshold.
+#[derive(Clone, Debug, PartialEq, Eq, std::hash::Hash)]
+pub struct WeightedFactor<F> {
+    pub factor: F,
+    pub weight: u8,
+}
+
+impl<F> WeightedFactor<F> {
+    pub fn new(factor: F, weight: u8) -> Self {
+        Self { factor, weight }
+    }
+
+    /// A factor with weight 1, i.e. counting as one factor of the threshold.
+    pub fn unweighted(factor: F) -> Self {
+        Self::new(factor, 1)
+    }
+
+    /// The same weight with the factor mapped by `map`.
+    pub fn map<G>(&self, map: impl Fn(&F) -> G) -> WeightedFactor<G> {
+        WeightedFactor::new(map(&self.factor), self.weight)
+    }
+}
+
+impl<F> From<(F, u8)> for WeightedFactor<F> {
+    fn from((factor, weight): (F, u8)) -> Self {
+        Self::new(factor, weight)
+    }
+}
@@ -54,7 +54,11 @@ mod key_derivation_tests {
         );
         let outcome = collector.collect_keys().await;
         println!("{:#?}", outcome);
-        assert!(outcome.all_factors().is_empty())
+        assert!(outcome.all_factors().is_empty());
+        assert_eq!(
+            outcome.failed_factor_sources,
+            IndexMap::<_, _>::from_iter([(factor_source.factor_source_id(), CommonError::Failure)])
+        );
     }
 
     mod multi_key {
@@ -540,11 +544,12 @@ mod signing_tests {
             assert!(outcome.successful());
             assert!(outcome.failed_transactions().is_empty());
             assert_eq!(outcome.signatures_of_successful_transactions().len(), 10);
+            assert!(outcome.all_signatures().iter().all(|s| s.is_valid()));
             assert_eq!(
                 outcome
                     .successful_transactions()
                     .into_iter()
-                    .map(|t| t.intent_hash)
+                    .map(|t| t.payload_id)
                     .collect::<HashSet<_>>(),
                 HashSet::from_iter([
                     t0.clone().intent_hash,
@@ -555,7 +560,7 @@ mod signing_tests {
             let st0 = outcome
                 .successful_transactions()
                 .into_iter()
-                .find(|st| st.intent_hash == t0.intent_hash)
+                .find(|st| st.payload_id == t0.intent_hash)
                 .unwrap();
 
             assert_eq!(
@@ -570,7 +575,7 @@ mod signing_tests {
             let st1 = outcome
                 .successful_transactions()
                 .into_iter()
-                .find(|st| st.intent_hash == t1.intent_hash)
+                .find(|st| st.payload_id == t1.intent_hash)
                 .unwrap();
 
             assert_eq!(
@@ -585,7 +590,7 @@ mod signing_tests {
             let st2 = outcome
                 .successful_transactions()
                 .into_iter()
-                .find(|st| st.intent_hash == t2.intent_hash)
+                .find(|st| st.payload_id == t2.intent_hash)
                 .unwrap();
 
             assert_eq!(
@@ -618,9 +623,13 @@ mod signing_tests {
         }
         #[derive(Clone, Debug, PartialEq, Eq)]
         struct Expected {
-            successful_txs_signature_count: usize,
+            /// Depends on the order of factor sources of the same kind,
+            /// which is not deterministic, since already finished entities
+            /// are not signed for again.
+            successful_txs_signature_count: std::ops::RangeInclusive<usize>,
             signed_factor_source_kinds: IndexSet<FactorSourceKind>,
             expected_skipped_factor_source_count: usize,
+            expected_failed_factor_source_count: usize,
         }
         async fn multi_securified_entities_with_sim_user(vector: Vector) {
             let factor_sources = &HDFactorSource::all();
@@ -660,18 +669,27 @@ mod signing_tests {
                 outcome.skipped_factor_sources().len(),
                 vector.expected.expected_skipped_factor_source_count
             );
+            assert_eq!(
+                outcome.failed_factor_sources().len(),
+                vector.expected.expected_failed_factor_source_count
+            );
 
             assert!(outcome.successful());
             assert!(outcome.failed_transactions().is_empty());
-            assert_eq!(
-                outcome.signatures_of_successful_transactions().len(),
-                vector.expected.successful_txs_signature_count
+            let signature_count = outcome.signatures_of_successful_transactions().len();
+            assert!(
+                vector
+                    .expected
+                    .successful_txs_signature_count
+                    .contains(&signature_count),
+                "Unexpected signature count: {}",
+                signature_count
             );
             assert_eq!(
                 outcome
                     .successful_transactions()
                     .into_iter()
-                    .map(|t| t.intent_hash)
+                    .map(|t| t.payload_id)
                     .collect::<HashSet<_>>(),
                 HashSet::from_iter([
                     t0.clone().intent_hash,
@@ -702,7 +720,7 @@ mod signing_tests {
                         SimulatedFailures::with_simulated_failures([FactorSourceIDFromHash::fs1()]),
                     ),
                     expected: Expected {
-                        successful_txs_signature_count: 24,
+                        successful_txs_signature_count: 16..=20,
                         // We always end early
                         // `Device` FactorSourceKind never got used since it
                         // we are done after YubiKey.
@@ -710,7 +728,8 @@ mod signing_tests {
                             FactorSourceKind::Arculus,
                             FactorSourceKind::Yubikey,
                         ]),
-                        expected_skipped_factor_source_count: 1,
+                        expected_skipped_factor_source_count: 0,
+                        expected_failed_factor_source_count: 1,
                     },
                 })
                 .await;
@@ -749,7 +768,7 @@ mod signing_tests {
                     outcome
                         .failed_transactions()
                         .iter()
-                        .map(|t| t.intent_hash.clone())
+                        .map(|t| t.payload_id.clone())
                         .collect_vec(),
                     failing_transactions
                         .iter()
@@ -761,7 +780,7 @@ mod signing_tests {
                     outcome
                         .successful_transactions()
                         .into_iter()
-                        .map(|t| t.intent_hash)
+                        .map(|t| t.payload_id)
                         .collect_vec(),
                     vec![tx.intent_hash]
                 )
@@ -790,7 +809,7 @@ mod signing_tests {
                 multi_securified_entities_with_sim_user(Vector {
                     simulated_user: SimulatedUser::prudent_no_fail(),
                     expected: Expected {
-                        successful_txs_signature_count: 32,
+                        successful_txs_signature_count: 16..=16,
                         // We always end early
                         // `Device` FactorSourceKind never got used since it
                         // we are done after YubiKey.
@@ -800,6 +819,7 @@ mod signing_tests {
                             FactorSourceKind::Yubikey,
                         ]),
                         expected_skipped_factor_source_count: 0,
+                        expected_failed_factor_source_count: 0,
                     },
                 })
                 .await;
@@ -807,7 +827,7 @@ mod signing_tests {
                 multi_securified_entities_with_sim_user(Vector {
                     simulated_user: SimulatedUser::lazy_sign_minimum([]),
                     expected: Expected {
-                        successful_txs_signature_count: 24,
+                        successful_txs_signature_count: 16..=16,
                         // We always end early, this lazy user was able to skip
                         // Ledger.
                         signed_factor_source_kinds: IndexSet::<FactorSourceKind>::from_iter([
@@ -816,6 +836,7 @@ mod signing_tests {
                             FactorSourceKind::Device,
                         ]),
                         expected_skipped_factor_source_count: 2,
+                        expected_failed_factor_source_count: 0,
                     },
                 })
                 .await;
@@ -890,7 +911,7 @@ mod signing_tests {
                 let tx = TXToSign::new([E::e0()]);
                 let collector = SignaturesCollector::test_prudent([tx.clone()]);
                 let signature = &collector.collect_signatures().await.all_signatures()[0];
-                assert_eq!(signature.intent_hash(), &tx.intent_hash);
+                assert_eq!(signature.payload_id(), &tx.payload_id);
                 assert_eq!(signature.derivation_path().entity_kind, E::kind());
             }
 
@@ -1101,6 +1122,7 @@ mod signing_tests {
                                     )
                                 }),
                             )
+                            .unwrap()
                         },
                     )]),
                 ]);
@@ -1128,7 +1150,7 @@ mod signing_tests {
                 assert!(signatures.is_empty());
             }
 
-            async fn fail_get_skipped_e0<E: IsEntity>() {
+            async fn fail_get_failed_e0<E: IsEntity>() {
                 let failing = IndexSet::<_>::from_iter([FactorSourceIDFromHash::fs0()]);
                 let collector = SignaturesCollector::test_prudent_with_failures(
                     [TXToSign::new([E::e0()])],
@@ -1136,8 +1158,10 @@ mod signing_tests {
                 );
                 let outcome = collector.collect_signatures().await;
                 assert!(!outcome.successful());
-                let skipped = outcome.skipped_factor_sources();
-                assert_eq!(skipped, failing);
+                assert!(outcome.skipped_factor_sources().is_empty());
+                let failed = outcome.failed_factor_sources();
+                assert_eq!(failed.keys().cloned().collect::<IndexSet<_>>(), failing);
+                assert!(failed.values().all(|e| *e == CommonError::Failure));
             }
 
             async fn lazy_always_skip_user_single_tx_e1<E: IsEntity>() {
@@ -1243,8 +1267,13 @@ mod signing_tests {
                 );
                 let outcome = collector.collect_signatures().await;
                 assert!(outcome.successful());
+                assert!(outcome.skipped_factor_sources().is_empty());
                 assert_eq!(
-                    outcome.skipped_factor_sources(),
+                    outcome
+                        .failed_factor_sources()
+                        .keys()
+                        .cloned()
+                        .collect::<IndexSet<_>>(),
                     IndexSet::<_>::from_iter([FactorSourceIDFromHash::fs3()])
                 );
             }
@@ -1366,8 +1395,8 @@ mod signing_tests {
                 }
 
                 #[actix_rt::test]
-                async fn fail_get_skipped_a0() {
-                    fail_get_skipped_e0::<E>().await
+                async fn fail_get_failed_a0() {
+                    fail_get_failed_e0::<E>().await
                 }
 
                 #[actix_rt::test]
@@ -1541,8 +1570,8 @@ mod signing_tests {
                 }
 
                 #[actix_rt::test]
-                async fn fail_get_skipped_p0() {
-                    fail_get_skipped_e0::<E>().await
+                async fn fail_get_failed_p0() {
+                    fail_get_failed_e0::<E>().await
                 }
 
                 #[actix_rt::test]